@@ -0,0 +1,11 @@
+//! Check all ink stories which characters play as their dialogue for problems
+//! without starting the game.
+//!
+//! Run from the directory with the `assets` folder, or give that directory as
+//! the only argument. Exits with an error if any problems are found.
+
+use windy_city_politics::{lint::lint_stories, validate::run_check};
+
+fn main() {
+    run_check(lint_stories, "All stories are fine");
+}
@@ -0,0 +1,11 @@
+//! Check the string tables of all languages for keys which are missing from a
+//! language or from the default language, without starting the game.
+//!
+//! Run from the directory with the `assets` folder, or give that directory as
+//! the only argument. Exits with an error if any problems are found.
+
+use windy_city_politics::{locale::check_translations, validate::run_check};
+
+fn main() {
+    run_check(check_translations, "All strings are translated");
+}
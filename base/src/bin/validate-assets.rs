@@ -0,0 +1,11 @@
+//! Check all asset and resource files for problems without starting the game.
+//!
+//! Run from the directory with the `assets` and `resources` folders, or give
+//! that directory as the only argument. Exits with an error if any problems
+//! are found.
+
+use windy_city_politics::validate::{run_check, validate_assets};
+
+fn main() {
+    run_check(validate_assets, "All assets are valid");
+}
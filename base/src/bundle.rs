@@ -6,6 +6,8 @@ use amethyst::{
     shred::DispatcherBuilder,
 };
 
+use crate::systems::LocaleSystem;
+
 pub struct SpriteBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for SpriteBundle {
@@ -24,3 +26,14 @@ impl<'a, 'b> SystemBundle<'a, 'b> for SpriteBundle {
         Ok(())
     }
 }
+
+/// Translates labels in every state, including menus.
+pub struct LocaleBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for LocaleBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(LocaleSystem, "locale_system", &[]);
+
+        Ok(())
+    }
+}
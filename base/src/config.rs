@@ -1,19 +1,66 @@
 use serde::{Deserialize, Serialize};
 use serde_millis;
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
+
+use crate::locale::DEFAULT_LANGUAGE;
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
+    /// Whether the player can move diagonally, either by combining the movement axes
+    /// or with the diagonal movement bindings.
+    pub allow_diagonal_movement: bool,
+    /// Time for glyphs and the camera to slide from one tile to the next.
+    /// Movement snaps between tiles if zero.
+    #[serde(with = "serde_millis")]
+    pub tween_duration: Duration,
+    /// Controller stick values at or below this (between 0 and 1) are ignored.
+    pub stick_deadzone: f32,
+    /// Used for all actions which do not have their own repeat settings.
+    pub default_repeat: RepeatSettings,
+    /// Repeat settings for actions by name, see `Action::name`.
+    pub repeat: HashMap<String, RepeatSettings>,
+    /// Whether characters near the player show their name above their glyph.
+    pub show_name_labels: bool,
+    /// Language that the game starts in, the name of a string table in `assets/locale`.
+    pub language: String,
+}
+
+impl Config {
+    pub fn get_repeat_settings(&self, action: &str) -> &RepeatSettings {
+        self.repeat.get(action).unwrap_or(&self.default_repeat)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            allow_diagonal_movement: false,
+            tween_duration: Duration::from_millis(0),
+            stick_deadzone: 0.25,
+            default_repeat: RepeatSettings::default(),
+            repeat: HashMap::new(),
+            show_name_labels: true,
+            language: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+/// How an action repeats while its input is held down.
+pub struct RepeatSettings {
+    /// Time that the input has to be held before the action starts repeating.
     #[serde(with = "serde_millis")]
     pub min_duration_hold: Duration,
+    /// Time between repeated actions.
     #[serde(with = "serde_millis")]
     pub min_duration_repeat: Duration,
 }
 
-impl Default for Config {
+impl Default for RepeatSettings {
     fn default() -> Self {
-        Config {
+        RepeatSettings {
             min_duration_hold: Duration::from_millis(350),
             min_duration_repeat: Duration::from_millis(100),
         }
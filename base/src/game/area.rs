@@ -1,7 +1,7 @@
 use amethyst::{
     assets::PrefabData,
     derive::PrefabData,
-    ecs::prelude::{Component, DenseVecStorage, Entity, VecStorage, WriteStorage},
+    ecs::prelude::{Component, DenseVecStorage, Entity, NullStorage, VecStorage, WriteStorage},
     Error,
 };
 
@@ -12,10 +12,16 @@ use super::consts::{TILE_HEIGHT, TILE_WIDTH};
 /// Use as a resource to keep track of the currently active area entity.
 pub struct ActiveArea(pub Entity);
 
+/// Use as a resource for the path of the area map that the `ActiveArea` was loaded
+/// from (relative to the assets directory).
+pub struct ActiveAreaMap(pub String);
+
 #[derive(Clone, Debug, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
 #[serde(deny_unknown_fields)]
 pub struct Area {
+    #[serde(default)]
+    pub name: String,
     pub dimensions: [u32; 2],
 }
 
@@ -23,7 +29,7 @@ impl Component for Area {
     type Storage = DenseVecStorage<Self>;
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PrefabData)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
 #[serde(deny_unknown_fields)]
 pub struct Position {
@@ -31,6 +37,24 @@ pub struct Position {
     pub y: u32,
 }
 
+impl Position {
+    /// Whether the other position is next to this one along either axis.
+    pub fn is_adjacent_to(&self, other: &Position) -> bool {
+        let dx = (self.x as i32 - other.x as i32).abs();
+        let dy = (self.y as i32 - other.y as i32).abs();
+
+        dx + dy == 1
+    }
+
+    /// Number of tiles to the other position along the axis where it is farthest.
+    pub fn distance_to(&self, other: &Position) -> u32 {
+        let dx = (self.x as i32 - other.x as i32).abs();
+        let dy = (self.y as i32 - other.y as i32).abs();
+
+        dx.max(dy) as u32
+    }
+}
+
 impl Component for Position {
     type Storage = VecStorage<Self>;
 }
@@ -46,3 +70,31 @@ impl Component for Collision {
 pub fn get_world_coordinates(x: u32, y: u32) -> (f32, f32) {
     ((x * TILE_WIDTH) as f32, (y * TILE_HEIGHT) as f32)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_adjacent_only_along_a_single_axis() {
+        let center = Position { x: 5, y: 5 };
+
+        assert!(center.is_adjacent_to(&Position { x: 4, y: 5 }));
+        assert!(center.is_adjacent_to(&Position { x: 6, y: 5 }));
+        assert!(center.is_adjacent_to(&Position { x: 5, y: 4 }));
+        assert!(center.is_adjacent_to(&Position { x: 5, y: 6 }));
+
+        assert!(!center.is_adjacent_to(&center));
+        assert!(!center.is_adjacent_to(&Position { x: 6, y: 6 }));
+        assert!(!center.is_adjacent_to(&Position { x: 7, y: 5 }));
+    }
+
+    #[test]
+    fn distance_is_the_larger_of_the_axis_distances() {
+        let center = Position { x: 5, y: 5 };
+
+        assert_eq!(center.distance_to(&center), 0);
+        assert_eq!(center.distance_to(&Position { x: 2, y: 6 }), 3);
+        assert_eq!(center.distance_to(&Position { x: 6, y: 9 }), 4);
+    }
+}
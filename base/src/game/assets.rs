@@ -4,6 +4,12 @@ use amethyst::{
     ui::{FontAsset, FontHandle, TtfFormat},
 };
 
+use std::path::PathBuf;
+
+/// Use as a resource for the directory below the application root that all assets
+/// are loaded from.
+pub struct AssetsDir(pub PathBuf);
+
 pub struct Fonts {
     pub main: FontHandle,
 }
@@ -1,39 +1,264 @@
 use amethyst::{
-    assets::PrefabLoaderSystem, core::SystemBundle, error::Error, shred::DispatcherBuilder,
+    assets::{PrefabLoaderSystem, Processor},
+    core::SystemBundle,
+    error::Error,
+    shred::DispatcherBuilder,
 };
 
-use crate::systems::{
-    CameraMovementSystem, InputSystem, PlayerMovementSystem, UpdateCharTileTransformsSystem,
-    UpdateTransformsSystem,
+use crate::{
+    replay::Recording,
+    systems::{
+        AreaChangeSystem, AreaTriggerSystem, BarkSystem, CameraMovementSystem, CampaignHudSystem,
+        CampaignSystem, ClickToMoveSystem, DebugOverlaySystem, InputRecorderSystem, InputSystem,
+        ItemPickupSystem, ItemTransferSystem, MessageHudSystem, MinimapSystem, MouseHoverSystem,
+        NameLabelSystem, ObjectiveSystem, PlayerMovementSystem, RelationshipHudSystem,
+        RelationshipSystem, ReplaySystem, ScreenResizeSystem, TalkSystem, TurnClockSystem,
+        TweenSystem, UpdateCharTileTransformsSystem, UpdateTransformsSystem, ZoomSystem,
+    },
 };
 
-use super::{area::Area, character::CharacterPrefab};
+use super::{
+    campaign::CampaignData, character::CharacterPrefab, dialogue::StoryData, faction::FactionsData,
+    map::AreaMapPrefab, objective::ObjectivesData,
+};
 
-pub struct MovementSystemsBundle;
+pub struct MovementSystemsBundle {
+    /// Player actions are read from this recording instead of the keyboard if set.
+    pub replay: Option<Recording>,
+}
 
 impl<'a, 'b> SystemBundle<'a, 'b> for MovementSystemsBundle {
     fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
-        builder.add(InputSystem::default(), "input_parsing_system", &[]);
+        // Mouse input is not used when replaying, since all actions come from the recording
+        let input_systems: &[&str] = match self.replay {
+            Some(recording) => {
+                builder.add(ReplaySystem::new(recording), "input_parsing_system", &[]);
+                &["input_parsing_system"]
+            }
+            None => {
+                builder.add(InputSystem::default(), "input_parsing_system", &[]);
+                builder.add(
+                    ClickToMoveSystem::default(),
+                    "click_to_move_system",
+                    &["input_parsing_system"],
+                );
+                &["input_parsing_system", "click_to_move_system"]
+            }
+        };
+
         builder.add(
             PlayerMovementSystem { reader: None },
             "player_movement_system",
-            &["input_parsing_system"],
+            input_systems,
         );
+        builder.add(ScreenResizeSystem::default(), "screen_resize_system", &[]);
+        builder.add(ZoomSystem { reader: None }, "zoom_system", &[]);
         builder.add(
             CameraMovementSystem { reader: None },
             "camera_movement_system",
-            &["player_movement_system"],
+            &[
+                "player_movement_system",
+                "screen_resize_system",
+                "zoom_system",
+            ],
+        );
+        builder.add(
+            TweenSystem::default(),
+            "tween_system",
+            &["player_movement_system", "camera_movement_system"],
         );
         builder.add(
             UpdateCharTileTransformsSystem { reader: None },
             "update_char_tile_transforms_system",
-            &["player_movement_system", "camera_movement_system"],
+            &[
+                "player_movement_system",
+                "camera_movement_system",
+                "tween_system",
+            ],
         );
         builder.add(
             UpdateTransformsSystem { reader: None },
             "update_sprite_transforms_system",
+            &["player_movement_system", "tween_system"],
+        );
+        builder.add(
+            MouseHoverSystem,
+            "mouse_hover_system",
+            &["camera_movement_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct RelationshipSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for RelationshipSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            RelationshipSystem { reader: None },
+            "relationship_system",
+            &[],
+        );
+        builder.add(
+            RelationshipHudSystem,
+            "relationship_hud_system",
+            &["relationship_system", "player_movement_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct CampaignSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for CampaignSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            TurnClockSystem { reader: None },
+            "turn_clock_system",
             &["player_movement_system"],
         );
+        builder.add(
+            CampaignSystem { reader: None },
+            "campaign_system",
+            &["turn_clock_system"],
+        );
+        builder.add(
+            CampaignHudSystem,
+            "campaign_hud_system",
+            &["campaign_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct InventorySystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for InventorySystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            ItemPickupSystem { reader: None },
+            "item_pickup_system",
+            &["input_parsing_system"],
+        );
+        builder.add(
+            ItemTransferSystem { reader: None },
+            "item_transfer_system",
+            &["item_pickup_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct ObjectiveSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for ObjectiveSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            TalkSystem { reader: None },
+            "talk_system",
+            &["input_parsing_system"],
+        );
+        builder.add(
+            ObjectiveSystem { reader: None },
+            "objective_system",
+            &["talk_system", "item_transfer_system", "campaign_system"],
+        );
+        builder.add(
+            MessageHudSystem,
+            "message_hud_system",
+            &["objective_system"],
+        );
+
+        Ok(())
+    }
+}
+
+/// Records all player actions. Must be built after the `CampaignSystemsBundle`
+/// since the turn clock is recorded with each action.
+pub struct RecordingSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for RecordingSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            InputRecorderSystem::default(),
+            "input_recorder_system",
+            &["turn_clock_system"],
+        );
+
+        Ok(())
+    }
+}
+
+/// Runs the triggers of the current area. Must be built after the
+/// `ObjectiveSystemsBundle` since triggers can start talking to characters.
+pub struct ScriptSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for ScriptSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            AreaTriggerSystem::default(),
+            "area_trigger_system",
+            // Scripts snap tweens, which must happen after the tweens of this frame
+            &["player_movement_system", "talk_system", "tween_system"],
+        );
+        builder.add(
+            AreaChangeSystem::default(),
+            "area_change_system",
+            &["area_trigger_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct MinimapSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for MinimapSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            MinimapSystem::default(),
+            "minimap_system",
+            &["player_movement_system", "camera_movement_system"],
+        );
+
+        Ok(())
+    }
+}
+
+/// Shows names and barks above characters near the player. Must be built after the
+/// `ScriptSystemsBundle` since labels of characters which leave the area are removed.
+pub struct LabelSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for LabelSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            NameLabelSystem,
+            "name_label_system",
+            &["update_char_tile_transforms_system", "area_change_system"],
+        );
+        builder.add(
+            BarkSystem,
+            "bark_system",
+            &["update_char_tile_transforms_system", "area_change_system"],
+        );
+
+        Ok(())
+    }
+}
+
+pub struct DebugSystemsBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for DebugSystemsBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        builder.add(
+            DebugOverlaySystem::default(),
+            "debug_overlay_system",
+            &["player_movement_system", "camera_movement_system"],
+        );
 
         Ok(())
     }
@@ -44,8 +269,8 @@ pub struct PrefabLoaderBundle;
 impl<'a, 'b> SystemBundle<'a, 'b> for PrefabLoaderBundle {
     fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
         builder.add(
-            PrefabLoaderSystem::<Area>::default(),
-            "area_prefab_loader",
+            PrefabLoaderSystem::<AreaMapPrefab>::default(),
+            "area_map_prefab_loader",
             &[],
         );
 
@@ -55,6 +280,26 @@ impl<'a, 'b> SystemBundle<'a, 'b> for PrefabLoaderBundle {
             &[],
         );
 
+        builder.add(
+            Processor::<CampaignData>::new(),
+            "campaign_data_processor",
+            &[],
+        );
+
+        builder.add(
+            Processor::<FactionsData>::new(),
+            "factions_data_processor",
+            &[],
+        );
+
+        builder.add(
+            Processor::<ObjectivesData>::new(),
+            "objectives_data_processor",
+            &[],
+        );
+
+        builder.add(Processor::<StoryData>::new(), "story_data_processor", &[]);
+
         Ok(())
     }
 }
@@ -0,0 +1,414 @@
+use amethyst::{
+    assets::{Asset, Handle, ProcessingState},
+    ecs::prelude::VecStorage,
+    Error,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Campaign definition which is read from an asset file.
+pub struct CampaignData {
+    /// Number of player turns that make up a campaign day.
+    pub turns_per_day: u32,
+    /// Day on which the election is held and the campaign is resolved.
+    pub election_day: u32,
+    /// Fraction of the change in bloc support that fades at the end of each day.
+    pub daily_drift: f32,
+    pub districts: Vec<District>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct District {
+    pub name: String,
+    pub blocs: Vec<VoterBloc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// A group of voters in a district who care about the same issues.
+pub struct VoterBloc {
+    pub name: String,
+    pub voters: u32,
+    /// Fraction of the bloc's voters who will turn out on election day.
+    pub turnout: f32,
+    /// Fraction of the bloc that supports the player when the campaign starts.
+    pub support: f32,
+    /// How strongly the bloc reacts to the player's stance on each issue. Negative
+    /// values mean that the bloc disagrees with the player's stance.
+    #[serde(default)]
+    pub preferences: HashMap<String, f32>,
+}
+
+impl Asset for CampaignData {
+    const NAME: &'static str = "windy_city_politics::CampaignData";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+impl From<CampaignData> for Result<ProcessingState<CampaignData>, Error> {
+    fn from(data: CampaignData) -> Result<ProcessingState<CampaignData>, Error> {
+        Ok(ProcessingState::Loaded(data))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Event emitted when the player has done something that voters care about.
+pub enum CampaignEvent {
+    /// Change support in all blocs in proportion to how much they care about the issue.
+    Issue { issue: String, amount: f32 },
+    /// Change support in all blocs of a single district.
+    District { district: String, amount: f32 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElectionResult {
+    pub districts_won: usize,
+    pub districts_total: usize,
+}
+
+impl ElectionResult {
+    /// The player wins the election by carrying a majority of all districts.
+    pub fn is_won(&self) -> bool {
+        2 * self.districts_won > self.districts_total
+    }
+}
+
+/// Use as a resource to keep track of the state of the campaign.
+pub struct Campaign {
+    pub data: CampaignData,
+    /// Current support for the player in each bloc, indexed by district then bloc.
+    support: Vec<Vec<f32>>,
+    /// Current day of the campaign, starting from 0.
+    pub day: u32,
+    /// Set once the election has been held.
+    pub result: Option<ElectionResult>,
+}
+
+impl Campaign {
+    pub fn new(data: CampaignData) -> Self {
+        let support = data
+            .districts
+            .iter()
+            .map(|district| district.blocs.iter().map(|bloc| bloc.support).collect())
+            .collect();
+
+        Campaign {
+            data,
+            support,
+            day: 0,
+            result: None,
+        }
+    }
+
+    pub fn apply(&mut self, event: &CampaignEvent) {
+        for (district, support) in self.data.districts.iter().zip(self.support.iter_mut()) {
+            for (bloc, value) in district.blocs.iter().zip(support.iter_mut()) {
+                let change = match event {
+                    CampaignEvent::Issue { issue, amount } => {
+                        bloc.preferences.get(issue).cloned().unwrap_or(0.0) * amount
+                    }
+                    CampaignEvent::District {
+                        district: name,
+                        amount,
+                    } if name == &district.name => *amount,
+                    CampaignEvent::District { .. } => 0.0,
+                };
+
+                *value = clamp_support(*value + change);
+            }
+        }
+    }
+
+    /// Fraction of voters who will turn out for the player in the district.
+    pub fn district_poll(&self, index: usize) -> f32 {
+        let blocs = &self.data.districts[index].blocs;
+        let support = &self.support[index];
+
+        let (for_player, turnout) = blocs.iter().zip(support.iter()).fold(
+            (0.0, 0.0),
+            |(for_player, turnout), (bloc, value)| {
+                let voters = bloc.voters as f32 * bloc.turnout;
+                (for_player + voters * value, turnout + voters)
+            },
+        );
+
+        if turnout > 0.0 {
+            for_player / turnout
+        } else {
+            0.0
+        }
+    }
+
+    /// Names and polling numbers of all districts.
+    pub fn polls(&self) -> Vec<(&str, f32)> {
+        self.data
+            .districts
+            .iter()
+            .enumerate()
+            .map(|(i, district)| (district.name.as_str(), self.district_poll(i)))
+            .collect()
+    }
+
+    /// Number of days left until the election.
+    pub fn days_left(&self) -> u32 {
+        self.data.election_day.saturating_sub(self.day)
+    }
+
+    /// Current support for the player in each bloc, indexed by district then bloc.
+    pub fn support(&self) -> &[Vec<f32>] {
+        &self.support
+    }
+
+    /// Continue a saved campaign from the given day and support. The support must
+    /// have a value for every bloc of every district.
+    pub fn restore(&mut self, day: u32, support: Vec<Vec<f32>>) -> Result<(), String> {
+        let matches = support.len() == self.data.districts.len()
+            && support
+                .iter()
+                .zip(self.data.districts.iter())
+                .all(|(values, district)| values.len() == district.blocs.len());
+
+        if !matches {
+            return Err("saved support does not match the districts of the campaign".to_string());
+        }
+
+        self.support = support;
+        self.day = day;
+        self.result = None;
+
+        Ok(())
+    }
+
+    /// Move to the given day, letting support drift back toward where it started
+    /// for every day that passes. Holds the election if it is due.
+    pub fn advance_to_day(&mut self, day: u32) {
+        while self.day < day && self.result.is_none() {
+            self.drift();
+            self.day += 1;
+
+            if self.day >= self.data.election_day {
+                self.result = Some(self.hold_election());
+            }
+        }
+    }
+
+    fn drift(&mut self) {
+        let drift = self.data.daily_drift;
+
+        for (district, support) in self.data.districts.iter().zip(self.support.iter_mut()) {
+            for (bloc, value) in district.blocs.iter().zip(support.iter_mut()) {
+                *value -= drift * (*value - bloc.support);
+            }
+        }
+    }
+
+    fn hold_election(&self) -> ElectionResult {
+        let districts_total = self.data.districts.len();
+        let districts_won = (0..districts_total)
+            .filter(|&i| self.district_poll(i) > 0.5)
+            .count();
+
+        ElectionResult {
+            districts_won,
+            districts_total,
+        }
+    }
+}
+
+fn clamp_support(value: f32) -> f32 {
+    value.max(0.0).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_bloc(voters: u32, support: f32, preferences: &[(&str, f32)]) -> VoterBloc {
+        VoterBloc {
+            name: String::new(),
+            voters,
+            turnout: 1.0,
+            support,
+            preferences: preferences
+                .iter()
+                .map(|&(issue, value)| (issue.to_string(), value))
+                .collect(),
+        }
+    }
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!(
+            (value - expected).abs() < 1e-5,
+            "{} is not close to {}",
+            value,
+            expected
+        );
+    }
+
+    fn get_campaign(election_day: u32, districts: Vec<Vec<VoterBloc>>) -> Campaign {
+        let districts = districts
+            .into_iter()
+            .enumerate()
+            .map(|(i, blocs)| District {
+                name: format!("district_{}", i),
+                blocs,
+            })
+            .collect();
+
+        Campaign::new(CampaignData {
+            turns_per_day: 10,
+            election_day,
+            daily_drift: 0.5,
+            districts,
+        })
+    }
+
+    #[test]
+    fn district_poll_is_weighted_by_voters_who_turn_out() {
+        let mut low_turnout = get_bloc(100, 1.0, &[]);
+        low_turnout.turnout = 0.0;
+
+        let campaign = get_campaign(
+            1,
+            vec![vec![
+                get_bloc(300, 1.0, &[]),
+                get_bloc(100, 0.0, &[]),
+                low_turnout,
+            ]],
+        );
+
+        assert_close(campaign.district_poll(0), 0.75);
+    }
+
+    #[test]
+    fn issue_events_change_support_by_bloc_preference() {
+        let mut campaign = get_campaign(
+            1,
+            vec![vec![
+                get_bloc(100, 0.5, &[("transit", 1.0)]),
+                get_bloc(100, 0.5, &[("transit", -0.5)]),
+                get_bloc(100, 0.5, &[("housing", 1.0)]),
+            ]],
+        );
+
+        campaign.apply(&CampaignEvent::Issue {
+            issue: "transit".to_string(),
+            amount: 0.2,
+        });
+
+        assert_close(campaign.support[0][0], 0.7);
+        assert_close(campaign.support[0][1], 0.4);
+        assert_close(campaign.support[0][2], 0.5);
+    }
+
+    #[test]
+    fn district_events_only_change_support_in_that_district() {
+        let mut campaign = get_campaign(
+            1,
+            vec![vec![get_bloc(100, 0.5, &[])], vec![get_bloc(100, 0.5, &[])]],
+        );
+
+        campaign.apply(&CampaignEvent::District {
+            district: "district_1".to_string(),
+            amount: 0.1,
+        });
+
+        assert_close(campaign.district_poll(0), 0.5);
+        assert_close(campaign.district_poll(1), 0.6);
+    }
+
+    #[test]
+    fn support_is_clamped_to_fractions() {
+        let mut campaign = get_campaign(1, vec![vec![get_bloc(100, 0.5, &[])]]);
+
+        campaign.apply(&CampaignEvent::District {
+            district: "district_0".to_string(),
+            amount: 2.0,
+        });
+        assert_close(campaign.district_poll(0), 1.0);
+
+        campaign.apply(&CampaignEvent::District {
+            district: "district_0".to_string(),
+            amount: -3.0,
+        });
+        assert_close(campaign.district_poll(0), 0.0);
+    }
+
+    #[test]
+    fn support_drifts_back_toward_start_every_day() {
+        let mut campaign = get_campaign(10, vec![vec![get_bloc(100, 0.5, &[])]]);
+
+        campaign.apply(&CampaignEvent::District {
+            district: "district_0".to_string(),
+            amount: 0.4,
+        });
+
+        campaign.advance_to_day(1);
+        assert_close(campaign.district_poll(0), 0.7);
+
+        campaign.advance_to_day(2);
+        assert_close(campaign.district_poll(0), 0.6);
+    }
+
+    #[test]
+    fn election_is_held_on_election_day_and_won_by_majority_of_districts() {
+        let mut campaign = get_campaign(
+            2,
+            vec![
+                vec![get_bloc(100, 0.9, &[])],
+                vec![get_bloc(100, 0.9, &[])],
+                vec![get_bloc(100, 0.1, &[])],
+            ],
+        );
+
+        campaign.advance_to_day(1);
+        assert!(campaign.result.is_none());
+
+        campaign.advance_to_day(2);
+        let result = campaign.result.clone().unwrap();
+
+        assert_eq!(
+            result,
+            ElectionResult {
+                districts_won: 2,
+                districts_total: 3,
+            }
+        );
+        assert!(result.is_won());
+    }
+
+    #[test]
+    fn days_do_not_advance_after_the_election() {
+        let mut campaign = get_campaign(1, vec![vec![get_bloc(100, 0.5, &[])]]);
+
+        campaign.advance_to_day(5);
+
+        assert_eq!(campaign.day, 1);
+        assert_eq!(campaign.days_left(), 0);
+    }
+
+    #[test]
+    fn restored_support_must_match_the_districts() {
+        let mut campaign = get_campaign(
+            5,
+            vec![vec![get_bloc(100, 0.5, &[])], vec![get_bloc(100, 0.5, &[])]],
+        );
+
+        assert!(campaign.restore(3, vec![vec![0.7]]).is_err());
+        assert!(campaign
+            .restore(3, vec![vec![0.7], vec![0.2, 0.1]])
+            .is_err());
+
+        campaign.restore(3, vec![vec![0.7], vec![0.2]]).unwrap();
+
+        assert_eq!(campaign.day, 3);
+        assert_close(campaign.district_poll(0), 0.7);
+        assert_eq!(campaign.support(), &[vec![0.7], vec![0.2]][..]);
+    }
+}
@@ -1,7 +1,9 @@
 use amethyst::{
     assets::PrefabData,
     core::Named,
-    ecs::prelude::{Component, DenseVecStorage, Entity, NullStorage, ReadExpect, WriteStorage},
+    ecs::prelude::{
+        Component, DenseVecStorage, Entity, NullStorage, Read, ReadExpect, WriteStorage,
+    },
     ui::{Anchor, FontHandle, UiText, UiTransform},
     Error,
 };
@@ -11,7 +13,13 @@ use serde::{Deserialize, Serialize};
 use super::{
     area::{Collision, Position},
     assets::Fonts,
-    consts::{GLYPH_FONT_SIZE, NPC_SPRITE_LAYER, PLAYER_SPRITE_LAYER, TILE_HEIGHT, TILE_WIDTH},
+    consts::{NPC_SPRITE_LAYER, PLAYER_SPRITE_LAYER},
+    dialogue::Dialogue,
+    faction::FactionMember,
+    item::Inventory,
+    label::Barks,
+    relations::Relationships,
+    zoom::Zoom,
 };
 
 #[derive(Clone, Copy, Default, Debug, Deserialize, Serialize)]
@@ -38,14 +46,112 @@ impl Component for Glyph {
 pub struct CharacterPrefab {
     glyph: char,
     position: Option<Position>,
+    /// Id of the faction that the character is a member of. Validated against
+    /// the loaded `Factions` once loading has finished.
+    faction: Option<String>,
+    /// Items that the character starts out with.
+    inventory: Option<Inventory>,
     variant: CharacterVariant,
 }
 
+#[derive(Clone, Debug, Default)]
+/// Everything that makes up a non-player character, for creating a `CharacterPrefab`
+/// in code. Fill in what is known and leave the rest to `Default`.
+pub struct NonPlayerCharacterPrefab {
+    pub glyph: char,
+    pub name: String,
+    pub position: Position,
+    pub faction: Option<String>,
+    pub inventory: Option<Inventory>,
+    pub relationships: Option<Relationships>,
+    pub dialogue: Option<Dialogue>,
+    pub barks: Option<Vec<String>>,
+}
+
+impl CharacterPrefab {
+    /// Create a non-player character at a position.
+    pub fn non_player(character: NonPlayerCharacterPrefab) -> Self {
+        let NonPlayerCharacterPrefab {
+            glyph,
+            name,
+            position,
+            faction,
+            inventory,
+            relationships,
+            dialogue,
+            barks,
+        } = character;
+
+        CharacterPrefab {
+            glyph,
+            position: Some(position),
+            faction,
+            inventory,
+            variant: CharacterVariant::NonPlayerCharacter {
+                name,
+                relationships,
+                dialogue,
+                barks,
+            },
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        self.glyph
+    }
+
+    /// Position that the character is placed at, (0, 0) if not specified.
+    pub fn position(&self) -> Position {
+        self.position.clone().unwrap_or_default()
+    }
+
+    pub fn is_player(&self) -> bool {
+        match self.variant {
+            CharacterVariant::PlayerCharacter => true,
+            CharacterVariant::NonPlayerCharacter { .. } => false,
+        }
+    }
+
+    /// Name of non-player characters.
+    pub fn name(&self) -> Option<&str> {
+        match self.variant {
+            CharacterVariant::PlayerCharacter => None,
+            CharacterVariant::NonPlayerCharacter { ref name, .. } => Some(name),
+        }
+    }
+
+    /// Dialogue of non-player characters, if they have anything to say.
+    pub fn dialogue(&self) -> Option<&Dialogue> {
+        match self.variant {
+            CharacterVariant::PlayerCharacter => None,
+            CharacterVariant::NonPlayerCharacter { ref dialogue, .. } => dialogue.as_ref(),
+        }
+    }
+
+    pub fn faction(&self) -> Option<&str> {
+        self.faction.as_ref().map(String::as_str)
+    }
+
+    /// Get a copy of the character which is placed at the position.
+    pub fn with_position(&self, position: Position) -> Self {
+        CharacterPrefab {
+            position: Some(position),
+            ..self.clone()
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 enum CharacterVariant {
     PlayerCharacter,
-    NonPlayerCharacter { name: String },
+    NonPlayerCharacter {
+        name: String,
+        relationships: Option<Relationships>,
+        dialogue: Option<Dialogue>,
+        /// Lines of ambient chatter, see `Barks`.
+        barks: Option<Vec<String>>,
+    },
 }
 
 /// Derive and add all required Components from the prefab when loading from a `PrefabLoader`.
@@ -53,8 +159,10 @@ enum CharacterVariant {
 /// For all characters:
 ///  * `Collision`
 ///  * `Glyph`
+///  * `Inventory`      empty if not specified
 ///  * `Position`       defaults to (0, 0) if not specified
-///  * `UiText`         for rendering the character as the given glyph
+///  * `FactionMember`  only if a faction is specified
+///  * `UiText`         for rendering the character as the given glyph at the current `Zoom`
 ///  * `UiTransform`    coordinates are not set, that's up to the rendering system
 ///
 /// For `PlayerCharacter` variant:
@@ -62,6 +170,9 @@ enum CharacterVariant {
 ///
 /// For `NonPlayerCharacter` variant:
 ///  * `Named`          with the given name
+///  * `Relationships`  neutral toward everyone if not specified
+///  * `Dialogue`       only if a dialogue is specified
+///  * `Barks`          only if any barks are specified
 ///
 /// # Notes
 ///  * Requires the `Fonts` resource to exist.
@@ -73,11 +184,17 @@ impl<'a> PrefabData<'a> for CharacterPrefab {
         WriteStorage<'a, Position>,
         WriteStorage<'a, Collision>,
         WriteStorage<'a, Glyph>,
+        WriteStorage<'a, FactionMember>,
+        WriteStorage<'a, Inventory>,
         WriteStorage<'a, PlayerCharacter>,
         WriteStorage<'a, Named>,
+        WriteStorage<'a, Relationships>,
+        WriteStorage<'a, Dialogue>,
+        WriteStorage<'a, Barks>,
         WriteStorage<'a, UiText>,
         WriteStorage<'a, UiTransform>,
         ReadExpect<'a, Fonts>,
+        Read<'a, Zoom>,
     );
 
     type Result = ();
@@ -93,25 +210,49 @@ impl<'a> PrefabData<'a> for CharacterPrefab {
             positions,
             collisions,
             glyphs,
+            faction_members,
+            inventories,
             player_characters,
             names,
+            relationships,
+            dialogues,
+            barks,
             ui_texts,
             ui_transforms,
             fonts,
+            zoom,
         ) = data;
 
-        let position = self.position.clone().unwrap_or(Position { x: 0, y: 0 });
-        positions.insert(entity, position)?;
+        positions.insert(entity, self.position())?;
 
         collisions.insert(entity, Collision)?;
         glyphs.insert(entity, Glyph(self.glyph))?;
+        inventories.insert(entity, self.inventory.clone().unwrap_or_default())?;
+
+        if let Some(ref faction) = self.faction {
+            faction_members.insert(entity, FactionMember(faction.clone()))?;
+        }
 
         match self.variant {
             CharacterVariant::PlayerCharacter => {
                 player_characters.insert(entity, PlayerCharacter)?;
             }
-            CharacterVariant::NonPlayerCharacter { ref name } => {
+            CharacterVariant::NonPlayerCharacter {
+                ref name,
+                relationships: ref initial_relationships,
+                ref dialogue,
+                barks: ref lines,
+            } => {
                 names.insert(entity, Named::new(name.clone()))?;
+                relationships.insert(entity, initial_relationships.clone().unwrap_or_default())?;
+
+                if let Some(dialogue) = dialogue {
+                    dialogues.insert(entity, dialogue.clone())?;
+                }
+
+                if let Some(lines) = lines.as_ref().filter(|lines| !lines.is_empty()) {
+                    barks.insert(entity, Barks::new(lines.clone()))?;
+                }
             }
         }
 
@@ -120,31 +261,67 @@ impl<'a> PrefabData<'a> for CharacterPrefab {
             CharacterVariant::NonPlayerCharacter { .. } => NPC_SPRITE_LAYER,
         };
 
-        ui_texts.insert(entity, get_base_ui_text(self.glyph, fonts.main.clone()))?;
-        ui_transforms.insert(entity, get_base_ui_transform(zlayer))?;
+        ui_texts.insert(
+            entity,
+            get_base_ui_text(self.glyph, fonts.main.clone(), zoom),
+        )?;
+        ui_transforms.insert(entity, get_base_ui_transform("character", zlayer, zoom))?;
 
         Ok(())
     }
 }
 
-fn get_base_ui_text(glyph: char, font: FontHandle) -> UiText {
+/// Text of a glyph at the font size of the current zoom level. Glyphs which exist
+/// when the zoom changes are rescaled by the `ZoomSystem`.
+pub(super) fn get_base_ui_text(glyph: char, font: FontHandle, zoom: &Zoom) -> UiText {
     UiText::new(
         font,
         glyph.to_string(),
         [1.0, 1.0, 1.0, 1.0],
-        GLYPH_FONT_SIZE,
+        zoom.glyph_font_size(),
     )
 }
 
-fn get_base_ui_transform(zlayer: f32) -> UiTransform {
+/// Transform of a glyph which is the size of a tile at the current zoom level.
+pub(super) fn get_base_ui_transform(id: &str, zlayer: f32, zoom: &Zoom) -> UiTransform {
+    let (tile_width, tile_height) = zoom.tile_size();
+
     UiTransform::new(
-        "character".to_string(),
+        id.to_string(),
         Anchor::BottomLeft, // Relative to the lower left corner  of the screen
         Anchor::Middle,
         0.0,
         0.0,
         zlayer,
-        TILE_WIDTH as f32,
-        TILE_HEIGHT as f32,
+        tile_width,
+        tile_height,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::consts::{TILE_HEIGHT, TILE_WIDTH};
+
+    #[test]
+    fn glyphs_spawned_after_zooming_are_scaled() {
+        let mut zoom = Zoom::default();
+        zoom.zoom_in();
+
+        let transform = get_base_ui_transform("character", NPC_SPRITE_LAYER, &zoom);
+
+        assert_eq!(transform.width, TILE_WIDTH as f32 * 2.0);
+        assert_eq!(transform.height, TILE_HEIGHT as f32 * 2.0);
+    }
+
+    #[test]
+    fn glyphs_spawned_without_zoom_are_tile_sized() {
+        let transform = get_base_ui_transform("item", NPC_SPRITE_LAYER, &Zoom::default());
+
+        assert_eq!(
+            (transform.width, transform.height),
+            (TILE_WIDTH as f32, TILE_HEIGHT as f32)
+        );
+    }
+}
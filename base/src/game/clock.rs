@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, Default)]
+/// Use as a resource to count the number of turns that the player has taken.
+///
+/// Every step that the player character takes advances the clock by a single turn.
+pub struct TurnClock {
+    pub turn: u32,
+}
@@ -0,0 +1,555 @@
+use amethyst::{
+    assets::{Prefab, PrefabLoader, RonFormat},
+    ecs::{Entity, Join},
+    input::{is_key_down, Bindings, InputHandler, StringBindings, VirtualKeyCode},
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, World},
+    shrev::EventChannel,
+    ui::{Anchor, Stretch, UiImage, UiText, UiTransform},
+    winit::{Event, WindowEvent},
+};
+
+use std::path::Path;
+
+use crate::{
+    systems::{
+        movement::{update_transforms::UpdateTransformsEvent, SnapTweensEvent},
+        script::AreaChangeEvent,
+    },
+    validate::{list_files, read_file},
+};
+
+use super::{
+    area::{ActiveArea, Area, Collision, Position},
+    assets::{AssetsDir, Fonts},
+    character::{CharacterPrefab, PlayerCharacter},
+    consts::{CONSOLE_LAYER, CONSOLE_OUTPUT_LINES, HUD_FONT_SIZE},
+    item::{Inventory, Item},
+    message::MessageLog,
+};
+
+const CONSOLE_BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.85];
+const CONSOLE_TEXT_COLOR: [f32; 4] = [0.388, 0.887, 0.678, 1.0];
+
+const CONSOLE_HELP: &str =
+    "teleport <x> <y> | spawn <prefab> | area <name> <x> <y> | give money <amount> | give document <title> | give favor <name>";
+
+#[derive(Clone, Debug, PartialEq)]
+/// A developer command entered in the console.
+pub enum ConsoleCommand {
+    /// Move the player character to a tile of the active area.
+    Teleport { x: u32, y: u32 },
+    /// Add the characters of `prefab/<name>.ron` to the world. Prefabs with a
+    /// player character are refused, since there is one already.
+    Spawn(String),
+    /// Go to the area map `area/<name>` or `district/<name>` and place the player
+    /// character at a tile of it.
+    Area { name: String, position: Position },
+    /// Add an item to the inventory of the player character.
+    Give(Item),
+}
+
+/// Drop-down console for developer commands, shown on top of the game.
+///
+/// Input bindings are disabled while the console is open so that typing does not
+/// trigger any actions once the game resumes. The output of all commands is also
+/// added to the `MessageLog`.
+#[derive(Default)]
+pub struct Console {
+    input: String,
+    output: Vec<String>,
+    /// Output lines followed by the input line.
+    lines: Vec<Entity>,
+    background: Option<Entity>,
+    bindings: Option<Bindings<StringBindings>>,
+}
+
+impl SimpleState for Console {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        let bindings = std::mem::replace(
+            &mut world
+                .write_resource::<InputHandler<StringBindings>>()
+                .bindings,
+            Bindings::new(),
+        );
+        self.bindings = Some(bindings);
+
+        self.output.push(CONSOLE_HELP.to_string());
+        self.create_entities(world);
+        self.update_text(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        if let Some(bindings) = self.bindings.take() {
+            world
+                .write_resource::<InputHandler<StringBindings>>()
+                .bindings = bindings;
+        }
+
+        let entities = self
+            .lines
+            .drain(..)
+            .chain(self.background.take())
+            .collect::<Vec<_>>();
+
+        world
+            .delete_entities(&entities)
+            .expect("could not delete console entities");
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        if let StateEvent::Window(event) = event {
+            if [VirtualKeyCode::Escape, VirtualKeyCode::Grave]
+                .iter()
+                .any(|&key| is_key_down(&event, key))
+            {
+                return Trans::Pop;
+            }
+
+            if is_key_down(&event, VirtualKeyCode::Back) {
+                self.input.pop();
+            } else if is_key_down(&event, VirtualKeyCode::Return) {
+                let line = std::mem::replace(&mut self.input, String::new());
+                self.run(world, &line);
+            } else if let Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(c),
+                ..
+            } = event
+            {
+                if !c.is_control() && c != '`' {
+                    self.input.push(c);
+                }
+            }
+
+            self.update_text(world);
+        }
+
+        Trans::None
+    }
+}
+
+impl Console {
+    fn create_entities(&mut self, world: &mut World) {
+        let font = world.read_resource::<Fonts>().main.clone();
+        let line_height = HUD_FONT_SIZE + 4.0;
+        let num_lines = CONSOLE_OUTPUT_LINES + 1;
+
+        let mut background_transform = UiTransform::new(
+            "console_background".to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            0.0,
+            0.0,
+            CONSOLE_LAYER,
+            20.0,
+            line_height * num_lines as f32 + 10.0,
+        );
+        background_transform.stretch = Stretch::X { x_margin: 0.0 };
+
+        self.background = Some(
+            world
+                .create_entity()
+                .with(background_transform)
+                .with(UiImage::SolidColor(CONSOLE_BACKGROUND_COLOR))
+                .build(),
+        );
+
+        for i in 0..num_lines {
+            let mut ui_text = UiText::new(
+                font.clone(),
+                String::new(),
+                CONSOLE_TEXT_COLOR,
+                HUD_FONT_SIZE,
+            );
+            ui_text.align = Anchor::MiddleLeft;
+
+            let mut transform = UiTransform::new(
+                format!("console_line_{}", i),
+                Anchor::TopLeft,
+                Anchor::TopLeft,
+                10.0,
+                -5.0 - line_height * i as f32,
+                CONSOLE_LAYER + 0.1,
+                20.0,
+                line_height,
+            );
+            transform.stretch = Stretch::X { x_margin: 10.0 };
+
+            self.lines
+                .push(world.create_entity().with(ui_text).with(transform).build());
+        }
+    }
+
+    fn update_text(&self, world: &mut World) {
+        let mut ui_texts = world.write_storage::<UiText>();
+
+        let output = self
+            .output
+            .iter()
+            .skip(self.output.len().saturating_sub(CONSOLE_OUTPUT_LINES))
+            .cloned()
+            .chain(std::iter::repeat(String::new()))
+            .take(CONSOLE_OUTPUT_LINES);
+        let prompt = std::iter::once(format!("> {}_", self.input));
+
+        for (entity, text) in self.lines.iter().zip(output.chain(prompt)) {
+            if let Some(ui_text) = ui_texts.get_mut(*entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    fn run(&mut self, world: &mut World, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+
+        self.output.push(format!("> {}", line));
+
+        let result = parse_command(line).and_then(|command| execute_command(world, command));
+
+        let message = match result {
+            Ok(message) => message,
+            Err(message) => format!("Error: {}", message),
+        };
+
+        world.write_resource::<MessageLog>().push(message.clone());
+        self.output.push(message);
+    }
+}
+
+/// Parse a line of console input into a command.
+pub fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let mut words = line.split_whitespace();
+
+    let command = words.next().ok_or_else(|| "no command given".to_string())?;
+    let args = words.collect::<Vec<_>>();
+
+    match (command, args.as_slice()) {
+        ("teleport", [x, y]) => Ok(ConsoleCommand::Teleport {
+            x: parse_number(x)?,
+            y: parse_number(y)?,
+        }),
+        ("teleport", _) => Err("usage: teleport <x> <y>".to_string()),
+        ("spawn", [name]) if is_asset_name(name) => Ok(ConsoleCommand::Spawn(name.to_string())),
+        ("spawn", _) => Err("usage: spawn <prefab>".to_string()),
+        ("area", [name, x, y]) if is_asset_name(name) => Ok(ConsoleCommand::Area {
+            name: name.to_string(),
+            position: Position {
+                x: parse_number(x)?,
+                y: parse_number(y)?,
+            },
+        }),
+        ("area", _) => Err("usage: area <name> <x> <y>".to_string()),
+        ("give", ["money", amount]) => Ok(ConsoleCommand::Give(Item::Money(parse_number(amount)?))),
+        ("give", ["document", _, ..]) => Ok(ConsoleCommand::Give(Item::Document {
+            title: args[1..].join(" "),
+        })),
+        ("give", ["favor", _, ..]) => Ok(ConsoleCommand::Give(Item::Favor {
+            from: args[1..].join(" "),
+        })),
+        ("give", _) => Err(
+            "usage: give money <amount> | give document <title> | give favor <name>".to_string(),
+        ),
+        (command, _) => Err(format!("unknown command '{}'", command)),
+    }
+}
+
+fn parse_number(value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a positive number", value))
+}
+
+/// Prefabs and area maps are referred to by file name only, to stay within their
+/// directory.
+fn is_asset_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Run a command and get a message about what it did.
+fn execute_command(world: &mut World, command: ConsoleCommand) -> Result<String, String> {
+    match command {
+        ConsoleCommand::Teleport { x, y } => teleport_player(world, Position { x, y }),
+        ConsoleCommand::Spawn(name) => {
+            let assets = world.read_resource::<AssetsDir>().0.clone();
+            let path = find_asset(&assets, &[("prefab", &["ron"])], &name)?;
+
+            let mut problems = Vec::new();
+            let (_, prefab) =
+                read_file::<Prefab<CharacterPrefab>>(&assets.join(&path), &mut problems)
+                    .ok_or_else(|| {
+                        problems
+                            .first()
+                            .map(ToString::to_string)
+                            .unwrap_or_default()
+                    })?;
+
+            check_no_player_character(&prefab, &path)?;
+
+            let handle = world.exec(|loader: PrefabLoader<'_, CharacterPrefab>| {
+                loader.load(path.as_str(), RonFormat, ())
+            });
+            world.create_entity().with(handle).build();
+
+            Ok(format!("Spawning characters from {}", path))
+        }
+        ConsoleCommand::Area { name, position } => {
+            let assets = world.read_resource::<AssetsDir>().0.clone();
+            let map = find_asset(&assets, AREA_DIRECTORIES, &name)?;
+            let message = format!("Going to {} at ({}, {})", map, position.x, position.y);
+
+            world
+                .write_resource::<EventChannel<AreaChangeEvent>>()
+                .single_write(AreaChangeEvent { map, position });
+
+            Ok(message)
+        }
+        ConsoleCommand::Give(item) => {
+            let characters = world.read_storage::<PlayerCharacter>();
+            let mut inventories = world.write_storage::<Inventory>();
+
+            let inventory = (&characters, &mut inventories)
+                .join()
+                .map(|(_, inventory)| inventory)
+                .next()
+                .ok_or_else(|| "the player character has no inventory".to_string())?;
+
+            let message = format!("Received {}", item);
+            inventory.add(item);
+
+            Ok(message)
+        }
+    }
+}
+
+/// Spawning a second `PlayerCharacter` would leave it to chance which of the two
+/// is controlled, so prefabs with one are refused.
+fn check_no_player_character(prefab: &Prefab<CharacterPrefab>, path: &str) -> Result<(), String> {
+    if prefab
+        .entities()
+        .filter_map(|entity| entity.data())
+        .any(CharacterPrefab::is_player)
+    {
+        return Err(format!(
+            "{} has a player character, which can not be spawned twice",
+            path
+        ));
+    }
+
+    Ok(())
+}
+
+/// Directories of area maps and the extensions of the maps in them.
+const AREA_DIRECTORIES: &[(&str, &[&str])] = &[("area", &["ron", "json"]), ("district", &["ron"])];
+
+/// Find the asset with the given file name (without extension) in one of the
+/// directories below `assets` and get its path relative to `assets`, or an error
+/// which lists the names to choose from.
+fn find_asset(
+    assets: &Path,
+    directories: &[(&str, &[&str])],
+    name: &str,
+) -> Result<String, String> {
+    let mut names = Vec::new();
+
+    for (directory, extensions) in directories {
+        let mut problems = Vec::new();
+        let paths = list_files(&assets.join(directory), extensions, &mut problems);
+
+        if let Some(problem) = problems.first() {
+            return Err(format!("{}: {}", problem.path.display(), problem.message));
+        }
+
+        for path in paths {
+            if let (Some(stem), Some(file_name)) = (
+                path.file_stem().and_then(|stem| stem.to_str()),
+                path.file_name().and_then(|file_name| file_name.to_str()),
+            ) {
+                if stem == name {
+                    return Ok(format!("{}/{}", directory, file_name));
+                }
+
+                names.push(stem.to_string());
+            }
+        }
+    }
+
+    Err(format!(
+        "unknown name '{}', choose one of: {}",
+        name,
+        names.join(", ")
+    ))
+}
+
+fn teleport_player(world: &mut World, target: Position) -> Result<String, String> {
+    {
+        let ActiveArea(entity) = *world.read_resource::<ActiveArea>();
+        let areas = world.read_storage::<Area>();
+        let [nx, ny] = areas
+            .get(entity)
+            .ok_or_else(|| "no active area".to_string())?
+            .dimensions;
+
+        if target.x >= nx || target.y >= ny {
+            return Err(format!(
+                "({}, {}) is outside of the area, which is {} by {} tiles",
+                target.x, target.y, nx, ny
+            ));
+        }
+
+        let positions = world.read_storage::<Position>();
+        let collisions = world.read_storage::<Collision>();
+        let characters = world.read_storage::<PlayerCharacter>();
+
+        if (&positions, &collisions, !&characters)
+            .join()
+            .any(|(position, _, _)| *position == target)
+        {
+            return Err(format!("({}, {}) is occupied", target.x, target.y));
+        }
+    }
+
+    {
+        let characters = world.read_storage::<PlayerCharacter>();
+        let mut positions = world.write_storage::<Position>();
+
+        let position = (&characters, &mut positions)
+            .join()
+            .map(|(_, position)| position)
+            .next()
+            .ok_or_else(|| "there is no player character".to_string())?;
+
+        *position = target.clone();
+    }
+
+    world
+        .write_resource::<EventChannel<UpdateTransformsEvent>>()
+        .single_write(UpdateTransformsEvent);
+    world
+        .write_resource::<EventChannel<SnapTweensEvent>>()
+        .single_write(SnapTweensEvent);
+
+    Ok(format!("Teleported to ({}, {})", target.x, target.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn teleport_takes_two_coordinates() {
+        assert_eq!(
+            parse_command("teleport 3 4"),
+            Ok(ConsoleCommand::Teleport { x: 3, y: 4 })
+        );
+        assert!(parse_command("teleport 3").is_err());
+        assert!(parse_command("teleport -1 4").is_err());
+    }
+
+    #[test]
+    fn spawn_only_accepts_prefab_file_names() {
+        assert_eq!(
+            parse_command("spawn character"),
+            Ok(ConsoleCommand::Spawn("character".to_string()))
+        );
+        assert!(parse_command("spawn ../config").is_err());
+        assert!(parse_command("spawn").is_err());
+    }
+
+    #[test]
+    fn area_takes_a_map_name_and_coordinates() {
+        assert_eq!(
+            parse_command("area cityhall 4 1"),
+            Ok(ConsoleCommand::Area {
+                name: "cityhall".to_string(),
+                position: Position { x: 4, y: 1 },
+            })
+        );
+        assert!(parse_command("area cityhall").is_err());
+        assert!(parse_command("area ../cityhall 4 1").is_err());
+    }
+
+    #[test]
+    fn assets_are_found_by_name_or_the_choices_are_listed() {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets");
+
+        assert_eq!(
+            find_asset(&assets, AREA_DIRECTORIES, "cityhall"),
+            Ok("area/cityhall.ron".to_string())
+        );
+        assert_eq!(
+            find_asset(&assets, AREA_DIRECTORIES, "westside"),
+            Ok("district/westside.ron".to_string())
+        );
+        assert_eq!(
+            find_asset(&assets, &[("prefab", &["ron"])], "character"),
+            Err("unknown name 'character', choose one of: playercharacter".to_string())
+        );
+    }
+
+    #[test]
+    fn prefabs_with_a_player_character_can_not_be_spawned() {
+        let player: Prefab<CharacterPrefab> =
+            ron::de::from_str(include_str!("../../../assets/prefab/playercharacter.ron")).unwrap();
+
+        assert_eq!(
+            check_no_player_character(&player, "prefab/playercharacter.ron"),
+            Err(
+                "prefab/playercharacter.ron has a player character, which can not be spawned twice"
+                    .to_string()
+            )
+        );
+
+        let clerk: Prefab<CharacterPrefab> = ron::de::from_str(
+            r#"#![enable(implicit_some)]
+            (entities: [(data: CharacterPrefab(
+                glyph: 'c',
+                variant: NonPlayerCharacter(name: "Clerk"),
+            ))])"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_no_player_character(&clerk, "prefab/clerk.ron"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn give_creates_items() {
+        assert_eq!(
+            parse_command("give money 250"),
+            Ok(ConsoleCommand::Give(Item::Money(250)))
+        );
+        assert_eq!(
+            parse_command("give document Leaked memo"),
+            Ok(ConsoleCommand::Give(Item::Document {
+                title: "Leaked memo".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_command("give favor Rob"),
+            Ok(ConsoleCommand::Give(Item::Favor {
+                from: "Rob".to_string()
+            }))
+        );
+        assert!(parse_command("give money lots").is_err());
+        assert!(parse_command("give votes 5").is_err());
+    }
+
+    #[test]
+    fn unknown_commands_are_errors() {
+        assert_eq!(
+            parse_command("fly 3 4"),
+            Err("unknown command 'fly'".to_string())
+        );
+        assert!(parse_command("   ").is_err());
+    }
+}
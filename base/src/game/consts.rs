@@ -2,6 +2,8 @@
 pub const DEBUG_SPRITE_LAYER: f32 = -1.0;
 /// Rendering z-depth for background entities
 pub const BACKGROUND_SPRITE_LAYER: f32 = 0.0;
+/// Rendering z-depth for item entities lying on the ground
+pub const ITEM_SPRITE_LAYER: f32 = 0.5;
 /// Rendering z-depth for non-player character entities
 pub const NPC_SPRITE_LAYER: f32 = 1.0;
 /// Rendering z-depth for player character entities
@@ -17,3 +19,48 @@ pub const GLYPH_FONT_SIZE: f32 = 20.0;
 pub const TILE_HEIGHT: u32 = 24;
 /// Width for area grid tiles (in pixels)
 pub const TILE_WIDTH: u32 = 16;
+
+/// Rendering z-depth for heads-up display entities
+pub const HUD_LAYER: f32 = 5.0;
+/// Font size for heads-up display text
+pub const HUD_FONT_SIZE: f32 = 16.0;
+/// Number of message log lines shown in the heads-up display
+pub const HUD_MESSAGE_LINES: usize = 5;
+/// Rendering z-depth for the minimap
+pub const MINIMAP_LAYER: f32 = 6.0;
+/// Size of area grid tiles on the minimap (in pixels)
+pub const MINIMAP_TILE_SIZE: u32 = 3;
+/// Rendering z-depth for full screen overlays
+pub const SCREEN_LAYER: f32 = 10.0;
+/// Rendering z-depth for the developer console
+pub const CONSOLE_LAYER: f32 = 11.0;
+/// Number of output lines shown in the developer console
+pub const CONSOLE_OUTPUT_LINES: usize = 8;
+/// Area map that the game starts in (relative to the assets directory)
+pub const START_AREA_MAP: &str = "area/cityhall.ron";
+/// Name of the quicksave file (in the working directory)
+pub const SAVE_FILE: &str = "save.ron";
+/// Number of characters of dialogue revealed per second
+pub const DIALOGUE_TEXT_SPEED: f32 = 40.0;
+/// Number of choice labels in the dialogue panel
+pub const DIALOGUE_CHOICE_LINES: usize = 6;
+/// Rendering z-depth for name labels and speech bubbles above characters
+pub const LABEL_LAYER: f32 = 3.0;
+/// Font size for name labels and speech bubbles
+pub const LABEL_FONT_SIZE: f32 = 12.0;
+/// Characters show their name within this many tiles of the player
+pub const NAME_LABEL_DISTANCE: u32 = 4;
+/// Characters only bark within this many tiles of the player
+pub const BARK_DISTANCE: u32 = 6;
+/// Shortest and longest time between barks of a character (in seconds)
+pub const BARK_INTERVAL: (f32, f32) = (8.0, 20.0);
+/// Time that a speech bubble is shown, including fading out (in seconds)
+pub const BARK_DURATION: f32 = 4.0;
+/// Time that a speech bubble takes to fade out (in seconds)
+pub const BARK_FADE_DURATION: f32 = 1.0;
+/// Name labels are shown this many tiles above the center of their character
+pub const NAME_LABEL_ROWS: f32 = 0.8;
+/// Speech bubbles are shown this many tiles above the center of their character
+pub const SPEECH_BUBBLE_ROWS: f32 = 1.6;
+/// Seed of the `GameRng` that picks barks and other random events
+pub const RNG_SEED: u64 = 0x5eed_ba2c;
@@ -0,0 +1,134 @@
+use amethyst::{
+    core::Hidden,
+    ecs::Entity,
+    prelude::{Builder, World},
+    ui::{Anchor, UiText, UiTransform},
+};
+
+use super::{
+    area::{get_world_coordinates, Position},
+    assets::Fonts,
+    consts::{DEBUG_SPRITE_LAYER, HUD_FONT_SIZE, HUD_LAYER, TILE_HEIGHT, TILE_WIDTH},
+};
+
+/// A line between two points in world coordinates.
+pub type DebugLine = ([f32; 3], [f32; 3]);
+
+/// Use as a resource to access the debug overlay entities.
+pub struct DebugOverlay {
+    pub is_visible: bool,
+    /// Holds the debug lines of the area grid and collision markers while visible.
+    pub lines: Entity,
+    /// Shows the frame rate and number of entities.
+    pub text: Entity,
+}
+
+/// Create the hidden debug overlay entities and add the `DebugOverlay` resource.
+///
+/// # Notes
+///  * Requires the `Fonts` resource to exist.
+pub fn create_debug_overlay(world: &mut World) {
+    let font = world.read_resource::<Fonts>().main.clone();
+
+    let mut text = UiText::new(font, String::new(), [1.0, 0.4, 0.4, 1.0], HUD_FONT_SIZE);
+    text.align = Anchor::MiddleRight;
+
+    let transform = UiTransform::new(
+        "debug_overlay_text".to_string(),
+        Anchor::BottomRight,
+        Anchor::BottomRight,
+        -10.0,
+        10.0,
+        HUD_LAYER,
+        400.0,
+        HUD_FONT_SIZE + 4.0,
+    );
+
+    let text = world
+        .create_entity()
+        .with(text)
+        .with(transform)
+        .with(Hidden)
+        .build();
+
+    let lines = world.create_entity().build();
+
+    world.add_resource(DebugOverlay {
+        is_visible: false,
+        lines,
+        text,
+    });
+}
+
+/// Get the lines of a grid around all tiles of an area.
+pub fn get_area_grid_lines([nx, ny]: &[u32; 2]) -> Vec<DebugLine> {
+    let (size_x, size_y) = get_world_coordinates(*nx, *ny);
+
+    let columns = (0..=*nx).map(|col| {
+        let x = (col * TILE_WIDTH) as f32;
+        (
+            [x, 0.0, DEBUG_SPRITE_LAYER],
+            [x, size_y, DEBUG_SPRITE_LAYER],
+        )
+    });
+
+    let rows = (0..=*ny).map(|row| {
+        let y = (row * TILE_HEIGHT) as f32;
+        (
+            [0.0, y, DEBUG_SPRITE_LAYER],
+            [size_x, y, DEBUG_SPRITE_LAYER],
+        )
+    });
+
+    columns.chain(rows).collect()
+}
+
+/// Get lines which cross out every given tile.
+pub fn get_collision_marker_lines<'a, I: Iterator<Item = &'a Position>>(
+    positions: I,
+) -> Vec<DebugLine> {
+    positions
+        .flat_map(|position| {
+            let (x0, y0) = get_world_coordinates(position.x, position.y);
+            let (x1, y1) = get_world_coordinates(position.x + 1, position.y + 1);
+
+            vec![
+                ([x0, y0, DEBUG_SPRITE_LAYER], [x1, y1, DEBUG_SPRITE_LAYER]),
+                ([x0, y1, DEBUG_SPRITE_LAYER], [x1, y0, DEBUG_SPRITE_LAYER]),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_grid_has_lines_around_every_tile() {
+        let lines = get_area_grid_lines(&[3, 2]);
+
+        assert_eq!(lines.len(), 4 + 3);
+
+        let (start, end) = lines[3];
+        assert_eq!(start[0], (3 * TILE_WIDTH) as f32);
+        assert_eq!(end[1], (2 * TILE_HEIGHT) as f32);
+    }
+
+    #[test]
+    fn collision_markers_cross_out_the_tile() {
+        let positions = vec![Position { x: 1, y: 2 }];
+        let lines = get_collision_marker_lines(positions.iter());
+
+        let (x0, y0) = get_world_coordinates(1, 2);
+        let (x1, y1) = get_world_coordinates(2, 3);
+
+        assert_eq!(
+            lines,
+            vec![
+                ([x0, y0, DEBUG_SPRITE_LAYER], [x1, y1, DEBUG_SPRITE_LAYER]),
+                ([x0, y1, DEBUG_SPRITE_LAYER], [x1, y0, DEBUG_SPRITE_LAYER]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,830 @@
+use amethyst::{
+    assets::{Asset, Format, Handle, ProcessingState},
+    core::{Named, ParentHierarchy, Time},
+    ecs::prelude::{Component, DenseVecStorage, Entity, Join, VecStorage, WriteStorage},
+    input::{is_key_down, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, World},
+    shrev::EventChannel,
+    ui::{UiCreator, UiFinder, UiText},
+    Error,
+};
+
+use inkling::{read_story_from_string, Prompt, Story, Variable};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+use crate::{
+    locale::Locale,
+    menu::{get_menu_command, MenuCommand},
+};
+
+use super::{
+    campaign::CampaignEvent,
+    character::{Glyph, PlayerCharacter},
+    consts::{DIALOGUE_CHOICE_LINES, DIALOGUE_TEXT_SPEED},
+    faction::{FactionMember, Factions},
+    flags::{get_story_variable_names, GameFlags},
+    item::{Item, ItemTransferEvent},
+    message::MessageLog,
+    relations::{Attitude, RelationshipEvent, Subject},
+};
+
+const DIALOGUE_SELECTED_COLOR: [f32; 4] = [0.388, 0.887, 0.678, 1.0];
+const DIALOGUE_UNSELECTED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const DIALOGUE_PORTRAIT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Story variable which holds the id of the faction of the character that the
+/// player talks to, or nothing if they are not a member of one. It is not shared
+/// with `GameFlags`.
+const SPEAKER_FACTION_VARIABLE: &str = "speaker_faction";
+
+const NUMBER_KEYS: [VirtualKeyCode; 9] = [
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+];
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Ink story which is played when the player talks to a character.
+pub struct Dialogue {
+    /// Path to the story, relative to the assets directory.
+    pub story: String,
+    /// Knot that the story starts from, the beginning of the story if not specified.
+    pub knot: Option<String>,
+}
+
+impl Component for Dialogue {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Debug)]
+/// Source of an ink story which is read from an asset file.
+pub struct StoryData(pub String);
+
+impl Asset for StoryData {
+    const NAME: &'static str = "windy_city_politics::StoryData";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+impl From<StoryData> for Result<ProcessingState<StoryData>, Error> {
+    fn from(data: StoryData) -> Result<ProcessingState<StoryData>, Error> {
+        Ok(ProcessingState::Loaded(data))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Format for reading ink stories as plain text.
+pub struct InkFormat;
+
+impl Format<StoryData> for InkFormat {
+    fn name(&self) -> &'static str {
+        "INK"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<StoryData, Error> {
+        String::from_utf8(bytes)
+            .map(StoryData)
+            .map_err(|err| Error::from_string(format!("story is not valid UTF-8: {}", err)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Something that happens when the story reaches a line with a tag. Tags are written
+/// as `# name: value` with the value in RON, other tags are ignored.
+pub enum DialogueOutcome {
+    /// `# attitude: (trust: 5)` changes how the speaker regards the player.
+    Attitude(Attitude),
+    /// `# give: Money(50)` hands an item from the speaker to the player.
+    Give(Item),
+    /// `# take: Favor(from: "Rob")` hands an item from the player to the speaker.
+    Take(Item),
+    /// `# campaign: Issue(issue: "housing", amount: 0.05)` changes the support of voters.
+    Campaign(CampaignEvent),
+}
+
+impl DialogueOutcome {
+    /// Read the outcome of a tag, if it is one.
+    pub fn from_tag(tag: &str) -> Result<Option<Self>, String> {
+        let mut parts = tag.splitn(2, ':');
+        let name = parts.next().unwrap_or_default().trim();
+        let value = parts.next().unwrap_or_default();
+
+        let outcome = match name {
+            "attitude" => DialogueOutcome::Attitude(parse_tag_value(name, value)?),
+            "give" => DialogueOutcome::Give(parse_tag_value(name, value)?),
+            "take" => DialogueOutcome::Take(parse_tag_value(name, value)?),
+            "campaign" => DialogueOutcome::Campaign(parse_tag_value(name, value)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(outcome))
+    }
+}
+
+fn parse_tag_value<T: DeserializeOwned>(name: &str, value: &str) -> Result<T, String> {
+    ron::de::from_str(value.trim()).map_err(|err| format!("could not read tag '{}': {}", name, err))
+}
+
+/// A running ink story, split into lines which are shown one at a time and the
+/// choices which follow them.
+pub struct Conversation {
+    story: Story,
+    /// Global variables of the story, which are shared with `GameFlags`.
+    variables: Vec<String>,
+    lines: VecDeque<String>,
+    choices: Vec<String>,
+    /// Outcomes of the tags which the story has reached and which have not been taken.
+    outcomes: Vec<DialogueOutcome>,
+}
+
+impl Conversation {
+    /// Start a story from the given knot or its beginning. Story variables are set
+    /// from the flags of the same names, and `speaker_faction` to the faction of the
+    /// speaker if the story declares it.
+    pub fn start(
+        ink: &str,
+        knot: Option<&str>,
+        flags: &GameFlags,
+        speaker_faction: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut story =
+            read_story_from_string(ink).map_err(|err| format!("could not parse story: {}", err))?;
+
+        let mut variables = get_story_variable_names(ink);
+        flags.write_to_story(&mut story, &variables);
+
+        if let Some(index) = variables
+            .iter()
+            .position(|name| name == SPEAKER_FACTION_VARIABLE)
+        {
+            variables.remove(index);
+
+            let faction = Variable::String(speaker_faction.unwrap_or_default().to_string());
+            if let Err(err) = story.set_variable(SPEAKER_FACTION_VARIABLE, faction) {
+                log::warn!("could not set the faction of the speaker: {}", err);
+            }
+        }
+
+        story
+            .start()
+            .map_err(|err| format!("could not start story: {}", err))?;
+
+        if let Some(knot) = knot {
+            story
+                .move_to(knot, None)
+                .map_err(|err| format!("could not move to knot '{}': {}", knot, err))?;
+        }
+
+        let mut conversation = Conversation {
+            story,
+            variables,
+            lines: VecDeque::new(),
+            choices: Vec::new(),
+            outcomes: Vec::new(),
+        };
+
+        conversation.resume()?;
+
+        Ok(conversation)
+    }
+
+    /// Take the next line of text to show, if any.
+    pub fn next_line(&mut self) -> Option<String> {
+        self.lines.pop_front()
+    }
+
+    pub fn has_lines(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Choices which are offered once all lines have been shown.
+    pub fn choices(&self) -> &[String] {
+        &self.choices
+    }
+
+    /// Make a choice and continue the story.
+    pub fn choose(&mut self, index: usize) -> Result<(), String> {
+        self.story
+            .make_choice(index)
+            .map_err(|err| format!("could not make choice {}: {}", index, err))?;
+
+        self.resume()
+    }
+
+    /// Whether all lines have been shown and there are no choices left.
+    pub fn is_finished(&self) -> bool {
+        self.lines.is_empty() && self.choices.is_empty()
+    }
+
+    /// Take the outcomes of all tags which the story has reached so far.
+    pub fn take_outcomes(&mut self) -> Vec<DialogueOutcome> {
+        std::mem::replace(&mut self.outcomes, Vec::new())
+    }
+
+    /// Set the flags of all story variables to their current values.
+    pub fn write_flags(&self, flags: &mut GameFlags) {
+        flags.read_from_story(&self.story, &self.variables);
+    }
+
+    fn resume(&mut self) -> Result<(), String> {
+        let mut buffer = Vec::new();
+
+        let prompt = self
+            .story
+            .resume(&mut buffer)
+            .map_err(|err| format!("could not continue story: {}", err))?;
+
+        for tag in buffer.iter().flat_map(|line| line.tags.iter()) {
+            match DialogueOutcome::from_tag(tag) {
+                Ok(Some(outcome)) => self.outcomes.push(outcome),
+                Ok(None) => (),
+                Err(msg) => log::warn!("{}", msg),
+            }
+        }
+
+        self.lines.extend(
+            buffer
+                .into_iter()
+                .map(|line| line.text.trim().to_string())
+                .filter(|text| !text.is_empty()),
+        );
+
+        self.choices = match prompt {
+            Prompt::Choice(choices) => choices.into_iter().map(|choice| choice.text).collect(),
+            Prompt::Done => Vec::new(),
+        };
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Reveals a line of text a few characters at a time.
+pub struct Typewriter {
+    text: String,
+    /// Number of characters shown so far, counting fractions between frames.
+    shown: f32,
+}
+
+impl Typewriter {
+    pub fn new(text: String) -> Self {
+        Typewriter { text, shown: 0.0 }
+    }
+
+    /// Reveal more characters for the time that has passed, see `DIALOGUE_TEXT_SPEED`.
+    pub fn advance(&mut self, seconds: f32) {
+        self.shown += seconds * DIALOGUE_TEXT_SPEED;
+    }
+
+    /// Reveal the whole text at once.
+    pub fn skip(&mut self) {
+        self.shown = self.text.chars().count() as f32;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.shown as usize >= self.text.chars().count()
+    }
+
+    /// The part of the text which has been revealed.
+    pub fn visible_text(&self) -> &str {
+        match self.text.char_indices().nth(self.shown as usize) {
+            Some((end, _)) => &self.text[..end],
+            None => &self.text,
+        }
+    }
+}
+
+/// Plays the `Dialogue` of a character in a panel at the bottom of the screen.
+///
+/// The panel is defined in `assets/ui/dialogue.ron`. It shows the name and glyph
+/// of the character, the current line of the story, revealed a few characters at
+/// a time, and a numbered list of choices. Press space or confirm to reveal the
+/// whole line or continue, pick choices with the arrow keys or number keys and
+/// leave with escape. Story variables are written back to `GameFlags` when the
+/// dialogue ends.
+pub struct DialogueScreen {
+    character: Entity,
+    story: StoryData,
+    knot: Option<String>,
+    conversation: Option<Conversation>,
+    typewriter: Typewriter,
+    /// Index of the selected choice.
+    selected: usize,
+    ui_entity: Option<Entity>,
+}
+
+impl DialogueScreen {
+    pub fn new(character: Entity, story: StoryData, knot: Option<String>) -> Self {
+        DialogueScreen {
+            character,
+            story,
+            knot,
+            conversation: None,
+            typewriter: Typewriter::default(),
+            selected: 0,
+            ui_entity: None,
+        }
+    }
+
+    /// Whether the choices are shown, which is once the last line has been revealed.
+    fn is_choosing(&self) -> bool {
+        self.typewriter.is_done()
+            && self
+                .conversation
+                .as_ref()
+                .map(|conversation| !conversation.has_lines() && !conversation.choices().is_empty())
+                .unwrap_or(false)
+    }
+
+    /// Show the next line, returns false when the conversation has finished.
+    fn next_line(&mut self) -> bool {
+        let conversation = match self.conversation.as_mut() {
+            Some(conversation) => conversation,
+            None => return false,
+        };
+
+        if let Some(line) = conversation.next_line() {
+            self.typewriter = Typewriter::new(line);
+        }
+
+        !(self.typewriter.is_done() && conversation.is_finished())
+    }
+
+    fn choose(&mut self, index: usize, world: &mut World) -> bool {
+        let result = match self.conversation.as_mut() {
+            Some(conversation) if index < conversation.choices().len() => {
+                conversation.choose(index)
+            }
+            _ => return true,
+        };
+
+        self.selected = 0;
+
+        match result {
+            Ok(()) => {
+                self.typewriter = Typewriter::default();
+                self.next_line()
+            }
+            Err(msg) => {
+                log::error!("{}", msg);
+                world
+                    .write_resource::<MessageLog>()
+                    .push("The conversation trails off");
+                false
+            }
+        }
+    }
+
+    /// Reveal the current line, continue to the next one or make the selected choice.
+    /// Returns false when the conversation has finished.
+    fn confirm(&mut self, world: &mut World) -> bool {
+        if !self.typewriter.is_done() {
+            self.typewriter.skip();
+            true
+        } else if self.is_choosing() {
+            self.choose(self.selected, world)
+        } else {
+            self.next_line()
+        }
+    }
+
+    /// Send the events for the outcomes of tags, which take effect once the game
+    /// resumes.
+    fn apply_outcomes(&self, outcomes: Vec<DialogueOutcome>, world: &mut World) {
+        if outcomes.is_empty() {
+            return;
+        }
+
+        let player = {
+            let entities = world.entities();
+            let characters = world.read_storage::<PlayerCharacter>();
+
+            (&entities, &characters)
+                .join()
+                .map(|(entity, _)| entity)
+                .next()
+        };
+
+        for outcome in outcomes {
+            match outcome {
+                DialogueOutcome::Attitude(change) => world
+                    .write_resource::<EventChannel<RelationshipEvent>>()
+                    .single_write(RelationshipEvent {
+                        character: self.character,
+                        subject: Subject::Player,
+                        change,
+                    }),
+                DialogueOutcome::Give(item) => match player {
+                    Some(player) => world
+                        .write_resource::<EventChannel<ItemTransferEvent>>()
+                        .single_write(ItemTransferEvent {
+                            from: self.character,
+                            to: player,
+                            item,
+                        }),
+                    None => log::warn!("there is no player character to give {} to", item),
+                },
+                DialogueOutcome::Take(item) => match player {
+                    Some(player) => world
+                        .write_resource::<EventChannel<ItemTransferEvent>>()
+                        .single_write(ItemTransferEvent {
+                            from: player,
+                            to: self.character,
+                            item,
+                        }),
+                    None => log::warn!("there is no player character to take {} from", item),
+                },
+                DialogueOutcome::Campaign(event) => world
+                    .write_resource::<EventChannel<CampaignEvent>>()
+                    .single_write(event),
+            }
+        }
+    }
+
+    fn update_ui(&self, world: &mut World) {
+        let (name, glyph, color) = {
+            let names = world.read_storage::<Named>();
+            let glyphs = world.read_storage::<Glyph>();
+            let members = world.read_storage::<FactionMember>();
+            let locale = world.read_resource::<Locale>();
+
+            let color = members
+                .get(self.character)
+                .and_then(|FactionMember(id)| {
+                    world
+                        .read_resource::<Factions>()
+                        .get(id)
+                        .map(|faction| faction.color)
+                })
+                .unwrap_or(DIALOGUE_PORTRAIT_COLOR);
+
+            (
+                names
+                    .get(self.character)
+                    .map(|named| locale.character_name(&named.name).to_string())
+                    .unwrap_or_default(),
+                glyphs
+                    .get(self.character)
+                    .map(|Glyph(glyph)| glyph.to_string())
+                    .unwrap_or_default(),
+                color,
+            )
+        };
+
+        let choices = if self.is_choosing() {
+            self.conversation
+                .as_ref()
+                .map(|conversation| conversation.choices().to_vec())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let text = self.typewriter.visible_text().to_string();
+        let selected = self.selected;
+
+        // The panel is loaded in the background so the labels may not exist yet,
+        // they are kept up to date every frame
+        world.exec(
+            |(finder, mut ui_texts): (UiFinder<'_>, WriteStorage<'_, UiText>)| {
+                let mut set_text = |id: &str, text: String, color: [f32; 4]| {
+                    if let Some(ui_text) = finder.find(id).and_then(|e| ui_texts.get_mut(e)) {
+                        ui_text.text = text;
+                        ui_text.color = color;
+                    }
+                };
+
+                set_text("dialogue_speaker", name, DIALOGUE_SELECTED_COLOR);
+                set_text("dialogue_portrait", glyph, color);
+                set_text("dialogue_text", text, DIALOGUE_UNSELECTED_COLOR);
+
+                for (i, line) in get_choice_lines(&choices).into_iter().enumerate() {
+                    let color = if i == selected {
+                        DIALOGUE_SELECTED_COLOR
+                    } else {
+                        DIALOGUE_UNSELECTED_COLOR
+                    };
+
+                    set_text(&format!("dialogue_choice_{}", i), line, color);
+                }
+            },
+        );
+    }
+}
+
+impl SimpleState for DialogueScreen {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        world.exec(|mut creator: UiCreator<'_>| {
+            self.ui_entity = Some(creator.create("ui/dialogue.ron", ()));
+        });
+
+        let faction = world
+            .read_storage::<FactionMember>()
+            .get(self.character)
+            .map(|FactionMember(id)| id.clone());
+
+        let result = Conversation::start(
+            &self.story.0,
+            self.knot.as_ref().map(String::as_str),
+            &world.read_resource::<GameFlags>(),
+            faction.as_ref().map(String::as_str),
+        );
+
+        match result {
+            Ok(conversation) => {
+                self.conversation = Some(conversation);
+                self.next_line();
+            }
+            Err(msg) => {
+                log::error!("{}", msg);
+                world
+                    .write_resource::<MessageLog>()
+                    .push("They have nothing to say");
+            }
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        if let Some(mut conversation) = self.conversation.take() {
+            self.apply_outcomes(conversation.take_outcomes(), world);
+            conversation.write_flags(&mut world.write_resource::<GameFlags>());
+        }
+
+        if let Some(ui_entity) = self.ui_entity.take() {
+            let mut entities = world
+                .read_resource::<ParentHierarchy>()
+                .all_children_iter(ui_entity)
+                .collect::<Vec<_>>();
+            entities.push(ui_entity);
+
+            world
+                .delete_entities(&entities)
+                .expect("could not delete dialogue entities");
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        let choice_count = if self.is_choosing() {
+            self.conversation
+                .as_ref()
+                .map(|conversation| conversation.choices().len())
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let running = match get_menu_command(&event) {
+            Some(MenuCommand::Up) => {
+                self.selected = self.selected.saturating_sub(1);
+                true
+            }
+            Some(MenuCommand::Down) => {
+                self.selected = (self.selected + 1).min(choice_count.saturating_sub(1));
+                true
+            }
+            Some(MenuCommand::Confirm) => self.confirm(world),
+            Some(MenuCommand::Back) => false,
+            None => match event {
+                StateEvent::Window(event) => {
+                    if is_key_down(&event, VirtualKeyCode::Escape) {
+                        false
+                    } else if is_key_down(&event, VirtualKeyCode::Space) {
+                        self.confirm(world)
+                    } else if let Some(index) = NUMBER_KEYS
+                        .iter()
+                        .take(choice_count)
+                        .position(|&key| is_key_down(&event, key))
+                    {
+                        self.choose(index, world)
+                    } else {
+                        true
+                    }
+                }
+                _ => true,
+            },
+        };
+
+        if running {
+            Trans::None
+        } else {
+            Trans::Pop
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if self.conversation.is_none() {
+            return Trans::Pop;
+        }
+
+        let outcomes = self
+            .conversation
+            .as_mut()
+            .map(Conversation::take_outcomes)
+            .unwrap_or_default();
+        self.apply_outcomes(outcomes, data.world);
+
+        let seconds = data.world.read_resource::<Time>().delta_seconds();
+        self.typewriter.advance(seconds);
+
+        self.update_ui(data.world);
+
+        Trans::None
+    }
+}
+
+/// Get the text of every choice label, numbered from 1. Labels without a choice are
+/// left empty.
+fn get_choice_lines(choices: &[String]) -> Vec<String> {
+    if choices.len() > DIALOGUE_CHOICE_LINES {
+        log::warn!(
+            "only {} of {} choices can be shown",
+            DIALOGUE_CHOICE_LINES,
+            choices.len()
+        );
+    }
+
+    (0..DIALOGUE_CHOICE_LINES)
+        .map(|i| match choices.get(i) {
+            Some(choice) => format!("{}. {}", i + 1, choice),
+            None => String::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORY: &str = "
+VAR read_waypoint_sign = false
+
+Rob looks up from his desk.
+What do you want?
+
+*   [Ask about the zoning vote]
+    It's settled. Don't worry about it.
+*   [Leave]
+
+- Rob goes back to his papers.
+";
+
+    #[test]
+    fn typewriter_reveals_whole_characters_over_time() {
+        let mut typewriter = Typewriter::new("Héllo".to_string());
+        assert_eq!(typewriter.visible_text(), "");
+
+        typewriter.advance(2.5 / DIALOGUE_TEXT_SPEED);
+        assert_eq!(typewriter.visible_text(), "Hé");
+        assert!(!typewriter.is_done());
+
+        typewriter.skip();
+        assert_eq!(typewriter.visible_text(), "Héllo");
+        assert!(typewriter.is_done());
+    }
+
+    #[test]
+    fn choice_lines_are_numbered_and_padded() {
+        let lines = get_choice_lines(&["Yes".to_string(), "No".to_string()]);
+
+        assert_eq!(lines.len(), DIALOGUE_CHOICE_LINES);
+        assert_eq!(lines[0], "1. Yes");
+        assert_eq!(lines[1], "2. No");
+        assert_eq!(lines[2], "");
+    }
+
+    #[test]
+    fn conversations_show_lines_then_choices() {
+        let flags = GameFlags::default();
+        let mut conversation = Conversation::start(STORY, None, &flags, None).unwrap();
+
+        assert_eq!(
+            conversation.next_line(),
+            Some("Rob looks up from his desk.".to_string())
+        );
+        assert_eq!(
+            conversation.next_line(),
+            Some("What do you want?".to_string())
+        );
+        assert!(!conversation.has_lines());
+        assert_eq!(
+            conversation.choices(),
+            &["Ask about the zoning vote".to_string(), "Leave".to_string()][..]
+        );
+
+        conversation.choose(0).unwrap();
+
+        assert_eq!(
+            conversation.next_line(),
+            Some("It's settled. Don't worry about it.".to_string())
+        );
+        assert_eq!(
+            conversation.next_line(),
+            Some("Rob goes back to his papers.".to_string())
+        );
+        assert!(conversation.is_finished());
+    }
+
+    #[test]
+    fn conversations_share_flags_with_story_variables() {
+        let mut flags = GameFlags::default();
+        flags.set("read_waypoint_sign", true);
+
+        let conversation = Conversation::start(STORY, None, &flags, None).unwrap();
+
+        let mut written = GameFlags::default();
+        conversation.write_flags(&mut written);
+
+        assert!(written.is_set("read_waypoint_sign"));
+    }
+
+    #[test]
+    fn tags_with_known_names_are_outcomes() {
+        assert_eq!(
+            DialogueOutcome::from_tag("attitude: (trust: 5)"),
+            Ok(Some(DialogueOutcome::Attitude(Attitude {
+                trust: 5,
+                ..Attitude::default()
+            })))
+        );
+        assert_eq!(
+            DialogueOutcome::from_tag("give: Money(50)"),
+            Ok(Some(DialogueOutcome::Give(Item::Money(50))))
+        );
+        assert_eq!(
+            DialogueOutcome::from_tag("take: Favor(from: \"Rob\")"),
+            Ok(Some(DialogueOutcome::Take(Item::Favor {
+                from: "Rob".to_string()
+            })))
+        );
+        assert_eq!(
+            DialogueOutcome::from_tag("campaign: Issue(issue: \"housing\", amount: 0.05)"),
+            Ok(Some(DialogueOutcome::Campaign(CampaignEvent::Issue {
+                issue: "housing".to_string(),
+                amount: 0.05,
+            })))
+        );
+        assert_eq!(DialogueOutcome::from_tag("mood: grumpy"), Ok(None));
+        assert!(DialogueOutcome::from_tag("give: lots").is_err());
+    }
+
+    #[test]
+    fn conversations_collect_the_outcomes_of_tags() {
+        let story = "
+Rob hands you an envelope. # give: Money(50)
+*   [Thank him]
+    He nods. # attitude: (favor: 5)
+";
+        let flags = GameFlags::default();
+        let mut conversation = Conversation::start(story, None, &flags, None).unwrap();
+
+        assert_eq!(
+            conversation.take_outcomes(),
+            vec![DialogueOutcome::Give(Item::Money(50))]
+        );
+        assert!(conversation.take_outcomes().is_empty());
+
+        conversation.choose(0).unwrap();
+
+        assert_eq!(
+            conversation.take_outcomes(),
+            vec![DialogueOutcome::Attitude(Attitude {
+                favor: 5,
+                ..Attitude::default()
+            })]
+        );
+    }
+
+    #[test]
+    fn stories_see_the_faction_of_the_speaker() {
+        let story = "
+VAR speaker_faction = \"\"
+
+{speaker_faction}
+";
+        let flags = GameFlags::default();
+        let mut conversation = Conversation::start(story, None, &flags, Some("machine")).unwrap();
+
+        assert_eq!(conversation.next_line(), Some("machine".to_string()));
+
+        let mut written = GameFlags::default();
+        conversation.write_flags(&mut written);
+
+        assert!(written.get("speaker_faction").is_none());
+    }
+}
@@ -0,0 +1,482 @@
+use amethyst::{
+    assets::{Format, Prefab},
+    Error,
+};
+
+use rand::{distributions::WeightedIndex, seq::SliceRandom, Rng, SeedableRng};
+use rand_pcg::Pcg32;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+use super::{
+    area::{Area, Position},
+    character::{CharacterPrefab, NonPlayerCharacterPrefab},
+    item::Inventory,
+    map::{get_area_map_prefab, AreaMapPrefab},
+    relations::{Attitude, Relationships},
+};
+
+/// Number of tiles along each side of a city block.
+const BLOCK_SIZE: u32 = 8;
+/// Number of tiles across streets, which run between and around all blocks.
+const STREET_WIDTH: u32 = 2;
+/// One in this many blocks is a park instead of a building.
+const PARK_ODDS: u32 = 4;
+/// One in this many tiles of a park has a tree, which blocks movement.
+const TREE_ODDS: u32 = 6;
+/// One in this many tiles of a park has a bench.
+const BENCH_ODDS: u32 = 12;
+
+const WALL_GLYPH: char = '#';
+const DOOR_GLYPH: char = '+';
+const TREE_GLYPH: char = 'T';
+const BENCH_GLYPH: char = 'h';
+
+/// Number of times to draw a new name for a voter before numbering it.
+const NAME_ATTEMPTS: usize = 10;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// A district of city blocks which is generated from a seed, read by `DistrictFormat`.
+///
+/// The blocks are separated by streets. Every block is a building with a door
+/// onto the street or a park with trees and benches. Voters from the demographic
+/// groups are placed on free tiles in the streets, parks and buildings. The same
+/// seed always generates the same district.
+pub struct District {
+    #[serde(default)]
+    pub name: String,
+    pub seed: u64,
+    /// Number of blocks along x and y.
+    pub blocks: [u32; 2],
+    /// Number of voters to place, fewer if the district does not have room for them.
+    pub voters: u32,
+    pub demographics: Demographics,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Tables which voters are drawn from.
+pub struct Demographics {
+    pub first_names: Vec<String>,
+    pub last_names: Vec<String>,
+    pub groups: Vec<DemographicGroup>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// A kind of voter in a district.
+pub struct DemographicGroup {
+    /// How many voters are in the group relative to other groups.
+    pub weight: u32,
+    pub glyph: char,
+    /// Id of the faction that voters in the group are members of.
+    #[serde(default)]
+    pub faction: Option<String>,
+    /// Lowest and highest attitude toward the player that voters start with.
+    #[serde(default)]
+    pub attitude: [Attitude; 2],
+    /// Least and most money that voters carry.
+    #[serde(default)]
+    pub money: [u32; 2],
+}
+
+impl District {
+    /// Number of tiles along x and y.
+    pub fn dimensions(&self) -> [u32; 2] {
+        let [bx, by] = self.blocks;
+        let size = |n| n * (BLOCK_SIZE + STREET_WIDTH) + STREET_WIDTH;
+
+        [size(bx), size(by)]
+    }
+
+    /// Generate the area and all entities of the district, or an error if the
+    /// district has no blocks or its demographic tables are empty.
+    pub fn get_prefab_data(&self) -> Result<Vec<AreaMapPrefab>, String> {
+        let demographics = &self.demographics;
+
+        if self.blocks.contains(&0) {
+            return Err("district needs at least one block along x and y".to_string());
+        }
+
+        if demographics.first_names.is_empty() || demographics.last_names.is_empty() {
+            return Err("demographics need at least one first and last name".to_string());
+        }
+
+        let weights = WeightedIndex::new(demographics.groups.iter().map(|group| group.weight))
+            .map_err(|err| format!("could not draw demographic groups ({})", err))?;
+
+        let mut rng = Pcg32::seed_from_u64(self.seed);
+
+        let mut data = vec![AreaMapPrefab::Area(Area {
+            name: self.name.clone(),
+            dimensions: self.dimensions(),
+        })];
+
+        let [bx, by] = self.blocks;
+
+        for block_y in 0..by {
+            for block_x in 0..bx {
+                let x0 = STREET_WIDTH + block_x * (BLOCK_SIZE + STREET_WIDTH);
+                let y0 = STREET_WIDTH + block_y * (BLOCK_SIZE + STREET_WIDTH);
+
+                if rng.gen_range(0, PARK_ODDS) == 0 {
+                    data.extend(get_park(x0, y0, &mut rng));
+                } else {
+                    data.extend(get_building(x0, y0, &mut rng));
+                }
+            }
+        }
+
+        let occupied = data
+            .iter()
+            .filter_map(AreaMapPrefab::position)
+            .collect::<HashSet<_>>();
+
+        let [nx, ny] = self.dimensions();
+        let mut free = (0..ny)
+            .flat_map(|y| (0..nx).map(move |x| Position { x, y }))
+            .filter(|position| !occupied.contains(position))
+            .collect::<Vec<_>>();
+
+        free.shuffle(&mut rng);
+
+        let mut names = HashSet::new();
+
+        for position in free.into_iter().take(self.voters as usize) {
+            let group = &demographics.groups[rng.sample(&weights)];
+            let name = get_unique_name(demographics, &mut names, &mut rng);
+
+            data.push(AreaMapPrefab::Character(get_voter(
+                group, name, position, &mut rng,
+            )));
+        }
+
+        Ok(data)
+    }
+
+    /// Create the prefab with the area as its main entity.
+    pub fn to_prefab(&self) -> Result<Prefab<AreaMapPrefab>, String> {
+        self.get_prefab_data().map(get_area_map_prefab)
+    }
+}
+
+/// Get the walls of a building which fills the block, with one door in a random
+/// wall. The door is never in a corner.
+fn get_building<R: Rng>(x0: u32, y0: u32, rng: &mut R) -> Vec<AreaMapPrefab> {
+    let last = BLOCK_SIZE - 1;
+    let along = rng.gen_range(1, last);
+
+    let door = match rng.gen_range(0, 4) {
+        0 => (along, 0),
+        1 => (along, last),
+        2 => (0, along),
+        _ => (last, along),
+    };
+
+    (0..BLOCK_SIZE)
+        .flat_map(|dy| (0..BLOCK_SIZE).map(move |dx| (dx, dy)))
+        .filter(|&(dx, dy)| dx == 0 || dy == 0 || dx == last || dy == last)
+        .map(|(dx, dy)| {
+            let is_door = (dx, dy) == door;
+
+            AreaMapPrefab::Tile {
+                glyph: if is_door { DOOR_GLYPH } else { WALL_GLYPH },
+                position: Position {
+                    x: x0 + dx,
+                    y: y0 + dy,
+                },
+                collision: !is_door,
+            }
+        })
+        .collect()
+}
+
+/// Get the trees and benches of a park which fills the block.
+fn get_park<R: Rng>(x0: u32, y0: u32, rng: &mut R) -> Vec<AreaMapPrefab> {
+    let mut tiles = Vec::new();
+
+    for dy in 0..BLOCK_SIZE {
+        for dx in 0..BLOCK_SIZE {
+            let position = Position {
+                x: x0 + dx,
+                y: y0 + dy,
+            };
+
+            if rng.gen_range(0, TREE_ODDS) == 0 {
+                tiles.push(AreaMapPrefab::Tile {
+                    glyph: TREE_GLYPH,
+                    position,
+                    collision: true,
+                });
+            } else if rng.gen_range(0, BENCH_ODDS) == 0 {
+                tiles.push(AreaMapPrefab::Tile {
+                    glyph: BENCH_GLYPH,
+                    position,
+                    collision: false,
+                });
+            }
+        }
+    }
+
+    tiles
+}
+
+/// Draw a name which no other voter in the district has, numbering it if the
+/// tables run out of new names.
+fn get_unique_name<R: Rng>(
+    demographics: &Demographics,
+    names: &mut HashSet<String>,
+    rng: &mut R,
+) -> String {
+    let mut draw = || {
+        format!(
+            "{} {}",
+            demographics.first_names.choose(rng).unwrap(),
+            demographics.last_names.choose(rng).unwrap()
+        )
+    };
+
+    let new_name = (0..NAME_ATTEMPTS)
+        .map(|_| draw())
+        .find(|name| !names.contains(name));
+
+    let name = match new_name {
+        Some(name) => name,
+        None => {
+            let name = draw();
+
+            (2..)
+                .map(|n| format!("{} {}", name, n))
+                .find(|numbered| !names.contains(numbered))
+                .unwrap()
+        }
+    };
+
+    names.insert(name.clone());
+
+    name
+}
+
+fn get_voter<R: Rng>(
+    group: &DemographicGroup,
+    name: String,
+    position: Position,
+    rng: &mut R,
+) -> CharacterPrefab {
+    let [low, high] = &group.attitude;
+    let mut draw = |low: i32, high: i32| rng.gen_range(low.min(high), low.max(high) + 1);
+
+    let player = Attitude {
+        trust: draw(low.trust, high.trust),
+        favor: draw(low.favor, high.favor),
+        alignment: draw(low.alignment, high.alignment),
+    };
+
+    let [least, most] = group.money;
+    let money = rng.gen_range(least.min(most), least.max(most) + 1);
+
+    CharacterPrefab::non_player(NonPlayerCharacterPrefab {
+        glyph: group.glyph,
+        name,
+        position,
+        faction: group.faction.clone(),
+        inventory: Some(Inventory {
+            money,
+            items: Vec::new(),
+        }),
+        relationships: Some(Relationships {
+            player,
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Format for generating a `District` from a RON file as a prefab.
+pub struct DistrictFormat;
+
+impl Format<Prefab<AreaMapPrefab>> for DistrictFormat {
+    fn name(&self) -> &'static str {
+        "District"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<Prefab<AreaMapPrefab>, Error> {
+        let district: District = ron::de::from_bytes(&bytes)
+            .map_err(|err| Error::from_string(format!("could not parse district: {}", err)))?;
+
+        district.to_prefab().map_err(Error::from_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_district(seed: u64) -> District {
+        District {
+            name: "Test".to_string(),
+            seed,
+            blocks: [3, 2],
+            voters: 20,
+            demographics: Demographics {
+                first_names: vec!["Ann".to_string(), "Bob".to_string()],
+                last_names: vec!["Lee".to_string(), "Kowalski".to_string()],
+                groups: vec![
+                    DemographicGroup {
+                        weight: 3,
+                        glyph: 'w',
+                        faction: Some("tenants".to_string()),
+                        attitude: [
+                            Attitude::default(),
+                            Attitude {
+                                trust: 10,
+                                ..Default::default()
+                            },
+                        ],
+                        money: [0, 20],
+                    },
+                    DemographicGroup {
+                        weight: 1,
+                        glyph: 'b',
+                        faction: None,
+                        attitude: Default::default(),
+                        money: [100, 100],
+                    },
+                ],
+            },
+        }
+    }
+
+    fn get_characters(data: &[AreaMapPrefab]) -> Vec<&CharacterPrefab> {
+        data.iter()
+            .filter_map(|entity| match entity {
+                AreaMapPrefab::Character(character) => Some(character),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_generates_same_district() {
+        let data = get_district(7).get_prefab_data().unwrap();
+        let again = get_district(7).get_prefab_data().unwrap();
+
+        assert_eq!(format!("{:?}", data), format!("{:?}", again));
+    }
+
+    #[test]
+    fn different_seeds_generate_different_districts() {
+        let data = get_district(7).get_prefab_data().unwrap();
+        let other = get_district(8).get_prefab_data().unwrap();
+
+        assert_ne!(format!("{:?}", data), format!("{:?}", other));
+    }
+
+    #[test]
+    fn district_is_sized_by_its_blocks() {
+        let data = get_district(1).get_prefab_data().unwrap();
+
+        match &data[0] {
+            AreaMapPrefab::Area(area) => assert_eq!(area.dimensions, [32, 22]),
+            other => panic!("expected the area, got {:?}", other),
+        }
+
+        assert!(data
+            .iter()
+            .filter_map(AreaMapPrefab::position)
+            .all(|position| position.x < 32 && position.y < 22));
+    }
+
+    #[test]
+    fn every_building_has_one_door() {
+        for seed in 0..10 {
+            let data = get_district(seed).get_prefab_data().unwrap();
+
+            let walls = data
+                .iter()
+                .filter(|entity| match entity {
+                    AreaMapPrefab::Tile { glyph, .. } => *glyph == WALL_GLYPH,
+                    _ => false,
+                })
+                .count();
+
+            let doors = data
+                .iter()
+                .filter(|entity| match entity {
+                    AreaMapPrefab::Tile {
+                        glyph, collision, ..
+                    } => *glyph == DOOR_GLYPH && !*collision,
+                    _ => false,
+                })
+                .count();
+
+            let walls_per_building = 4 * (BLOCK_SIZE - 1) as usize - 1;
+
+            assert_eq!(walls, doors * walls_per_building);
+        }
+    }
+
+    #[test]
+    fn voters_are_placed_on_free_tiles_with_unique_names() {
+        let data = get_district(3).get_prefab_data().unwrap();
+        let characters = get_characters(&data);
+
+        assert_eq!(characters.len(), 20);
+
+        let positions = data
+            .iter()
+            .filter_map(AreaMapPrefab::position)
+            .collect::<HashSet<_>>();
+        assert_eq!(positions.len(), data.len() - 1);
+
+        let names = characters
+            .iter()
+            .map(|character| character.name().unwrap())
+            .collect::<HashSet<_>>();
+        assert_eq!(names.len(), 20);
+    }
+
+    #[test]
+    fn voters_are_drawn_from_the_demographic_groups() {
+        let data = get_district(5).get_prefab_data().unwrap();
+
+        for character in get_characters(&data) {
+            match character.glyph() {
+                'w' => assert_eq!(character.faction(), Some("tenants")),
+                'b' => assert_eq!(character.faction(), None),
+                glyph => panic!("voter with glyph {} is in no group", glyph),
+            }
+        }
+    }
+
+    #[test]
+    fn voters_are_limited_by_free_tiles() {
+        let mut district = get_district(2);
+        district.blocks = [1, 1];
+        district.voters = 1000;
+
+        let data = district.get_prefab_data().unwrap();
+        let [nx, ny] = district.dimensions();
+
+        assert_eq!(data.len() - 1, (nx * ny) as usize);
+    }
+
+    #[test]
+    fn empty_tables_are_errors() {
+        let mut district = get_district(2);
+        district.demographics.groups.clear();
+        assert!(district.get_prefab_data().is_err());
+
+        let mut district = get_district(2);
+        district.demographics.last_names.clear();
+        assert!(district.get_prefab_data().is_err());
+
+        let mut district = get_district(2);
+        district.blocks = [0, 2];
+        assert!(district.get_prefab_data().is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use amethyst::{
+    ecs::{Entity, Join},
+    input::{is_key_down, VirtualKeyCode},
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, World},
+    renderer::Camera,
+    ui::{Anchor, UiText, UiTransform},
+};
+
+use crate::menu::{get_menu_command, MenuCommand};
+
+use super::{
+    area::{ActiveArea, Position},
+    assets::Fonts,
+    campaign::ElectionResult,
+    consts::HUD_LAYER,
+    debug::DebugOverlay,
+    hud::Hud,
+    label::{NameLabel, SpeechBubble},
+    minimap::Minimap,
+    script::AreaTrigger,
+};
+
+/// Shows the result of the election after the campaign has ended.
+pub struct EndGame {
+    result: ElectionResult,
+}
+
+impl EndGame {
+    pub fn new(result: ElectionResult) -> Self {
+        EndGame { result }
+    }
+}
+
+impl SimpleState for EndGame {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        // The campaign is over so nothing from the game should remain on screen.
+        delete_game_entities(world);
+
+        let font = world.read_resource::<Fonts>().main.clone();
+
+        let headline = if self.result.is_won() {
+            "You won the election!"
+        } else {
+            "You lost the election."
+        };
+
+        let summary = format!(
+            "Districts carried: {} of {}",
+            self.result.districts_won, self.result.districts_total
+        );
+
+        for (i, (text, font_size)) in [(headline.to_string(), 48.0), (summary, 28.0)]
+            .iter()
+            .enumerate()
+        {
+            let ui_text = UiText::new(font.clone(), text.clone(), [1.0, 1.0, 1.0, 1.0], *font_size);
+            let transform = UiTransform::new(
+                format!("end_game_text_{}", i),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.0,
+                40.0 - 80.0 * i as f32,
+                HUD_LAYER,
+                1000.0,
+                60.0,
+            );
+
+            world.create_entity().with(ui_text).with(transform).build();
+        }
+    }
+
+    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        if let Some(MenuCommand::Confirm) | Some(MenuCommand::Back) = get_menu_command(&event) {
+            return Trans::Quit;
+        }
+
+        if let StateEvent::Window(event) = event {
+            if [VirtualKeyCode::Q, VirtualKeyCode::Escape]
+                .iter()
+                .any(|&key| is_key_down(&event, key))
+            {
+                return Trans::Quit;
+            }
+        }
+
+        Trans::None
+    }
+}
+
+/// Delete the current area with everything in it along with the heads-up display,
+/// minimap and debug overlay. The camera and the entities of other states, such as
+/// the main menu below the game, are kept.
+fn delete_game_entities(world: &mut World) {
+    let mut game_entities = {
+        let entities = world.entities();
+        let positions = world.read_storage::<Position>();
+        let cameras = world.read_storage::<Camera>();
+        let triggers = world.read_storage::<AreaTrigger>();
+        let labels = world.read_storage::<NameLabel>();
+        let bubbles = world.read_storage::<SpeechBubble>();
+
+        (&entities, &positions, !&cameras)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .chain((&entities, &triggers).join().map(|(entity, _)| entity))
+            .chain((&entities, &labels).join().map(|(entity, _)| entity))
+            .chain((&entities, &bubbles).join().map(|(entity, _)| entity))
+            .collect::<Vec<Entity>>()
+    };
+
+    game_entities.push(world.read_resource::<ActiveArea>().0);
+    game_entities.push(world.read_resource::<Minimap>().entity);
+
+    {
+        let hud = world.read_resource::<Hud>();
+        game_entities.extend(&[hud.campaign, hud.relationship, hud.messages, hud.tooltip]);
+    }
+
+    {
+        let debug_overlay = world.read_resource::<DebugOverlay>();
+        game_entities.extend(&[debug_overlay.lines, debug_overlay.text]);
+    }
+
+    // Deleting an entity twice is an error
+    game_entities.sort();
+    game_entities.dedup();
+
+    if let Err(err) = world.delete_entities(&game_entities) {
+        log::error!("could not delete the entities of the game: {}", err);
+    }
+}
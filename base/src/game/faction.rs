@@ -0,0 +1,210 @@
+use amethyst::{
+    assets::{Asset, Handle, ProcessingState},
+    core::Named,
+    ecs::prelude::{Component, DenseVecStorage, Join, ReadStorage, VecStorage, WriteStorage},
+    ui::UiText,
+    Error,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+use super::relations::{Attitude, Relationships, ATTITUDE_MAX};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Faction definitions which are read from an asset file.
+pub struct FactionsData {
+    pub factions: Vec<FactionDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FactionDefinition {
+    /// Identifier which characters use to refer to the faction.
+    pub id: String,
+    pub name: String,
+    /// Color of the glyphs of all faction members.
+    pub color: [f32; 4],
+    /// Campaign issues that the faction cares about.
+    #[serde(default)]
+    pub agenda: Vec<String>,
+}
+
+impl Asset for FactionsData {
+    const NAME: &'static str = "windy_city_politics::FactionsData";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+impl From<FactionsData> for Result<ProcessingState<FactionsData>, Error> {
+    fn from(data: FactionsData) -> Result<ProcessingState<FactionsData>, Error> {
+        Ok(ProcessingState::Loaded(data))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+/// Membership of a character in the faction with the given id.
+pub struct FactionMember(pub String);
+
+impl Component for FactionMember {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Default)]
+/// Use as a resource to look up faction definitions by their id.
+pub struct Factions(HashMap<String, FactionDefinition>);
+
+impl Factions {
+    pub fn new(data: FactionsData) -> Self {
+        Factions(
+            data.factions
+                .into_iter()
+                .map(|faction| (faction.id.clone(), faction))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, id: &str) -> Option<&FactionDefinition> {
+        self.0.get(id)
+    }
+
+    /// How well the agendas of two factions line up. Members of the same faction are
+    /// fully aligned at half of `ATTITUDE_MAX`, factions with no shared issues at minus
+    /// that value. Returns 0 if either faction is unknown.
+    pub fn alignment(&self, faction: &str, other: &str) -> i32 {
+        match (self.get(faction), self.get(other)) {
+            (Some(_), Some(_)) if faction == other => ATTITUDE_MAX / 2,
+            (Some(a), Some(b)) => get_agenda_alignment(&a.agenda, &b.agenda),
+            _ => 0,
+        }
+    }
+}
+
+/// Set up all faction members with their colors and alignments toward each other.
+/// Done once the characters of an area have been loaded.
+pub fn init_faction_members(
+    factions: &Factions,
+    members: &ReadStorage<FactionMember>,
+    names: &ReadStorage<Named>,
+    ui_texts: &mut WriteStorage<UiText>,
+    relationships: &mut WriteStorage<Relationships>,
+) {
+    color_faction_members(factions, members, ui_texts);
+    align_faction_members(factions, members, names, relationships);
+}
+
+fn color_faction_members(
+    factions: &Factions,
+    members: &ReadStorage<FactionMember>,
+    ui_texts: &mut WriteStorage<UiText>,
+) {
+    for (FactionMember(id), ui_text) in (members, ui_texts).join() {
+        if let Some(faction) = factions.get(id) {
+            ui_text.color = faction.color;
+        }
+    }
+}
+
+/// Set the alignment of faction members toward each other, unless the character
+/// prefab already specified an attitude toward the other character.
+fn align_faction_members(
+    factions: &Factions,
+    members: &ReadStorage<FactionMember>,
+    names: &ReadStorage<Named>,
+    relationships: &mut WriteStorage<Relationships>,
+) {
+    let all_members = (names, members)
+        .join()
+        .map(|(name, FactionMember(id))| (name.name.to_string(), id.clone()))
+        .collect::<Vec<_>>();
+
+    for (name, FactionMember(id), relationship) in (names, members, relationships).join() {
+        for (other_name, other_id) in all_members.iter().filter(|(other, _)| other != &name.name) {
+            relationship
+                .characters
+                .entry(other_name.clone())
+                .or_insert_with(|| Attitude {
+                    alignment: factions.alignment(id, other_id),
+                    ..Attitude::default()
+                });
+        }
+    }
+}
+
+/// Scale the number of shared issues between two agendas to the range
+/// [-`ATTITUDE_MAX` / 2, `ATTITUDE_MAX` / 2].
+fn get_agenda_alignment(agenda: &[String], other: &[String]) -> i32 {
+    let agenda = agenda.iter().collect::<HashSet<_>>();
+    let other = other.iter().collect::<HashSet<_>>();
+
+    let num_total = agenda.union(&other).count() as i32;
+    let num_shared = agenda.intersection(&other).count() as i32;
+
+    if num_total > 0 {
+        (2 * num_shared - num_total) * (ATTITUDE_MAX / 2) / num_total
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_faction(id: &str, agenda: &[&str]) -> FactionDefinition {
+        FactionDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            color: [1.0, 1.0, 1.0, 1.0],
+            agenda: agenda.iter().map(|issue| issue.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn factions_are_looked_up_by_id() {
+        let factions = Factions::new(FactionsData {
+            factions: vec![get_faction("machine", &[]), get_faction("reform", &[])],
+        });
+
+        assert_eq!(factions.get("machine").unwrap().id, "machine");
+        assert_eq!(factions.get("reform").unwrap().id, "reform");
+        assert!(factions.get("socialists").is_none());
+    }
+
+    #[test]
+    fn members_of_the_same_faction_are_aligned() {
+        let factions = Factions::new(FactionsData {
+            factions: vec![get_faction("machine", &[])],
+        });
+
+        assert_eq!(factions.alignment("machine", "machine"), ATTITUDE_MAX / 2);
+    }
+
+    #[test]
+    fn alignment_with_unknown_factions_is_neutral() {
+        let factions = Factions::new(FactionsData {
+            factions: vec![get_faction("machine", &["taxes"])],
+        });
+
+        assert_eq!(factions.alignment("machine", "socialists"), 0);
+        assert_eq!(factions.alignment("socialists", "socialists"), 0);
+    }
+
+    #[test]
+    fn alignment_between_factions_scales_with_shared_agenda() {
+        let factions = Factions::new(FactionsData {
+            factions: vec![
+                get_faction("a", &["taxes", "transit"]),
+                get_faction("b", &["taxes", "transit"]),
+                get_faction("c", &["taxes"]),
+                get_faction("d", &["schools"]),
+            ],
+        });
+
+        assert_eq!(factions.alignment("a", "b"), ATTITUDE_MAX / 2);
+        assert_eq!(factions.alignment("a", "c"), 0);
+        assert_eq!(factions.alignment("a", "d"), -ATTITUDE_MAX / 2);
+    }
+}
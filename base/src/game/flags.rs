@@ -0,0 +1,239 @@
+use inkling::{Story, Variable};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+/// Value of a flag, written as `true`, `3` or `"text"` in RON files.
+pub enum FlagValue {
+    Bool(bool),
+    Int(i32),
+    String(String),
+}
+
+impl FlagValue {
+    /// Whether the value is true, a number other than 0 or a text which is not empty.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            FlagValue::Bool(value) => *value,
+            FlagValue::Int(value) => *value != 0,
+            FlagValue::String(value) => !value.is_empty(),
+        }
+    }
+
+    fn from_variable(variable: Variable) -> Option<Self> {
+        match variable {
+            Variable::Bool(value) => Some(FlagValue::Bool(value)),
+            Variable::Int(value) => Some(FlagValue::Int(value)),
+            Variable::String(value) => Some(FlagValue::String(value)),
+            _ => None,
+        }
+    }
+
+    fn to_variable(&self) -> Variable {
+        match self {
+            FlagValue::Bool(value) => Variable::Bool(*value),
+            FlagValue::Int(value) => Variable::Int(*value),
+            FlagValue::String(value) => Variable::String(value.clone()),
+        }
+    }
+}
+
+impl From<bool> for FlagValue {
+    fn from(value: bool) -> Self {
+        FlagValue::Bool(value)
+    }
+}
+
+impl From<i32> for FlagValue {
+    fn from(value: i32) -> Self {
+        FlagValue::Int(value)
+    }
+}
+
+impl From<&str> for FlagValue {
+    fn from(value: &str) -> Self {
+        FlagValue::String(value.to_string())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Test of a flag, used by objectives and area triggers.
+pub enum FlagCondition {
+    /// The flag is truthy, see `FlagValue::is_truthy`.
+    IsSet(String),
+    /// The flag is not set or not truthy.
+    NotSet(String),
+    Equals(String, FlagValue),
+    /// The flag is a number which is at least the value.
+    AtLeast(String, i32),
+}
+
+impl FlagCondition {
+    /// Name of the tested flag.
+    pub fn name(&self) -> &str {
+        match self {
+            FlagCondition::IsSet(name)
+            | FlagCondition::NotSet(name)
+            | FlagCondition::Equals(name, _)
+            | FlagCondition::AtLeast(name, _) => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+/// Use as a resource to hold named story flags which are shared between the ink
+/// stories and the game.
+///
+/// Flags are set by scripted events and read by objectives and area triggers. Stories
+/// read and write the flags which they declare as variables (`VAR name = value`), see
+/// `write_to_story` and `read_from_story`. All flags are kept in save files.
+pub struct GameFlags {
+    flags: BTreeMap<String, FlagValue>,
+}
+
+impl GameFlags {
+    pub fn set<S: Into<String>, V: Into<FlagValue>>(&mut self, name: S, value: V) {
+        self.flags.insert(name.into(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FlagValue> {
+        self.flags.get(name)
+    }
+
+    /// Whether the flag is set to a truthy value.
+    pub fn is_set(&self, name: &str) -> bool {
+        self.get(name).map(FlagValue::is_truthy).unwrap_or(false)
+    }
+
+    pub fn test(&self, condition: &FlagCondition) -> bool {
+        match condition {
+            FlagCondition::IsSet(name) => self.is_set(name),
+            FlagCondition::NotSet(name) => !self.is_set(name),
+            FlagCondition::Equals(name, value) => self.get(name) == Some(value),
+            FlagCondition::AtLeast(name, value) => match self.get(name) {
+                Some(FlagValue::Int(flag)) => flag >= value,
+                _ => false,
+            },
+        }
+    }
+
+    /// Set the story variables of the given names to the flags of the same names,
+    /// for flags which have been set.
+    pub fn write_to_story(&self, story: &mut Story, names: &[String]) {
+        for name in names {
+            if let Some(value) = self.flags.get(name) {
+                if let Err(err) = story.set_variable(name, value.to_variable()) {
+                    log::warn!("could not set story variable '{}': {}", name, err);
+                }
+            }
+        }
+    }
+
+    /// Set the flags of the given names to the story variables of the same names.
+    pub fn read_from_story(&mut self, story: &Story, names: &[String]) {
+        for name in names {
+            match story.get_variable(name).map(FlagValue::from_variable) {
+                Ok(Some(value)) => self.set(name.as_str(), value),
+                Ok(None) => log::warn!("story variable '{}' can not be a flag", name),
+                Err(err) => log::warn!("could not read story variable '{}': {}", name, err),
+            }
+        }
+    }
+}
+
+/// Get the names of all global variables which are declared in an ink story.
+pub fn get_story_variable_names(ink: &str) -> Vec<String> {
+    ink.lines()
+        .filter_map(|line| {
+            let declaration = line.trim_start();
+
+            if !declaration.starts_with("VAR ") {
+                return None;
+            }
+
+            declaration[4..]
+                .split('=')
+                .next()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use inkling::read_story_from_string;
+
+    const STORY: &str = "
+VAR met_the_mayor = false
+VAR donations = 0
+
+Hello.
+";
+
+    #[test]
+    fn flag_conditions_test_set_values() {
+        let mut flags = GameFlags::default();
+        flags.set("met_the_mayor", true);
+        flags.set("donations", 3);
+        flags.set("rumor", "");
+
+        assert!(flags.test(&FlagCondition::IsSet("met_the_mayor".to_string())));
+        assert!(flags.test(&FlagCondition::NotSet("rumor".to_string())));
+        assert!(flags.test(&FlagCondition::NotSet("unknown".to_string())));
+        assert!(flags.test(&FlagCondition::Equals(
+            "donations".to_string(),
+            FlagValue::Int(3)
+        )));
+        assert!(flags.test(&FlagCondition::AtLeast("donations".to_string(), 2)));
+        assert!(!flags.test(&FlagCondition::AtLeast("donations".to_string(), 4)));
+        assert!(!flags.test(&FlagCondition::AtLeast("met_the_mayor".to_string(), 0)));
+    }
+
+    #[test]
+    fn flags_are_written_as_a_map_of_plain_values() {
+        let mut flags = GameFlags::default();
+        flags.set("donations", 3);
+        flags.set("met_the_mayor", true);
+        flags.set("mood", "angry");
+
+        let written = ron::ser::to_string(&flags).unwrap();
+        assert!(written.starts_with(r#"{"donations":3,"met_the_mayor":true,"mood":"angry""#));
+
+        let read: GameFlags = ron::de::from_str(&written).unwrap();
+        assert_eq!(read.get("mood"), Some(&FlagValue::from("angry")));
+    }
+
+    #[test]
+    fn story_variable_declarations_are_found() {
+        assert_eq!(
+            get_story_variable_names(STORY),
+            vec!["met_the_mayor".to_string(), "donations".to_string()]
+        );
+    }
+
+    #[test]
+    fn flags_are_shared_with_story_variables() {
+        let mut story = read_story_from_string(STORY).unwrap();
+        let names = get_story_variable_names(STORY);
+
+        let mut flags = GameFlags::default();
+        flags.set("donations", 5);
+        flags.write_to_story(&mut story, &names);
+
+        assert_eq!(story.get_variable("donations").unwrap(), Variable::Int(5));
+
+        story.set_variable("met_the_mayor", true).unwrap();
+        flags.read_from_story(&story, &names);
+
+        assert!(flags.is_set("met_the_mayor"));
+        assert_eq!(flags.get("donations"), Some(&FlagValue::Int(5)));
+    }
+}
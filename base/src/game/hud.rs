@@ -0,0 +1,109 @@
+use amethyst::{
+    ecs::Entity,
+    prelude::{Builder, World},
+    ui::{Anchor, LineMode, UiText, UiTransform},
+};
+
+use super::{
+    assets::Fonts,
+    consts::{HUD_FONT_SIZE, HUD_LAYER, HUD_MESSAGE_LINES},
+};
+
+/// Use as a resource to access the text entities of the heads-up display.
+pub struct Hud {
+    /// Shows the campaign calendar and polling numbers.
+    pub campaign: Entity,
+    /// Shows how the character next to the player regards them.
+    pub relationship: Entity,
+    /// Shows the latest messages from the message log.
+    pub messages: Entity,
+    /// Shows what is under the mouse cursor, next to it.
+    pub tooltip: Entity,
+}
+
+/// Create all heads-up display entities and add the `Hud` resource.
+///
+/// # Notes
+///  * Requires the `Fonts` resource to exist.
+pub fn create_hud(world: &mut World) {
+    let campaign = create_hud_line(world, "hud_campaign", 0);
+    let relationship = create_hud_line(world, "hud_relationship", 1);
+    let messages = create_hud_messages(world);
+    let tooltip = create_hud_tooltip(world);
+
+    world.add_resource(Hud {
+        campaign,
+        relationship,
+        messages,
+        tooltip,
+    });
+}
+
+/// Create an empty text entity at the given line from the top left corner of the screen.
+fn create_hud_line(world: &mut World, id: &str, line: usize) -> Entity {
+    let font = world.read_resource::<Fonts>().main.clone();
+
+    let mut text = UiText::new(font, String::new(), [1.0, 1.0, 1.0, 1.0], HUD_FONT_SIZE);
+    text.align = Anchor::MiddleLeft;
+
+    let line_height = HUD_FONT_SIZE + 4.0;
+
+    let transform = UiTransform::new(
+        id.to_string(),
+        Anchor::TopLeft,
+        Anchor::TopLeft,
+        10.0,
+        -10.0 - line_height * line as f32,
+        HUD_LAYER,
+        1000.0,
+        line_height,
+    );
+
+    world.create_entity().with(text).with(transform).build()
+}
+
+/// Create an empty text entity with room for several lines in the bottom left corner
+/// of the screen.
+fn create_hud_messages(world: &mut World) -> Entity {
+    let font = world.read_resource::<Fonts>().main.clone();
+
+    let mut text = UiText::new(font, String::new(), [1.0, 1.0, 1.0, 1.0], HUD_FONT_SIZE);
+    text.align = Anchor::BottomLeft;
+    text.line_mode = LineMode::Wrap;
+
+    let height = (HUD_FONT_SIZE + 4.0) * HUD_MESSAGE_LINES as f32;
+
+    let transform = UiTransform::new(
+        "hud_messages".to_string(),
+        Anchor::BottomLeft,
+        Anchor::BottomLeft,
+        10.0,
+        10.0,
+        HUD_LAYER,
+        1000.0,
+        height,
+    );
+
+    world.create_entity().with(text).with(transform).build()
+}
+
+/// Create an empty text entity which is moved along with the mouse cursor.
+fn create_hud_tooltip(world: &mut World) -> Entity {
+    let font = world.read_resource::<Fonts>().main.clone();
+
+    let mut text = UiText::new(font, String::new(), [1.0, 1.0, 0.6, 1.0], HUD_FONT_SIZE);
+    text.align = Anchor::MiddleLeft;
+
+    let transform = UiTransform::new(
+        "hud_tooltip".to_string(),
+        Anchor::BottomLeft,
+        Anchor::BottomLeft,
+        0.0,
+        0.0,
+        HUD_LAYER,
+        300.0,
+        HUD_FONT_SIZE + 4.0,
+    );
+
+    world.create_entity().with(text).with(transform).build()
+}
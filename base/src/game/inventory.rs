@@ -0,0 +1,74 @@
+use amethyst::{
+    ecs::{Entity, Join},
+    input::{is_key_down, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans},
+};
+
+use crate::menu::{get_menu_command, MenuCommand};
+
+use super::{character::PlayerCharacter, item::Inventory, screen::create_text_screen};
+
+#[derive(Default)]
+/// Lists everything that the player is carrying on top of the game.
+pub struct InventoryScreen {
+    entities: Vec<Entity>,
+}
+
+impl SimpleState for InventoryScreen {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        let lines = {
+            let inventories = world.read_storage::<Inventory>();
+            let characters = world.read_storage::<PlayerCharacter>();
+
+            (&inventories, &characters)
+                .join()
+                .next()
+                .map(|(inventory, _)| get_inventory_lines(inventory))
+                .unwrap_or_default()
+        };
+
+        self.entities = create_text_screen(world, "Inventory", &lines);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        data.world
+            .delete_entities(&self.entities)
+            .expect("could not delete inventory screen entities");
+        self.entities.clear();
+    }
+
+    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        if get_menu_command(&event) == Some(MenuCommand::Back) {
+            return Trans::Pop;
+        }
+
+        if let StateEvent::Window(event) = event {
+            if [VirtualKeyCode::I, VirtualKeyCode::Escape]
+                .iter()
+                .any(|&key| is_key_down(&event, key))
+            {
+                return Trans::Pop;
+            }
+        }
+
+        Trans::None
+    }
+}
+
+fn get_inventory_lines(inventory: &Inventory) -> Vec<String> {
+    let money = format!("Campaign cash: ${}", inventory.money);
+
+    let items = if inventory.items.is_empty() {
+        vec!["You are not carrying anything else.".to_string()]
+    } else {
+        inventory
+            .items
+            .iter()
+            .map(|item| item.to_string())
+            .collect()
+    };
+
+    std::iter::once(money).chain(items).collect()
+}
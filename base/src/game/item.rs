@@ -0,0 +1,148 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Something that a character can carry. As a component, an item lying on the ground
+/// which can be picked up.
+pub enum Item {
+    /// A document with political leverage, eg. a leaked memo.
+    Document { title: String },
+    /// A favor owed by the named character.
+    Favor { from: String },
+    /// Campaign cash in dollars.
+    Money(u32),
+}
+
+impl Component for Item {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Item::Document { title } => write!(f, "Document: {}", title),
+            Item::Favor { from } => write!(f, "Favor from {}", from),
+            Item::Money(amount) => write!(f, "${}", amount),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+/// All items carried by a character. Money is kept as a sum instead of separate items.
+pub struct Inventory {
+    pub money: u32,
+    pub items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Item) {
+        match item {
+            Item::Money(amount) => self.money += amount,
+            item => self.items.push(item),
+        }
+    }
+
+    /// Whether the inventory holds the item, or at least the amount of money.
+    pub fn contains(&self, item: &Item) -> bool {
+        match item {
+            Item::Money(amount) => self.money >= *amount,
+            item => self.items.contains(item),
+        }
+    }
+
+    /// Remove and return the item if it is in the inventory.
+    pub fn take(&mut self, item: &Item) -> Option<Item> {
+        match item {
+            Item::Money(amount) if self.money >= *amount => {
+                self.money -= amount;
+                Some(item.clone())
+            }
+            Item::Money(_) => None,
+            item => self
+                .items
+                .iter()
+                .position(|other| other == item)
+                .map(|index| self.items.remove(index)),
+        }
+    }
+}
+
+impl Component for Inventory {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Debug)]
+/// Event used to move an item from one character's `Inventory` to another's,
+/// eg. when the player hands something over in a dialogue.
+pub struct ItemTransferEvent {
+    pub from: Entity,
+    pub to: Entity,
+    pub item: Item,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_memo() -> Item {
+        Item::Document {
+            title: "Leaked memo".to_string(),
+        }
+    }
+
+    #[test]
+    fn money_is_added_to_the_sum_instead_of_the_items() {
+        let mut inventory = Inventory::default();
+
+        inventory.add(Item::Money(100));
+        inventory.add(Item::Money(50));
+        inventory.add(get_memo());
+
+        assert_eq!(inventory.money, 150);
+        assert_eq!(inventory.items, vec![get_memo()]);
+    }
+
+    #[test]
+    fn inventory_contains_money_up_to_the_sum() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Money(100));
+
+        assert!(inventory.contains(&Item::Money(0)));
+        assert!(inventory.contains(&Item::Money(100)));
+        assert!(!inventory.contains(&Item::Money(101)));
+        assert!(!inventory.contains(&get_memo()));
+    }
+
+    #[test]
+    fn taking_items_removes_them_only_if_they_exist() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Money(100));
+        inventory.add(get_memo());
+
+        assert_eq!(inventory.take(&Item::Money(200)), None);
+        assert_eq!(inventory.take(&Item::Money(60)), Some(Item::Money(60)));
+        assert_eq!(inventory.money, 40);
+
+        assert_eq!(inventory.take(&get_memo()), Some(get_memo()));
+        assert_eq!(inventory.take(&get_memo()), None);
+        assert!(inventory.items.is_empty());
+    }
+
+    #[test]
+    fn items_are_displayed_by_kind() {
+        assert_eq!(get_memo().to_string(), "Document: Leaked memo");
+        assert_eq!(
+            Item::Favor {
+                from: "Rob".to_string()
+            }
+            .to_string(),
+            "Favor from Rob"
+        );
+        assert_eq!(Item::Money(500).to_string(), "$500");
+    }
+}
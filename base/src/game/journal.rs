@@ -0,0 +1,60 @@
+use amethyst::{
+    ecs::Entity,
+    input::{is_key_down, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans},
+};
+
+use crate::menu::{get_menu_command, MenuCommand};
+
+use super::{objective::Objectives, screen::create_text_screen};
+
+#[derive(Default)]
+/// Lists all active and completed objectives on top of the game.
+pub struct JournalScreen {
+    entities: Vec<Entity>,
+}
+
+impl SimpleState for JournalScreen {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        let lines = get_journal_lines(&world.read_resource::<Objectives>());
+        self.entities = create_text_screen(world, "Journal", &lines);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        data.world
+            .delete_entities(&self.entities)
+            .expect("could not delete journal screen entities");
+        self.entities.clear();
+    }
+
+    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        if get_menu_command(&event) == Some(MenuCommand::Back) {
+            return Trans::Pop;
+        }
+
+        if let StateEvent::Window(event) = event {
+            if [VirtualKeyCode::J, VirtualKeyCode::Escape]
+                .iter()
+                .any(|&key| is_key_down(&event, key))
+            {
+                return Trans::Pop;
+            }
+        }
+
+        Trans::None
+    }
+}
+
+fn get_journal_lines(objectives: &Objectives) -> Vec<String> {
+    let active = objectives
+        .active()
+        .map(|objective| format!("[ ] {}", objective.description));
+
+    let completed = objectives
+        .completed()
+        .map(|objective| format!("[x] {}", objective.description));
+
+    active.chain(completed).collect()
+}
@@ -0,0 +1,172 @@
+use amethyst::{
+    ecs::prelude::{Component, DenseVecStorage, Entity},
+    ui::{Anchor, FontHandle, UiImage, UiText, UiTransform},
+};
+
+use super::consts::{
+    BARK_DURATION, BARK_FADE_DURATION, BARK_INTERVAL, LABEL_FONT_SIZE, LABEL_LAYER,
+};
+
+const NAME_LABEL_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+const SPEECH_BUBBLE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const SPEECH_BUBBLE_BACKGROUND_COLOR: [f32; 4] = [0.018, 0.018, 0.018, 0.85];
+
+/// Width of a name label, which is wide enough for any name (in pixels).
+const NAME_LABEL_WIDTH: f32 = 200.0;
+/// Rough width of a character of label text, to fit speech bubbles to their line.
+const LABEL_CHARACTER_WIDTH: f32 = LABEL_FONT_SIZE * 0.6;
+/// Room around the line of a speech bubble (in pixels).
+const SPEECH_BUBBLE_PADDING: f32 = 8.0;
+
+#[derive(Clone, Debug)]
+/// Lines of ambient chatter that a character says now and then in a speech bubble
+/// while the player is nearby. Lines which are keys (`@key`) are translated.
+pub struct Barks {
+    pub lines: Vec<String>,
+    /// Time until the next bark (in seconds).
+    pub cooldown: f32,
+}
+
+impl Barks {
+    /// Characters wait the shortest interval before their first bark, so that not
+    /// everyone talks at once when an area is entered.
+    pub fn new(lines: Vec<String>) -> Self {
+        Barks {
+            lines,
+            cooldown: BARK_INTERVAL.0,
+        }
+    }
+}
+
+impl Component for Barks {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Text entity which shows the name of a character above its glyph.
+pub struct NameLabel {
+    pub character: Entity,
+}
+
+impl Component for NameLabel {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Text entity which shows a bark of a character above its glyph until it has faded.
+pub struct SpeechBubble {
+    pub character: Entity,
+    /// Time since the bubble appeared (in seconds).
+    pub age: f32,
+}
+
+impl SpeechBubble {
+    pub fn new(character: Entity) -> Self {
+        SpeechBubble {
+            character,
+            age: 0.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.age >= BARK_DURATION
+    }
+
+    pub fn alpha(&self) -> f32 {
+        get_bark_alpha(self.age)
+    }
+}
+
+impl Component for SpeechBubble {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Get the opacity of a speech bubble of the given age, which fades out at the end
+/// of its duration.
+pub fn get_bark_alpha(age: f32) -> f32 {
+    let fade_start = BARK_DURATION - BARK_FADE_DURATION;
+
+    if age <= fade_start {
+        1.0
+    } else {
+        ((BARK_DURATION - age) / BARK_FADE_DURATION).max(0.0)
+    }
+}
+
+/// Get the text of a name label.
+pub fn get_name_label_text(font: FontHandle, name: &str) -> UiText {
+    UiText::new(font, name.to_string(), NAME_LABEL_COLOR, LABEL_FONT_SIZE)
+}
+
+/// Get the transform of a name label. It is placed above its character by the
+/// `NameLabelSystem`.
+pub fn get_name_label_transform() -> UiTransform {
+    get_label_transform("name_label", NAME_LABEL_WIDTH)
+}
+
+/// Get the text and background of a speech bubble.
+pub fn get_speech_bubble_text(font: FontHandle, line: &str) -> (UiText, UiImage) {
+    (
+        UiText::new(font, line.to_string(), SPEECH_BUBBLE_COLOR, LABEL_FONT_SIZE),
+        UiImage::SolidColor(SPEECH_BUBBLE_BACKGROUND_COLOR),
+    )
+}
+
+/// Get the transform of a speech bubble, wide enough for its line. It is placed
+/// above its character by the `BarkSystem`.
+pub fn get_speech_bubble_transform(line: &str) -> UiTransform {
+    let width = line.chars().count() as f32 * LABEL_CHARACTER_WIDTH + 2.0 * SPEECH_BUBBLE_PADDING;
+
+    get_label_transform("speech_bubble", width)
+}
+
+/// Set the opacity of a speech bubble, keeping the color of its text and background.
+pub fn set_speech_bubble_alpha(text: &mut UiText, background: &mut UiImage, alpha: f32) {
+    text.color[3] = SPEECH_BUBBLE_COLOR[3] * alpha;
+
+    if let UiImage::SolidColor(ref mut color) = background {
+        color[3] = SPEECH_BUBBLE_BACKGROUND_COLOR[3] * alpha;
+    }
+}
+
+/// Placed in screen-absolute coordinates from the lower left corner, like glyphs.
+fn get_label_transform(id: &str, width: f32) -> UiTransform {
+    UiTransform::new(
+        id.to_string(),
+        Anchor::BottomLeft,
+        Anchor::Middle,
+        0.0,
+        0.0,
+        LABEL_LAYER,
+        width,
+        LABEL_FONT_SIZE + 4.0,
+    )
+}
+
+/// Get the coordinates of a label which is shown some rows above the glyph of its
+/// character at the given coordinates, for tiles of the given height.
+pub fn get_label_coordinates((x, y): (f32, f32), rows: f32, tile_height: f32) -> (f32, f32) {
+    (x, y + rows * tile_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speech_bubbles_fade_out_at_the_end_of_their_duration() {
+        assert_eq!(get_bark_alpha(0.0), 1.0);
+        assert_eq!(get_bark_alpha(BARK_DURATION - BARK_FADE_DURATION), 1.0);
+        assert!((get_bark_alpha(BARK_DURATION - BARK_FADE_DURATION / 2.0) - 0.5).abs() < 1e-6);
+        assert_eq!(get_bark_alpha(BARK_DURATION + 1.0), 0.0);
+    }
+
+    #[test]
+    fn labels_are_placed_rows_above_their_character() {
+        assert_eq!(get_label_coordinates((40.0, 60.0), 1.0, 24.0), (40.0, 84.0));
+        assert_eq!(
+            get_label_coordinates((40.0, 60.0), 1.5, 48.0),
+            (40.0, 132.0)
+        );
+    }
+}
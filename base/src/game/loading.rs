@@ -1,30 +1,48 @@
 use amethyst::{
-    assets::{Completion, Handle, Prefab, PrefabLoader, ProgressCounter, RonFormat},
-    core::{ArcThreadPool, SystemBundle, Transform},
+    assets::{
+        AssetStorage, Completion, Handle, Loader, Prefab, PrefabLoader, Progress, ProgressCounter,
+        RonFormat,
+    },
+    core::{ArcThreadPool, Named, SystemBundle, Transform},
     ecs::Join,
     prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, Trans, World},
     renderer::{ActiveCamera, Camera},
     shred::{Dispatcher, DispatcherBuilder},
+    ui::UiText,
     window::ScreenDimensions,
 };
 
 use std::borrow::BorrowMut;
 
 use super::{
-    area::{ActiveArea, Area, Position},
+    area::{ActiveArea, ActiveAreaMap, Position},
     assets::load_fonts,
     bundle::PrefabLoaderBundle,
+    campaign::{Campaign, CampaignData},
     character::{CharacterPrefab, PlayerCharacter},
-    consts::CAMERA_POSITION_Z,
+    consts::{CAMERA_POSITION_Z, START_AREA_MAP},
+    district::DistrictFormat,
+    faction::{init_faction_members, FactionMember, Factions, FactionsData},
+    map::{AreaMapFormat, AreaMapPrefab},
+    objective::{Objectives, ObjectivesData},
+    relations::Relationships,
     state::Regular,
+    tiled::TiledMapFormat,
 };
 
 pub struct PrefabLoaderHandles {
-    pub area: Handle<Prefab<Area>>,
-    pub character: Handle<Prefab<CharacterPrefab>>,
+    pub area: Handle<Prefab<AreaMapPrefab>>,
     pub player_character: Handle<Prefab<CharacterPrefab>>,
 }
 
+/// Handles to game data which is read from asset files and turned into resources
+/// once loading has finished.
+pub struct DataHandles {
+    pub campaign: Handle<CampaignData>,
+    pub factions: Handle<FactionsData>,
+    pub objectives: Handle<ObjectivesData>,
+}
+
 /// Load all required assets and prefabs, then set up all components
 /// and switch to the game state.
 pub struct Loading<'a, 'b> {
@@ -52,6 +70,7 @@ impl<'a, 'b> SimpleState for Loading<'a, 'b> {
         load_fonts(world, progress);
 
         setup_prefab_loaders(world, progress);
+        load_game_data(world, progress);
 
         load_area_entities(world);
         load_player_character_entity(world);
@@ -60,6 +79,9 @@ impl<'a, 'b> SimpleState for Loading<'a, 'b> {
     fn on_stop(&mut self, data: StateData<GameData>) {
         let world = data.world;
         init_camera(world);
+        init_campaign(world);
+        init_factions(world);
+        init_objectives(world);
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
@@ -130,17 +152,93 @@ fn init_camera(world: &mut World) {
     };
 }
 
-fn load_area_entities(world: &mut World) {
-    let character_handle = world
-        .read_resource::<PrefabLoaderHandles>()
-        .character
-        .clone();
-    world.create_entity().with(character_handle).build();
+fn init_campaign(world: &mut World) {
+    let campaign = {
+        let handle = world.read_resource::<DataHandles>().campaign.clone();
+        let store = world.read_resource::<AssetStorage<CampaignData>>();
 
+        store
+            .get(&handle)
+            .cloned()
+            .map(Campaign::new)
+            .expect("campaign data was not loaded")
+    };
+
+    world.add_resource(campaign);
+}
+
+/// Add the `Factions` resource and set up all faction members with their colors and
+/// alignments toward each other.
+///
+/// # Panics
+///  * If any character is a member of a faction which has not been defined.
+fn init_factions(world: &mut World) {
+    let factions = {
+        let handle = world.read_resource::<DataHandles>().factions.clone();
+        let store = world.read_resource::<AssetStorage<FactionsData>>();
+
+        store
+            .get(&handle)
+            .cloned()
+            .map(Factions::new)
+            .expect("faction data was not loaded")
+    };
+
+    validate_faction_members(world, &factions);
+    init_faction_members(
+        &factions,
+        &world.read_storage::<FactionMember>(),
+        &world.read_storage::<Named>(),
+        &mut world.write_storage::<UiText>(),
+        &mut world.write_storage::<Relationships>(),
+    );
+
+    world.add_resource(factions);
+}
+
+fn validate_faction_members(world: &World, factions: &Factions) {
+    let members = world.read_storage::<FactionMember>();
+
+    let mut unknown = (&members)
+        .join()
+        .filter(|FactionMember(id)| factions.get(id).is_none())
+        .map(|FactionMember(id)| format!("'{}'", id))
+        .collect::<Vec<_>>();
+
+    unknown.sort();
+    unknown.dedup();
+
+    if !unknown.is_empty() {
+        panic!(
+            "characters are members of undefined factions: {}",
+            unknown.join(", ")
+        );
+    }
+}
+
+fn init_objectives(world: &mut World) {
+    let objectives = {
+        let handle = world.read_resource::<DataHandles>().objectives.clone();
+        let store = world.read_resource::<AssetStorage<ObjectivesData>>();
+
+        store
+            .get(&handle)
+            .cloned()
+            .map(Objectives::new)
+            .expect("objective data was not loaded")
+    };
+
+    world.add_resource(objectives);
+}
+
+/// Create the entity for the area map. Its prefab adds the `Area` to that entity
+/// along with separate entities for everything that is drawn in the map.
+fn load_area_entities(world: &mut World) {
     let area_handle = world.read_resource::<PrefabLoaderHandles>().area.clone();
     let area_entity = world.create_entity().with(area_handle).build();
 
     world.add_resource(ActiveArea(area_entity));
+    world.add_resource(ActiveAreaMap(START_AREA_MAP.to_string()));
 }
 
 fn load_player_character_entity(world: &mut World) {
@@ -152,14 +250,27 @@ fn load_player_character_entity(world: &mut World) {
     world.create_entity().with(handle).build();
 }
 
+/// Load an area map, district or map made in Tiled (relative to the assets
+/// directory) with the format that its path calls for.
+pub fn load_area_map<P: Progress>(
+    path: &str,
+    loader: &PrefabLoader<'_, AreaMapPrefab>,
+    progress: P,
+) -> Handle<Prefab<AreaMapPrefab>> {
+    // Maps made in Tiled are exported as JSON
+    if path.ends_with(".json") {
+        loader.load(path, TiledMapFormat, progress)
+    } else if path.starts_with("district/") {
+        loader.load(path, DistrictFormat, progress)
+    } else {
+        loader.load(path, AreaMapFormat, progress)
+    }
+}
+
 fn setup_prefab_loaders(world: &mut World, progress: &mut ProgressCounter) {
     let handles = {
-        let area = world.exec(|loader: PrefabLoader<'_, Area>| {
-            loader.load("prefab/area.ron", RonFormat, progress.borrow_mut())
-        });
-
-        let character = world.exec(|loader: PrefabLoader<'_, CharacterPrefab>| {
-            loader.load("prefab/character.ron", RonFormat, progress.borrow_mut())
+        let area = world.exec(|loader: PrefabLoader<'_, AreaMapPrefab>| {
+            load_area_map(START_AREA_MAP, &loader, progress.borrow_mut())
         });
 
         let player_character = world.exec(|loader: PrefabLoader<'_, CharacterPrefab>| {
@@ -172,10 +283,44 @@ fn setup_prefab_loaders(world: &mut World, progress: &mut ProgressCounter) {
 
         PrefabLoaderHandles {
             area,
-            character,
             player_character,
         }
     };
 
     world.add_resource(handles);
 }
+
+fn load_game_data(world: &mut World, progress: &mut ProgressCounter) {
+    let handles = {
+        let loader = world.read_resource::<Loader>();
+
+        let campaign = loader.load(
+            "campaign/campaign.ron",
+            RonFormat,
+            progress.borrow_mut(),
+            &world.read_resource::<AssetStorage<CampaignData>>(),
+        );
+
+        let factions = loader.load(
+            "factions/factions.ron",
+            RonFormat,
+            progress.borrow_mut(),
+            &world.read_resource::<AssetStorage<FactionsData>>(),
+        );
+
+        let objectives = loader.load(
+            "objectives/objectives.ron",
+            RonFormat,
+            progress.borrow_mut(),
+            &world.read_resource::<AssetStorage<ObjectivesData>>(),
+        );
+
+        DataHandles {
+            campaign,
+            factions,
+            objectives,
+        }
+    };
+
+    world.add_resource(handles);
+}
@@ -0,0 +1,517 @@
+use amethyst::{
+    assets::{Format, Prefab, PrefabData},
+    ecs::prelude::{Entity, WriteStorage},
+    Error,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::{collections::HashMap, fmt};
+
+use super::{
+    area::{Area, Collision, Position},
+    character::{get_base_ui_text, get_base_ui_transform, CharacterPrefab, Glyph},
+    consts::{BACKGROUND_SPRITE_LAYER, ITEM_SPRITE_LAYER},
+    item::Item,
+    script::AreaTrigger,
+};
+
+const WALL_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+const PROP_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
+/// Glyphs which are floor unless the legend says otherwise.
+const FLOOR_GLYPHS: [char; 2] = [' ', '.'];
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// An area which is drawn as a map of glyphs, read by `AreaMapFormat`.
+///
+/// Every glyph in the map is looked up in the legend to see what is at that tile.
+/// The map starts on the line after its opening quote and every row of it starts
+/// in the first column. Rows may be of different lengths, the area is as wide as
+/// the longest row.
+pub struct AreaMap {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub legend: HashMap<char, LegendEntry>,
+    pub map: String,
+    /// Regions which run scripted actions, see `AreaTrigger`.
+    #[serde(default)]
+    pub triggers: Vec<AreaTrigger>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// What a glyph in an `AreaMap` stands for.
+pub enum LegendEntry {
+    /// Empty tile. Spaces and `.` are floor if they are not in the legend.
+    Floor,
+    /// Tile which blocks movement, shown as the glyph.
+    Wall,
+    /// Furniture or other decoration shown as the glyph.
+    Prop {
+        #[serde(default)]
+        collision: bool,
+    },
+    /// Character which is placed at the tile. The character is shown as the
+    /// glyph of its prefab and any position in the prefab is ignored.
+    Character(CharacterPrefab),
+    /// Item lying on the tile, shown as the given glyph or else as the map glyph.
+    Item {
+        item: Item,
+        #[serde(default)]
+        glyph: Option<char>,
+    },
+}
+
+#[derive(Clone, Debug)]
+/// Prefab data for the area and all entities of an `AreaMap`.
+///
+/// The area is the main entity of the prefab, so it is added to the entity which
+/// holds the prefab handle.
+pub enum AreaMapPrefab {
+    Area(Area),
+    /// Wall or prop.
+    Tile {
+        glyph: char,
+        position: Position,
+        collision: bool,
+    },
+    Character(CharacterPrefab),
+    Item {
+        glyph: char,
+        position: Position,
+        item: Item,
+    },
+    Trigger(AreaTrigger),
+}
+
+impl AreaMapPrefab {
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            AreaMapPrefab::Area(_) | AreaMapPrefab::Trigger(_) => None,
+            AreaMapPrefab::Tile { position, .. } | AreaMapPrefab::Item { position, .. } => {
+                Some(position.clone())
+            }
+            AreaMapPrefab::Character(character) => Some(character.position()),
+        }
+    }
+}
+
+/// Give the triggers of an area map their ids in the order they are declared, so
+/// that they can be told apart when the area is loaded again.
+pub fn number_triggers(data: &mut [AreaMapPrefab]) {
+    let triggers = data.iter_mut().filter_map(|entity| match entity {
+        AreaMapPrefab::Trigger(trigger) => Some(trigger),
+        _ => None,
+    });
+
+    for (id, trigger) in triggers.enumerate() {
+        trigger.set_id(id);
+    }
+}
+
+/// Create the prefab of an area map with the area as its main entity, from the
+/// prefab data of any format. The area is always the first entity of the data.
+pub fn get_area_map_prefab(data: Vec<AreaMapPrefab>) -> Prefab<AreaMapPrefab> {
+    let mut data = data.into_iter();
+
+    let mut prefab = Prefab::new_main(data.next().expect("area is always the first entity"));
+
+    for entity in data {
+        prefab.add(None, Some(entity));
+    }
+
+    prefab
+}
+
+/// Derive and add all required Components from the prefab when loading from a `PrefabLoader`.
+///
+/// For `Area`:
+///  * `Area`
+///
+/// For `Tile` and `Item`:
+///  * `Glyph`
+///  * `Position`
+///  * `UiText`         for rendering the tile or item as the given glyph
+///  * `UiTransform`    coordinates are not set, that's up to the rendering system
+///  * `Collision`      only for tiles which block movement
+///  * `Item`           only for items
+///
+/// For `Trigger`:
+///  * `AreaTrigger`
+///
+/// For `Character`, see `CharacterPrefab`.
+///
+/// # Notes
+///  * Requires the `Fonts` resource to exist.
+impl<'a> PrefabData<'a> for AreaMapPrefab {
+    type SystemData = (
+        <CharacterPrefab as PrefabData<'a>>::SystemData,
+        WriteStorage<'a, Area>,
+        WriteStorage<'a, Item>,
+        WriteStorage<'a, AreaTrigger>,
+    );
+
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        data: &mut Self::SystemData,
+        entities: &[Entity],
+        children: &[Entity],
+    ) -> Result<Self::Result, Error> {
+        let (character_data, areas, items, triggers) = data;
+
+        match self {
+            AreaMapPrefab::Area(area) => {
+                areas.insert(entity, area.clone())?;
+            }
+            AreaMapPrefab::Character(character) => {
+                character.add_to_entity(entity, character_data, entities, children)?;
+            }
+            AreaMapPrefab::Trigger(trigger) => {
+                triggers.insert(entity, trigger.clone())?;
+            }
+            AreaMapPrefab::Tile {
+                glyph,
+                position,
+                collision,
+            } => {
+                let (positions, collisions, glyphs, .., ui_texts, ui_transforms, fonts, zoom) =
+                    character_data;
+
+                let mut ui_text = get_base_ui_text(*glyph, fonts.main.clone(), zoom);
+                ui_text.color = if *collision { WALL_COLOR } else { PROP_COLOR };
+
+                positions.insert(entity, position.clone())?;
+                glyphs.insert(entity, Glyph(*glyph))?;
+                ui_texts.insert(entity, ui_text)?;
+                ui_transforms.insert(
+                    entity,
+                    get_base_ui_transform("tile", BACKGROUND_SPRITE_LAYER, zoom),
+                )?;
+
+                if *collision {
+                    collisions.insert(entity, Collision)?;
+                }
+            }
+            AreaMapPrefab::Item {
+                glyph,
+                position,
+                item,
+            } => {
+                let (positions, _, glyphs, .., ui_texts, ui_transforms, fonts, zoom) =
+                    character_data;
+
+                positions.insert(entity, position.clone())?;
+                glyphs.insert(entity, Glyph(*glyph))?;
+                items.insert(entity, item.clone())?;
+                ui_texts.insert(entity, get_base_ui_text(*glyph, fonts.main.clone(), zoom))?;
+                ui_transforms.insert(
+                    entity,
+                    get_base_ui_transform("item", ITEM_SPRITE_LAYER, zoom),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A glyph in the map which is not in the legend.
+pub struct UnknownGlyph {
+    pub glyph: char,
+    /// Row of the map from the top, starting from 0.
+    pub row: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for UnknownGlyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' in row {}, column {} of the map is not in the legend",
+            self.glyph,
+            self.row + 1,
+            self.column + 1
+        )
+    }
+}
+
+impl AreaMap {
+    /// Rows of the map from the top, without the line breaks around the map.
+    fn rows(&self) -> Vec<&str> {
+        let map = self.map.trim_start_matches(|c| c == '\r' || c == '\n');
+        let mut rows = map.lines().collect::<Vec<_>>();
+
+        while rows
+            .last()
+            .map(|row| row.trim().is_empty())
+            .unwrap_or(false)
+        {
+            rows.pop();
+        }
+
+        rows
+    }
+
+    /// Number of tiles along x and y.
+    pub fn dimensions(&self) -> [u32; 2] {
+        let rows = self.rows();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        [width as u32, rows.len() as u32]
+    }
+
+    /// Get the area, all entities which are drawn in the map and the triggers, or
+    /// an error for the first glyph that is not in the legend.
+    pub fn get_prefab_data(&self) -> Result<Vec<AreaMapPrefab>, UnknownGlyph> {
+        let rows = self.rows();
+        let [nx, ny] = self.dimensions();
+
+        let area = AreaMapPrefab::Area(Area {
+            name: self.name.clone(),
+            dimensions: [nx, ny],
+        });
+
+        let mut data = vec![area];
+
+        for (row, line) in rows.iter().enumerate() {
+            for (x, glyph) in line.chars().enumerate() {
+                let position = Position {
+                    x: x as u32,
+                    y: ny - 1 - row as u32,
+                };
+
+                let entry = match self.legend.get(&glyph) {
+                    Some(entry) => entry,
+                    None if FLOOR_GLYPHS.contains(&glyph) => &LegendEntry::Floor,
+                    None => {
+                        return Err(UnknownGlyph {
+                            glyph,
+                            row,
+                            column: x,
+                        });
+                    }
+                };
+
+                let tile = match entry {
+                    LegendEntry::Floor => None,
+                    LegendEntry::Wall => Some(AreaMapPrefab::Tile {
+                        glyph,
+                        position,
+                        collision: true,
+                    }),
+                    LegendEntry::Prop { collision } => Some(AreaMapPrefab::Tile {
+                        glyph,
+                        position,
+                        collision: *collision,
+                    }),
+                    LegendEntry::Character(character) => {
+                        Some(AreaMapPrefab::Character(character.with_position(position)))
+                    }
+                    LegendEntry::Item { item, glyph: shown } => Some(AreaMapPrefab::Item {
+                        glyph: shown.unwrap_or(glyph),
+                        position,
+                        item: item.clone(),
+                    }),
+                };
+
+                data.extend(tile);
+            }
+        }
+
+        data.extend(self.triggers.iter().cloned().map(AreaMapPrefab::Trigger));
+        number_triggers(&mut data);
+
+        Ok(data)
+    }
+
+    /// Create the prefab with the area as its main entity.
+    pub fn to_prefab(&self) -> Result<Prefab<AreaMapPrefab>, UnknownGlyph> {
+        self.get_prefab_data().map(get_area_map_prefab)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Format for reading an `AreaMap` from a RON file as a prefab.
+pub struct AreaMapFormat;
+
+impl Format<Prefab<AreaMapPrefab>> for AreaMapFormat {
+    fn name(&self) -> &'static str {
+        "AreaMap"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<Prefab<AreaMapPrefab>, Error> {
+        let area_map: AreaMap = ron::de::from_bytes(&bytes)
+            .map_err(|err| Error::from_string(format!("could not parse area map: {}", err)))?;
+
+        area_map
+            .to_prefab()
+            .map_err(|err| Error::from_string(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::script::{TriggerOn, TriggerRegion};
+
+    fn get_area_map(map: &str) -> AreaMap {
+        let mut legend = HashMap::new();
+        legend.insert('#', LegendEntry::Wall);
+        legend.insert('h', LegendEntry::Prop { collision: false });
+        legend.insert(
+            '$',
+            LegendEntry::Item {
+                item: Item::Money(10),
+                glyph: None,
+            },
+        );
+
+        AreaMap {
+            name: "City Hall".to_string(),
+            legend,
+            map: map.to_string(),
+            triggers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dimensions_are_from_the_longest_row_without_surrounding_line_breaks() {
+        let area_map = get_area_map("\n####\n#.\n#..#\n    ");
+
+        assert_eq!(area_map.dimensions(), [4, 3]);
+    }
+
+    #[test]
+    fn first_entity_is_the_area() {
+        let area_map = get_area_map("\n...\n...\n");
+
+        match &area_map.get_prefab_data().unwrap()[..] {
+            [AreaMapPrefab::Area(area)] => {
+                assert_eq!(area.name, "City Hall");
+                assert_eq!(area.dimensions, [3, 2]);
+            }
+            data => panic!("expected only the area, got {:?}", data),
+        }
+    }
+
+    #[test]
+    fn tiles_are_placed_with_the_top_row_last() {
+        let area_map = get_area_map("\n#h\n.$\n");
+        let data = area_map.get_prefab_data().unwrap();
+
+        let positions = data
+            .iter()
+            .filter_map(AreaMapPrefab::position)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            positions,
+            vec![
+                Position { x: 0, y: 1 },
+                Position { x: 1, y: 1 },
+                Position { x: 1, y: 0 },
+            ]
+        );
+
+        match &data[1] {
+            AreaMapPrefab::Tile {
+                glyph, collision, ..
+            } => {
+                assert_eq!(*glyph, '#');
+                assert!(*collision);
+            }
+            other => panic!("expected a wall, got {:?}", other),
+        }
+
+        match &data[2] {
+            AreaMapPrefab::Tile { collision, .. } => assert!(!*collision),
+            other => panic!("expected a prop, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn triggers_are_numbered_in_the_order_they_are_declared() {
+        let mut area_map = get_area_map("\n..\n");
+        let trigger = AreaTrigger::new(
+            TriggerRegion::Tiles(vec![Position { x: 0, y: 0 }]),
+            TriggerOn::Enter,
+            Vec::new(),
+            true,
+            Vec::new(),
+        );
+        area_map.triggers = vec![trigger.clone(), trigger];
+
+        let ids = area_map
+            .get_prefab_data()
+            .unwrap()
+            .iter()
+            .filter_map(|entity| match entity {
+                AreaMapPrefab::Trigger(trigger) => Some(trigger.id()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn glyphs_which_are_not_in_the_legend_are_errors() {
+        let area_map = get_area_map("\n##\n#x\n");
+
+        let err = area_map.get_prefab_data().unwrap_err();
+
+        assert_eq!(
+            err,
+            UnknownGlyph {
+                glyph: 'x',
+                row: 1,
+                column: 1
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "'x' in row 2, column 2 of the map is not in the legend"
+        );
+    }
+
+    #[test]
+    fn area_maps_are_read_from_ron() {
+        let source = r#"(
+    name: "Lobby",
+    legend: {
+        '#': Wall,
+        '1': Item(item: Money(5), glyph: Some('$')),
+    },
+    map: "
+###
+#1
+",
+)"#;
+
+        let area_map: AreaMap = ron::de::from_str(source).unwrap();
+
+        assert_eq!(area_map.dimensions(), [3, 2]);
+        let data = area_map.get_prefab_data().unwrap();
+        assert_eq!(data.len(), 1 + 4 + 1);
+
+        match &data[5] {
+            AreaMapPrefab::Item { glyph, item, .. } => {
+                assert_eq!(*glyph, '$');
+                assert_eq!(*item, Item::Money(5));
+            }
+            other => panic!("expected an item, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+
+/// Number of messages that are kept in the log before the oldest are dropped.
+const MESSAGE_LOG_CAPACITY: usize = 100;
+
+#[derive(Debug, Default)]
+/// Use as a resource to collect messages about what happens in the game for the player.
+pub struct MessageLog {
+    messages: VecDeque<String>,
+}
+
+impl MessageLog {
+    pub fn push<S: Into<String>>(&mut self, message: S) {
+        if self.messages.len() >= MESSAGE_LOG_CAPACITY {
+            self.messages.pop_front();
+        }
+
+        self.messages.push_back(message.into());
+    }
+
+    /// Up to `num` of the most recent messages, oldest first.
+    pub fn latest(&self, num: usize) -> impl Iterator<Item = &String> {
+        self.messages
+            .iter()
+            .skip(self.messages.len().saturating_sub(num))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_messages_are_returned_oldest_first() {
+        let mut log = MessageLog::default();
+
+        log.push("first");
+        log.push("second");
+        log.push("third");
+
+        assert_eq!(
+            log.latest(2).cloned().collect::<Vec<_>>(),
+            vec!["second".to_string(), "third".to_string()]
+        );
+        assert_eq!(log.latest(10).count(), 3);
+    }
+
+    #[test]
+    fn oldest_messages_are_dropped_when_the_log_is_full() {
+        let mut log = MessageLog::default();
+
+        for i in 0..=MESSAGE_LOG_CAPACITY {
+            log.push(i.to_string());
+        }
+
+        assert_eq!(
+            log.latest(MESSAGE_LOG_CAPACITY + 1).count(),
+            MESSAGE_LOG_CAPACITY
+        );
+        assert_eq!(log.latest(MESSAGE_LOG_CAPACITY).next().unwrap(), "1");
+    }
+}
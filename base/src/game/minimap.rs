@@ -0,0 +1,201 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::Hidden,
+    ecs::Entity,
+    prelude::{Builder, World},
+    renderer::Texture,
+    ui::{Anchor, UiImage, UiTransform},
+};
+
+use crate::texture::create_texture;
+
+use super::{
+    area::{ActiveArea, Area, Position},
+    consts::{MINIMAP_LAYER, MINIMAP_TILE_SIZE},
+};
+
+const MINIMAP_FLOOR_COLOR: [u8; 4] = [30, 30, 30, 255];
+const MINIMAP_WALL_COLOR: [u8; 4] = [150, 150, 150, 255];
+const MINIMAP_CHARACTER_COLOR: [u8; 4] = [255, 255, 255, 255];
+const MINIMAP_PLAYER_COLOR: [u8; 4] = [255, 220, 0, 255];
+
+/// Use as a resource to access the minimap entity.
+pub struct Minimap {
+    pub entity: Entity,
+    pub is_visible: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// What is shown on a minimap tile, in increasing order of precedence.
+pub enum MinimapTile {
+    Wall,
+    /// A non-player character with the color of its faction, if any.
+    Character(Option<[f32; 4]>),
+    Player,
+}
+
+impl MinimapTile {
+    fn precedence(&self) -> u8 {
+        match self {
+            MinimapTile::Wall => 0,
+            MinimapTile::Character(_) => 1,
+            MinimapTile::Player => 2,
+        }
+    }
+
+    fn color(&self) -> [u8; 4] {
+        match self {
+            MinimapTile::Wall => MINIMAP_WALL_COLOR,
+            MinimapTile::Character(Some(color)) => get_pixel_color(color),
+            MinimapTile::Character(None) => MINIMAP_CHARACTER_COLOR,
+            MinimapTile::Player => MINIMAP_PLAYER_COLOR,
+        }
+    }
+}
+
+/// Create the hidden minimap entity in the top right corner of the screen, sized
+/// for the active area, and add the `Minimap` resource.
+pub fn create_minimap(world: &mut World) {
+    let dimensions = {
+        let ActiveArea(entity) = *world.read_resource::<ActiveArea>();
+        world.read_storage::<Area>().get(entity).unwrap().dimensions
+    };
+    let (width, height) = get_minimap_size(&dimensions);
+
+    let transform = UiTransform::new(
+        "minimap".to_string(),
+        Anchor::TopRight,
+        Anchor::TopRight,
+        -10.0,
+        -10.0,
+        MINIMAP_LAYER,
+        width,
+        height,
+    );
+
+    let entity = world.create_entity().with(transform).with(Hidden).build();
+
+    world.add_resource(Minimap {
+        entity,
+        is_visible: false,
+    });
+}
+
+/// Get the size of the minimap for an area (in pixels).
+pub fn get_minimap_size([nx, ny]: &[u32; 2]) -> (f32, f32) {
+    (
+        (nx * MINIMAP_TILE_SIZE) as f32,
+        (ny * MINIMAP_TILE_SIZE) as f32,
+    )
+}
+
+/// Create the minimap image for an area from the tiles of its entities.
+pub fn create_minimap_image(
+    dimensions: &[u32; 2],
+    tiles: &[(Position, MinimapTile)],
+    store: &AssetStorage<Texture>,
+    loader: &Loader,
+) -> Result<UiImage, String> {
+    let [nx, ny] = *dimensions;
+    let data = get_minimap_pixels(dimensions, tiles);
+
+    create_texture(&data, (nx, ny), store, loader, ()).map(UiImage::Texture)
+}
+
+/// Get the pixels of the minimap with one pixel per tile, starting from the top row.
+///
+/// If several tiles are at the same position the one with the highest precedence
+/// is shown. Tiles outside of the area are ignored.
+pub fn get_minimap_pixels([nx, ny]: &[u32; 2], tiles: &[(Position, MinimapTile)]) -> Vec<[u8; 4]> {
+    let mut pixels = vec![MINIMAP_FLOOR_COLOR; (nx * ny) as usize];
+    let mut shown: Vec<Option<u8>> = vec![None; pixels.len()];
+
+    for (position, tile) in tiles {
+        if position.x >= *nx || position.y >= *ny {
+            continue;
+        }
+
+        let row = ny - 1 - position.y;
+        let index = (row * nx + position.x) as usize;
+
+        if shown[index].map(|p| tile.precedence() > p).unwrap_or(true) {
+            pixels[index] = tile.color();
+            shown[index] = Some(tile.precedence());
+        }
+    }
+
+    pixels
+}
+
+fn get_pixel_color(color: &[f32; 4]) -> [u8; 4] {
+    let mut pixel = [0; 4];
+
+    for (value, component) in pixel.iter_mut().zip(color.iter()) {
+        *value = (component.max(0.0).min(1.0) * 255.0).round() as u8;
+    }
+
+    pixel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_area_is_all_floor() {
+        assert_eq!(
+            get_minimap_pixels(&[2, 3], &[]),
+            vec![MINIMAP_FLOOR_COLOR; 6]
+        );
+    }
+
+    #[test]
+    fn pixels_start_from_the_top_row() {
+        let tiles = vec![(Position { x: 1, y: 0 }, MinimapTile::Wall)];
+        let pixels = get_minimap_pixels(&[2, 2], &tiles);
+
+        assert_eq!(pixels[3], MINIMAP_WALL_COLOR);
+        assert_eq!(
+            pixels.iter().filter(|&&p| p == MINIMAP_FLOOR_COLOR).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn characters_are_colored_by_faction() {
+        let tiles = vec![
+            (
+                Position { x: 0, y: 0 },
+                MinimapTile::Character(Some([1.0, 0.0, 0.0, 1.0])),
+            ),
+            (Position { x: 1, y: 0 }, MinimapTile::Character(None)),
+        ];
+        let pixels = get_minimap_pixels(&[2, 1], &tiles);
+
+        assert_eq!(pixels, vec![[255, 0, 0, 255], MINIMAP_CHARACTER_COLOR]);
+    }
+
+    #[test]
+    fn player_is_shown_on_top_regardless_of_order() {
+        let position = Position { x: 0, y: 0 };
+        let tiles = vec![
+            (position.clone(), MinimapTile::Player),
+            (position.clone(), MinimapTile::Wall),
+        ];
+
+        assert_eq!(
+            get_minimap_pixels(&[1, 1], &tiles),
+            vec![MINIMAP_PLAYER_COLOR]
+        );
+    }
+
+    #[test]
+    fn tiles_outside_of_the_area_are_ignored() {
+        let tiles = vec![(Position { x: 5, y: 0 }, MinimapTile::Player)];
+
+        assert_eq!(
+            get_minimap_pixels(&[1, 1], &tiles),
+            vec![MINIMAP_FLOOR_COLOR]
+        );
+    }
+}
@@ -1,12 +1,63 @@
 mod area;
 mod assets;
 mod bundle;
+mod campaign;
 mod character;
+mod clock;
+mod console;
 pub mod consts;
+mod debug;
+mod dialogue;
+mod district;
+mod end;
+mod faction;
+mod flags;
+mod hud;
+mod inventory;
+mod item;
+mod journal;
+mod label;
 mod loading;
+mod map;
+mod message;
+mod minimap;
+mod objective;
+mod random;
+mod relations;
+mod save;
+mod screen;
+mod script;
 mod state;
+mod tiled;
+mod tween;
+mod zoom;
 
-pub use area::{get_world_coordinates, ActiveArea, Area, Collision, Position};
-pub use character::PlayerCharacter;
-pub use loading::Loading;
+pub use area::{get_world_coordinates, ActiveArea, ActiveAreaMap, Area, Collision, Position};
+pub use assets::AssetsDir;
+pub use campaign::{Campaign, CampaignData, CampaignEvent, ElectionResult};
+pub use character::{CharacterPrefab, Glyph, NonPlayerCharacterPrefab, PlayerCharacter};
+pub use clock::TurnClock;
+pub use debug::{get_area_grid_lines, get_collision_marker_lines, DebugOverlay};
+pub use dialogue::{Conversation, Dialogue, DialogueScreen, InkFormat, StoryData, Typewriter};
+pub use district::{DemographicGroup, Demographics, District, DistrictFormat};
+pub use faction::{init_faction_members, FactionDefinition, FactionMember, Factions, FactionsData};
+pub use flags::{get_story_variable_names, FlagCondition, FlagValue, GameFlags};
+pub use hud::Hud;
+pub use item::{Inventory, Item, ItemTransferEvent};
+pub use label::{
+    get_label_coordinates, get_name_label_text, get_name_label_transform, get_speech_bubble_text,
+    get_speech_bubble_transform, set_speech_bubble_alpha, Barks, NameLabel, SpeechBubble,
+};
+pub use loading::{load_area_map, Loading};
+pub use map::{AreaMap, AreaMapFormat, AreaMapPrefab, LegendEntry, UnknownGlyph};
+pub use message::MessageLog;
+pub use minimap::{create_minimap_image, get_minimap_size, Minimap, MinimapTile};
+pub use objective::{Objectives, ObjectivesData, Reward, Trigger, TriggerContext};
+pub use random::GameRng;
+pub use relations::{Attitude, RelationshipEvent, Relationships, Subject};
+pub use save::{quickload, quicksave, SaveGame};
+pub use script::{AreaTrigger, FiredTriggers, ScriptAction, TriggerOn, TriggerRegion};
 pub use state::Regular;
+pub use tiled::{TiledLayer, TiledMap, TiledMapFormat, TiledObject};
+pub use tween::{get_render_coordinates, Easing, Tween};
+pub use zoom::{get_zoomed_camera, Zoom, ZOOM_LEVELS};
@@ -0,0 +1,321 @@
+use amethyst::{
+    assets::{Asset, Handle, ProcessingState},
+    ecs::prelude::VecStorage,
+    Error,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+use super::{
+    campaign::CampaignEvent,
+    flags::{FlagCondition, GameFlags},
+    item::{Inventory, Item},
+    relations::Attitude,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Objective definitions which are read from an asset file.
+pub struct ObjectivesData {
+    pub objectives: Vec<ObjectiveDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectiveDefinition {
+    pub id: String,
+    /// Shown in the journal and when the objective is completed.
+    pub description: String,
+    /// Ids of objectives that must be completed before this one becomes active.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Condition which completes an active objective once it is met.
+pub enum Trigger {
+    /// Talk to the character with the given name.
+    TalkTo(String),
+    /// Be in the area with the given name.
+    EnterArea(String),
+    /// Carry the item (or at least the amount of money).
+    HaveItem(Item),
+    /// Poll at or above the threshold in the named district, or in every district
+    /// if no name is given.
+    PollThreshold { district: Option<String>, poll: f32 },
+    /// Have a flag in `GameFlags` meet the condition.
+    Flag(FlagCondition),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// What the player receives for completing an objective.
+pub enum Reward {
+    Item(Item),
+    Relationship { character: String, change: Attitude },
+    Campaign(CampaignEvent),
+}
+
+impl Asset for ObjectivesData {
+    const NAME: &'static str = "windy_city_politics::ObjectivesData";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+impl From<ObjectivesData> for Result<ProcessingState<ObjectivesData>, Error> {
+    fn from(data: ObjectivesData) -> Result<ProcessingState<ObjectivesData>, Error> {
+        Ok(ProcessingState::Loaded(data))
+    }
+}
+
+/// Current state of the game that triggers are tested against.
+pub struct TriggerContext<'a> {
+    pub area: &'a str,
+    pub inventory: Option<&'a Inventory>,
+    pub polls: Vec<(&'a str, f32)>,
+    pub flags: &'a GameFlags,
+}
+
+impl Trigger {
+    pub fn is_met(&self, context: &TriggerContext, talked_to: &HashSet<String>) -> bool {
+        match self {
+            Trigger::TalkTo(name) => talked_to.contains(name),
+            Trigger::EnterArea(name) => context.area == name,
+            Trigger::HaveItem(item) => context
+                .inventory
+                .map(|inventory| inventory.contains(item))
+                .unwrap_or(false),
+            Trigger::PollThreshold { district, poll } => {
+                let mut polls = context
+                    .polls
+                    .iter()
+                    .filter(|(name, _)| district.as_ref().map(|d| d == name).unwrap_or(true))
+                    .peekable();
+
+                polls.peek().is_some() && polls.all(|(_, value)| value >= poll)
+            }
+            Trigger::Flag(condition) => context.flags.test(condition),
+        }
+    }
+}
+
+/// Use as a resource to track the progress of all objectives.
+pub struct Objectives {
+    pub definitions: Vec<ObjectiveDefinition>,
+    completed: HashSet<String>,
+    /// Names of all characters that the player has talked to.
+    pub talked_to: HashSet<String>,
+}
+
+impl Objectives {
+    pub fn new(data: ObjectivesData) -> Self {
+        Objectives {
+            definitions: data.objectives,
+            completed: HashSet::new(),
+            talked_to: HashSet::new(),
+        }
+    }
+
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.completed.contains(id)
+    }
+
+    /// Objectives which have not been completed but whose requirements have.
+    pub fn active(&self) -> impl Iterator<Item = &ObjectiveDefinition> {
+        self.definitions.iter().filter(move |objective| {
+            !self.is_completed(&objective.id)
+                && objective.requires.iter().all(|id| self.is_completed(id))
+        })
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &ObjectiveDefinition> {
+        self.definitions
+            .iter()
+            .filter(move |objective| self.is_completed(&objective.id))
+    }
+
+    /// Continue from saved progress, given the ids of completed objectives and the
+    /// names of characters that the player has talked to.
+    pub fn restore(&mut self, completed: Vec<String>, talked_to: Vec<String>) {
+        self.completed = completed.into_iter().collect();
+        self.talked_to = talked_to.into_iter().collect();
+    }
+
+    /// Complete all active objectives whose triggers are met and return them.
+    pub fn complete_triggered(&mut self, context: &TriggerContext) -> Vec<ObjectiveDefinition> {
+        let triggered = self
+            .active()
+            .filter(|objective| objective.trigger.is_met(context, &self.talked_to))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for objective in triggered.iter() {
+            self.completed.insert(objective.id.clone());
+        }
+
+        triggered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_objective(id: &str, requires: &[&str], trigger: Trigger) -> ObjectiveDefinition {
+        ObjectiveDefinition {
+            id: id.to_string(),
+            description: String::new(),
+            requires: requires.iter().map(|id| id.to_string()).collect(),
+            trigger,
+            rewards: Vec::new(),
+        }
+    }
+
+    fn get_context<'a>(polls: &[(&'a str, f32)], flags: &'a GameFlags) -> TriggerContext<'a> {
+        TriggerContext {
+            area: "City Hall",
+            inventory: None,
+            polls: polls.to_vec(),
+            flags,
+        }
+    }
+
+    #[test]
+    fn talk_to_triggers_are_met_after_talking() {
+        let flags = GameFlags::default();
+        let trigger = Trigger::TalkTo("Rob".to_string());
+        let mut talked_to = HashSet::new();
+
+        assert!(!trigger.is_met(&get_context(&[], &flags), &talked_to));
+
+        talked_to.insert("Rob".to_string());
+        assert!(trigger.is_met(&get_context(&[], &flags), &talked_to));
+    }
+
+    #[test]
+    fn enter_area_triggers_check_the_area_name() {
+        let flags = GameFlags::default();
+        let talked_to = HashSet::new();
+
+        assert!(Trigger::EnterArea("City Hall".to_string())
+            .is_met(&get_context(&[], &flags), &talked_to));
+        assert!(
+            !Trigger::EnterArea("Pilsen".to_string()).is_met(&get_context(&[], &flags), &talked_to)
+        );
+    }
+
+    #[test]
+    fn have_item_triggers_check_the_inventory() {
+        let flags = GameFlags::default();
+        let talked_to = HashSet::new();
+        let trigger = Trigger::HaveItem(Item::Money(100));
+
+        let poor = Inventory::default();
+        let mut rich = Inventory::default();
+        rich.add(Item::Money(100));
+
+        let mut context = get_context(&[], &flags);
+        assert!(!trigger.is_met(&context, &talked_to), "no inventory");
+
+        context.inventory = Some(&poor);
+        assert!(!trigger.is_met(&context, &talked_to), "not enough money");
+
+        context.inventory = Some(&rich);
+        assert!(trigger.is_met(&context, &talked_to));
+    }
+
+    #[test]
+    fn poll_threshold_triggers_check_single_or_all_districts() {
+        let flags = GameFlags::default();
+        let talked_to = HashSet::new();
+        let context = get_context(&[("Loop", 0.6), ("Pilsen", 0.4)], &flags);
+
+        let loop_trigger = Trigger::PollThreshold {
+            district: Some("Loop".to_string()),
+            poll: 0.5,
+        };
+        let all_trigger = Trigger::PollThreshold {
+            district: None,
+            poll: 0.5,
+        };
+        let unknown_trigger = Trigger::PollThreshold {
+            district: Some("Hyde Park".to_string()),
+            poll: 0.0,
+        };
+
+        assert!(loop_trigger.is_met(&context, &talked_to));
+        assert!(!all_trigger.is_met(&context, &talked_to));
+        assert!(!unknown_trigger.is_met(&context, &talked_to));
+    }
+
+    #[test]
+    fn flag_triggers_test_the_flags() {
+        let talked_to = HashSet::new();
+        let trigger = Trigger::Flag(FlagCondition::IsSet("met_the_mayor".to_string()));
+
+        let mut flags = GameFlags::default();
+        assert!(!trigger.is_met(&get_context(&[], &flags), &talked_to));
+
+        flags.set("met_the_mayor", true);
+        assert!(trigger.is_met(&get_context(&[], &flags), &talked_to));
+    }
+
+    #[test]
+    fn objectives_are_active_once_their_requirements_are_completed() {
+        let flags = GameFlags::default();
+        let mut objectives = Objectives::new(ObjectivesData {
+            objectives: vec![
+                get_objective("first", &[], Trigger::EnterArea("City Hall".to_string())),
+                get_objective("second", &["first"], Trigger::TalkTo("Rob".to_string())),
+            ],
+        });
+
+        let active = objectives
+            .active()
+            .map(|o| o.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(active, vec!["first".to_string()]);
+
+        let completed = objectives.complete_triggered(&get_context(&[], &flags));
+        assert_eq!(completed.len(), 1);
+        assert!(objectives.is_completed("first"));
+
+        let active = objectives
+            .active()
+            .map(|o| o.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(active, vec!["second".to_string()]);
+    }
+
+    #[test]
+    fn objectives_are_only_completed_once() {
+        let flags = GameFlags::default();
+        let mut objectives = Objectives::new(ObjectivesData {
+            objectives: vec![get_objective(
+                "first",
+                &[],
+                Trigger::EnterArea("City Hall".to_string()),
+            )],
+        });
+
+        assert_eq!(
+            objectives
+                .complete_triggered(&get_context(&[], &flags))
+                .len(),
+            1
+        );
+        assert_eq!(
+            objectives
+                .complete_triggered(&get_context(&[], &flags))
+                .len(),
+            0
+        );
+        assert_eq!(objectives.completed().count(), 1);
+    }
+}
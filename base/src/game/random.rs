@@ -0,0 +1,15 @@
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+
+use super::consts::RNG_SEED;
+
+/// Use as a resource for all randomness of the running game, such as barks.
+///
+/// It is seeded with `RNG_SEED`, so a game plays out the same for the same input.
+pub struct GameRng(pub Pcg32);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng(Pcg32::seed_from_u64(RNG_SEED))
+    }
+}
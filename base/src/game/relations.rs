@@ -0,0 +1,166 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+/// Lower bound for all `Attitude` values.
+pub const ATTITUDE_MIN: i32 = -100;
+/// Upper bound for all `Attitude` values.
+pub const ATTITUDE_MAX: i32 = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+/// How a character regards someone else. All values are kept in the closed range
+/// [`ATTITUDE_MIN`, `ATTITUDE_MAX`] where 0 is neutral.
+pub struct Attitude {
+    /// Whether the character believes what the other one tells them.
+    pub trust: i32,
+    /// Whether the character is inclined to do the other one a favor.
+    pub favor: i32,
+    /// How well the character's political faction lines up with the other one's.
+    pub alignment: i32,
+}
+
+impl Attitude {
+    /// Add the given change to all values, clamping them to the valid range.
+    pub fn adjust(&mut self, change: &Attitude) {
+        self.trust = clamp_attitude(self.trust + change.trust);
+        self.favor = clamp_attitude(self.favor + change.favor);
+        self.alignment = clamp_attitude(self.alignment + change.alignment);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+/// Someone that a character can have an attitude toward.
+pub enum Subject {
+    Player,
+    /// Another character, identified by their `Named` name.
+    Character(String),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+/// All attitudes that a character holds toward the player and other characters.
+///
+/// Characters who have never met are neutral toward each other, so only attitudes
+/// which have been set are stored.
+pub struct Relationships {
+    pub player: Attitude,
+    pub characters: HashMap<String, Attitude>,
+}
+
+impl Relationships {
+    pub fn toward(&self, subject: &Subject) -> Attitude {
+        match subject {
+            Subject::Player => self.player,
+            Subject::Character(name) => self.characters.get(name).cloned().unwrap_or_default(),
+        }
+    }
+
+    pub fn adjust(&mut self, subject: &Subject, change: &Attitude) {
+        match subject {
+            Subject::Player => self.player.adjust(change),
+            Subject::Character(name) => self
+                .characters
+                .entry(name.clone())
+                .or_default()
+                .adjust(change),
+        }
+    }
+}
+
+impl Component for Relationships {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Debug)]
+/// Event used to change how a character regards someone else, eg. after a dialogue
+/// or when the player has done something that they care about.
+pub struct RelationshipEvent {
+    /// Character entity whose `Relationships` will change.
+    pub character: Entity,
+    pub subject: Subject,
+    pub change: Attitude,
+}
+
+fn clamp_attitude(value: i32) -> i32 {
+    value.max(ATTITUDE_MIN).min(ATTITUDE_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attitude_adjustments_add_to_all_values() {
+        let mut attitude = Attitude {
+            trust: 10,
+            favor: -10,
+            alignment: 0,
+        };
+
+        attitude.adjust(&Attitude {
+            trust: 5,
+            favor: 15,
+            alignment: -20,
+        });
+
+        assert_eq!(
+            attitude,
+            Attitude {
+                trust: 15,
+                favor: 5,
+                alignment: -20,
+            }
+        );
+    }
+
+    #[test]
+    fn attitude_adjustments_are_clamped_to_valid_range() {
+        let mut attitude = Attitude {
+            trust: 90,
+            favor: -90,
+            alignment: 0,
+        };
+
+        attitude.adjust(&Attitude {
+            trust: 50,
+            favor: -50,
+            alignment: 1000,
+        });
+
+        assert_eq!(attitude.trust, ATTITUDE_MAX);
+        assert_eq!(attitude.favor, ATTITUDE_MIN);
+        assert_eq!(attitude.alignment, ATTITUDE_MAX);
+    }
+
+    #[test]
+    fn relationships_toward_unknown_characters_are_neutral() {
+        let relationships = Relationships::default();
+
+        assert_eq!(
+            relationships.toward(&Subject::Character("Rob".to_string())),
+            Attitude::default()
+        );
+    }
+
+    #[test]
+    fn adjusting_relationships_only_changes_the_given_subject() {
+        let mut relationships = Relationships::default();
+        let rob = Subject::Character("Rob".to_string());
+        let change = Attitude {
+            trust: 10,
+            ..Attitude::default()
+        };
+
+        relationships.adjust(&rob, &change);
+
+        assert_eq!(relationships.toward(&rob), change);
+        assert_eq!(relationships.toward(&Subject::Player), Attitude::default());
+        assert_eq!(
+            relationships.toward(&Subject::Character("Austin".to_string())),
+            Attitude::default()
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use amethyst::{ecs::Join, prelude::World, shrev::EventChannel};
+
+use serde::{Deserialize, Serialize};
+
+use std::{fs, path::PathBuf};
+
+use crate::systems::script::AreaChangeEvent;
+
+use super::{
+    area::{ActiveAreaMap, Position},
+    campaign::Campaign,
+    character::PlayerCharacter,
+    clock::TurnClock,
+    consts::SAVE_FILE,
+    flags::GameFlags,
+    item::Inventory,
+    message::MessageLog,
+    objective::Objectives,
+    script::FiredTriggers,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Progress of a game which can be written to and read from a file.
+///
+/// Areas are not saved. The current area is loaded again from its map, so changes
+/// to the characters in it (such as their relationships with the player) are lost.
+pub struct SaveGame {
+    /// Path to the map of the current area, relative to the assets directory.
+    pub area_map: String,
+    /// Position of the player character in the current area.
+    pub position: Position,
+    /// Inventory of the player character.
+    pub inventory: Inventory,
+    pub flags: GameFlags,
+    /// Triggers which have fired in each area map.
+    pub fired_triggers: FiredTriggers,
+    /// Ids of all completed objectives.
+    pub completed_objectives: Vec<String>,
+    /// Names of all characters that the player has talked to.
+    pub talked_to: Vec<String>,
+    /// Current day of the campaign.
+    pub day: u32,
+    /// Support for the player in each bloc, indexed by district then bloc.
+    pub support: Vec<Vec<f32>>,
+    /// Turn of the `TurnClock`.
+    pub turn: u32,
+}
+
+impl SaveGame {
+    /// Collect the progress of the game in the world.
+    pub fn from_world(world: &World) -> Result<Self, String> {
+        let (position, inventory) = {
+            let positions = world.read_storage::<Position>();
+            let inventories = world.read_storage::<Inventory>();
+            let characters = world.read_storage::<PlayerCharacter>();
+
+            (&positions, inventories.maybe(), &characters)
+                .join()
+                .map(|(position, inventory, _)| {
+                    (position.clone(), inventory.cloned().unwrap_or_default())
+                })
+                .next()
+                .ok_or_else(|| "there is no player character to save".to_string())?
+        };
+
+        let objectives = world.read_resource::<Objectives>();
+        let campaign = world.read_resource::<Campaign>();
+
+        let mut talked_to = objectives.talked_to.iter().cloned().collect::<Vec<_>>();
+        talked_to.sort();
+
+        Ok(SaveGame {
+            area_map: world.read_resource::<ActiveAreaMap>().0.clone(),
+            position,
+            inventory,
+            flags: world.read_resource::<GameFlags>().clone(),
+            fired_triggers: world.read_resource::<FiredTriggers>().clone(),
+            completed_objectives: objectives
+                .completed()
+                .map(|objective| objective.id.clone())
+                .collect(),
+            talked_to,
+            day: campaign.day,
+            support: campaign.support().to_vec(),
+            turn: world.read_resource::<TurnClock>().turn,
+        })
+    }
+
+    /// Continue the saved game in the world. The saved area is loaded by the
+    /// `AreaChangeSystem` which places the player character when it is ready.
+    pub fn restore(self, world: &mut World) -> Result<(), String> {
+        world
+            .write_resource::<Campaign>()
+            .restore(self.day, self.support)?;
+
+        world
+            .write_resource::<Objectives>()
+            .restore(self.completed_objectives, self.talked_to);
+
+        *world.write_resource::<GameFlags>() = self.flags;
+        *world.write_resource::<FiredTriggers>() = self.fired_triggers;
+        world.write_resource::<TurnClock>().turn = self.turn;
+
+        {
+            let mut inventories = world.write_storage::<Inventory>();
+            let characters = world.read_storage::<PlayerCharacter>();
+
+            for (inventory, _) in (&mut inventories, &characters).join() {
+                *inventory = self.inventory.clone();
+            }
+        }
+
+        world
+            .write_resource::<EventChannel<AreaChangeEvent>>()
+            .single_write(AreaChangeEvent {
+                map: self.area_map,
+                position: self.position,
+            });
+
+        Ok(())
+    }
+
+    pub fn read(path: &PathBuf) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("could not read save file '{}': {}", path.display(), err))?;
+
+        ron::de::from_str(&contents)
+            .map_err(|err| format!("could not parse save file '{}': {}", path.display(), err))
+    }
+
+    pub fn write(&self, path: &PathBuf) -> Result<(), String> {
+        let contents = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| format!("could not serialize save file: {}", err))?;
+
+        fs::write(path, contents)
+            .map_err(|err| format!("could not write save file '{}': {}", path.display(), err))
+    }
+}
+
+fn get_save_path() -> Result<PathBuf, String> {
+    std::env::current_dir()
+        .map(|directory| directory.join(SAVE_FILE))
+        .map_err(|err| format!("could not find the save directory: {}", err))
+}
+
+/// Save the game to the save file in the working directory.
+pub fn quicksave(world: &mut World) {
+    let result = get_save_path()
+        .and_then(|path| SaveGame::from_world(world).and_then(|save| save.write(&path)));
+
+    let message = match result {
+        Ok(()) => "Game saved".to_string(),
+        Err(msg) => {
+            log::error!("{}", msg);
+            "Could not save the game".to_string()
+        }
+    };
+
+    world.write_resource::<MessageLog>().push(message);
+}
+
+/// Load the game from the save file in the working directory.
+pub fn quickload(world: &mut World) {
+    let result = get_save_path()
+        .and_then(|path| SaveGame::read(&path))
+        .and_then(|save| save.restore(world));
+
+    let message = match result {
+        Ok(()) => "Game loaded".to_string(),
+        Err(msg) => {
+            log::error!("{}", msg);
+            "Could not load the game".to_string()
+        }
+    };
+
+    world.write_resource::<MessageLog>().push(message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::{AreaTrigger, Item, TriggerOn, TriggerRegion};
+
+    #[test]
+    fn save_games_are_written_and_read_back() {
+        let mut flags = GameFlags::default();
+        flags.set("met_the_mayor", true);
+
+        let trigger = AreaTrigger::new(
+            TriggerRegion::Tiles(vec![Position { x: 4, y: 1 }]),
+            TriggerOn::Enter,
+            Vec::new(),
+            true,
+            Vec::new(),
+        );
+        let mut fired_triggers = FiredTriggers::default();
+        fired_triggers.insert("area/lobby.json", &trigger);
+
+        let save = SaveGame {
+            area_map: "area/lobby.json".to_string(),
+            position: Position { x: 4, y: 1 },
+            inventory: Inventory {
+                money: 50,
+                items: vec![Item::Favor {
+                    from: "Marge".to_string(),
+                }],
+            },
+            flags,
+            fired_triggers,
+            completed_objectives: vec!["meet_marge".to_string()],
+            talked_to: vec!["Marge".to_string()],
+            day: 3,
+            support: vec![vec![0.4, 0.6]],
+            turn: 120,
+        };
+
+        let written = ron::ser::to_string_pretty(&save, Default::default()).unwrap();
+        let read: SaveGame = ron::de::from_str(&written).unwrap();
+
+        assert_eq!(read.area_map, save.area_map);
+        assert_eq!(read.position, save.position);
+        assert_eq!(read.inventory.money, 50);
+        assert_eq!(read.inventory.items.len(), 1);
+        assert!(read.flags.is_set("met_the_mayor"));
+        assert!(read.fired_triggers.contains("area/lobby.json", &trigger));
+        assert_eq!(read.completed_objectives, save.completed_objectives);
+        assert_eq!(read.support, save.support);
+        assert_eq!(read.turn, 120);
+    }
+}
@@ -0,0 +1,74 @@
+use amethyst::{
+    ecs::Entity,
+    prelude::{Builder, World},
+    ui::{Anchor, Stretch, UiImage, UiText, UiTransform},
+};
+
+use super::{
+    assets::Fonts,
+    consts::{HUD_FONT_SIZE, SCREEN_LAYER},
+};
+
+const SCREEN_BACKGROUND_COLOR: [f32; 4] = [0.018, 0.018, 0.018, 0.95];
+const SCREEN_TITLE_COLOR: [f32; 4] = [0.388, 0.887, 0.678, 1.0];
+const SCREEN_TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Create a full screen overlay with a title and a list of text lines.
+///
+/// Returns all created entities so that the caller can delete them when the
+/// screen is closed.
+///
+/// # Notes
+///  * Requires the `Fonts` resource to exist.
+pub fn create_text_screen(world: &mut World, title: &str, lines: &[String]) -> Vec<Entity> {
+    let font = world.read_resource::<Fonts>().main.clone();
+
+    let mut background_transform = UiTransform::new(
+        "screen_background".to_string(),
+        Anchor::Middle,
+        Anchor::Middle,
+        0.0,
+        0.0,
+        SCREEN_LAYER,
+        20.0,
+        20.0,
+    );
+    background_transform.stretch = Stretch::XY {
+        x_margin: 60.0,
+        y_margin: 40.0,
+        keep_aspect_ratio: false,
+    };
+
+    let background = world
+        .create_entity()
+        .with(background_transform)
+        .with(UiImage::SolidColor(SCREEN_BACKGROUND_COLOR))
+        .build();
+
+    let mut entities = vec![background];
+
+    let texts = std::iter::once((title.to_string(), SCREEN_TITLE_COLOR))
+        .chain(lines.iter().map(|line| (line.clone(), SCREEN_TEXT_COLOR)));
+
+    let line_height = HUD_FONT_SIZE + 8.0;
+
+    for (i, (text, color)) in texts.enumerate() {
+        let mut ui_text = UiText::new(font.clone(), text, color, HUD_FONT_SIZE);
+        ui_text.align = Anchor::MiddleLeft;
+
+        let transform = UiTransform::new(
+            format!("screen_line_{}", i),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            100.0,
+            -80.0 - line_height * i as f32,
+            SCREEN_LAYER + 0.1,
+            1000.0,
+            line_height,
+        );
+
+        entities.push(world.create_entity().with(ui_text).with(transform).build());
+    }
+
+    entities
+}
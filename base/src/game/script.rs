@@ -0,0 +1,359 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    area::Position,
+    campaign::CampaignEvent,
+    flags::{FlagCondition, FlagValue, GameFlags},
+    item::Item,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Region of an area which runs scripted actions when the player enters it, leaves
+/// it or presses the action key inside of it.
+///
+/// Triggers are declared with the area, either in the `triggers` of an `AreaMap`
+/// or as trigger objects in a map made in Tiled.
+pub struct AreaTrigger {
+    pub region: TriggerRegion,
+    pub on: TriggerOn,
+    /// Run in order when the trigger fires.
+    pub actions: Vec<ScriptAction>,
+    /// Only fire the first time.
+    #[serde(default)]
+    pub once: bool,
+    /// Flags which must all be met for the trigger to fire.
+    #[serde(default)]
+    pub conditions: Vec<FlagCondition>,
+    /// Number of the trigger in its area map, in the order they are declared.
+    #[serde(skip)]
+    id: usize,
+    #[serde(skip)]
+    fired: bool,
+}
+
+impl Component for AreaTrigger {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Tiles which are covered by a trigger.
+pub enum TriggerRegion {
+    /// Rectangle of tiles with its lower left corner at (x, y).
+    Rect {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    Tiles(Vec<Position>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+/// What the player does to fire a trigger.
+pub enum TriggerOn {
+    /// Move into the region from outside of it.
+    Enter,
+    /// Move out of the region.
+    Exit,
+    /// Press the action key while inside the region.
+    Interact,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Something that happens when a trigger fires.
+pub enum ScriptAction {
+    /// Start talking to the named character, wherever they are.
+    StartDialogue(String),
+    /// Add a message to the message log.
+    ShowMessage(String),
+    /// Move the named character to a tile in the current area.
+    MoveCharacter { name: String, to: Position },
+    /// Leave the current area for the area map at the path (relative to the assets
+    /// directory), placing the player character at the position.
+    ChangeArea { map: String, position: Position },
+    /// Set the named flag in `GameFlags` to the value.
+    SetFlag(String, FlagValue),
+    /// Change the support of voters in the campaign.
+    Campaign(CampaignEvent),
+    /// Hand an item from the inventory of the player character to the named
+    /// character. Nothing is given if the player does not carry the item.
+    GiveItem { to: String, item: Item },
+}
+
+impl TriggerRegion {
+    pub fn contains(&self, position: &Position) -> bool {
+        match self {
+            TriggerRegion::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                position.x >= *x
+                    && position.y >= *y
+                    && position.x < x + width
+                    && position.y < y + height
+            }
+            TriggerRegion::Tiles(tiles) => tiles.contains(position),
+        }
+    }
+
+    /// Get all covered tiles.
+    pub fn tiles(&self) -> Vec<Position> {
+        match self {
+            TriggerRegion::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => (*y..y + height)
+                .flat_map(|y| (*x..x + width).map(move |x| Position { x, y }))
+                .collect(),
+            TriggerRegion::Tiles(tiles) => tiles.clone(),
+        }
+    }
+}
+
+impl AreaTrigger {
+    pub fn new(
+        region: TriggerRegion,
+        on: TriggerOn,
+        actions: Vec<ScriptAction>,
+        once: bool,
+        conditions: Vec<FlagCondition>,
+    ) -> Self {
+        AreaTrigger {
+            region,
+            on,
+            actions,
+            once,
+            conditions,
+            id: 0,
+            fired: false,
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
+    /// Mark the trigger as fired before, eg. when its area is entered again.
+    pub fn set_fired(&mut self) {
+        self.fired = true;
+    }
+
+    /// Whether the trigger fires when the player moves between two tiles.
+    pub fn fires_on_move(&self, from: &Position, to: &Position, flags: &GameFlags) -> bool {
+        let (was_inside, is_inside) = (self.region.contains(from), self.region.contains(to));
+
+        let fires = match self.on {
+            TriggerOn::Enter => !was_inside && is_inside,
+            TriggerOn::Exit => was_inside && !is_inside,
+            TriggerOn::Interact => false,
+        };
+
+        fires && self.can_fire(flags)
+    }
+
+    /// Whether the trigger fires when the player presses the action key at a tile.
+    pub fn fires_on_interact(&self, position: &Position, flags: &GameFlags) -> bool {
+        self.on == TriggerOn::Interact && self.region.contains(position) && self.can_fire(flags)
+    }
+
+    /// Get the actions to run and mark the trigger as fired.
+    pub fn fire(&mut self) -> Vec<ScriptAction> {
+        self.fired = true;
+        self.actions.clone()
+    }
+
+    fn can_fire(&self, flags: &GameFlags) -> bool {
+        !(self.once && self.fired)
+            && self
+                .conditions
+                .iter()
+                .all(|condition| flags.test(condition))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Use as a resource to remember the `AreaTrigger`s which have fired in each area
+/// map, so that triggers which only fire once stay fired when an area is loaded
+/// again. Triggers are kept by area map path and id.
+pub struct FiredTriggers(HashMap<String, HashSet<usize>>);
+
+impl FiredTriggers {
+    pub fn insert(&mut self, map: &str, trigger: &AreaTrigger) {
+        self.0
+            .entry(map.to_string())
+            .or_insert_with(HashSet::new)
+            .insert(trigger.id());
+    }
+
+    pub fn contains(&self, map: &str, trigger: &AreaTrigger) -> bool {
+        self.0
+            .get(map)
+            .map(|ids| ids.contains(&trigger.id()))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_trigger(on: TriggerOn, once: bool) -> AreaTrigger {
+        AreaTrigger::new(
+            TriggerRegion::Rect {
+                x: 2,
+                y: 3,
+                width: 2,
+                height: 1,
+            },
+            on,
+            vec![ScriptAction::ShowMessage("Hello".to_string())],
+            once,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn rect_regions_contain_tiles_from_their_lower_left_corner() {
+        let region = get_trigger(TriggerOn::Enter, false).region;
+
+        assert_eq!(
+            region.tiles(),
+            vec![Position { x: 2, y: 3 }, Position { x: 3, y: 3 }]
+        );
+
+        assert!(region.contains(&Position { x: 3, y: 3 }));
+        assert!(!region.contains(&Position { x: 4, y: 3 }));
+        assert!(!region.contains(&Position { x: 2, y: 4 }));
+    }
+
+    #[test]
+    fn enter_and_exit_triggers_fire_when_crossing_the_region_edge() {
+        let flags = GameFlags::default();
+        let enter = get_trigger(TriggerOn::Enter, false);
+        let exit = get_trigger(TriggerOn::Exit, false);
+
+        let outside = Position { x: 1, y: 3 };
+        let inside = Position { x: 2, y: 3 };
+        let also_inside = Position { x: 3, y: 3 };
+
+        assert!(enter.fires_on_move(&outside, &inside, &flags));
+        assert!(!enter.fires_on_move(&inside, &also_inside, &flags));
+        assert!(!enter.fires_on_move(&inside, &outside, &flags));
+
+        assert!(exit.fires_on_move(&inside, &outside, &flags));
+        assert!(!exit.fires_on_move(&outside, &inside, &flags));
+    }
+
+    #[test]
+    fn interact_triggers_fire_only_inside_the_region() {
+        let flags = GameFlags::default();
+        let trigger = get_trigger(TriggerOn::Interact, false);
+
+        assert!(trigger.fires_on_interact(&Position { x: 2, y: 3 }, &flags));
+        assert!(!trigger.fires_on_interact(&Position { x: 2, y: 2 }, &flags));
+        assert!(!trigger.fires_on_move(&Position { x: 1, y: 3 }, &Position { x: 2, y: 3 }, &flags));
+
+        let enter = get_trigger(TriggerOn::Enter, false);
+        assert!(!enter.fires_on_interact(&Position { x: 2, y: 3 }, &flags));
+    }
+
+    #[test]
+    fn once_triggers_fire_only_the_first_time() {
+        let flags = GameFlags::default();
+        let mut once = get_trigger(TriggerOn::Interact, true);
+        let mut always = get_trigger(TriggerOn::Interact, false);
+        let position = Position { x: 2, y: 3 };
+
+        assert_eq!(once.fire().len(), 1);
+        always.fire();
+
+        assert!(!once.fires_on_interact(&position, &flags));
+        assert!(always.fires_on_interact(&position, &flags));
+    }
+
+    #[test]
+    fn triggers_with_conditions_fire_only_when_all_flags_are_met() {
+        let mut trigger = get_trigger(TriggerOn::Interact, false);
+        trigger.conditions = vec![
+            FlagCondition::IsSet("met_the_mayor".to_string()),
+            FlagCondition::AtLeast("donations".to_string(), 2),
+        ];
+
+        let position = Position { x: 2, y: 3 };
+        let mut flags = GameFlags::default();
+
+        flags.set("met_the_mayor", true);
+        assert!(!trigger.fires_on_interact(&position, &flags));
+
+        flags.set("donations", 2);
+        assert!(trigger.fires_on_interact(&position, &flags));
+    }
+
+    #[test]
+    fn fired_triggers_are_kept_per_area_map() {
+        let mut trigger = get_trigger(TriggerOn::Enter, true);
+        trigger.set_id(1);
+        let mut other = get_trigger(TriggerOn::Enter, true);
+        other.set_id(2);
+
+        let mut fired = FiredTriggers::default();
+        fired.insert("area/cityhall.ron", &trigger);
+
+        assert!(fired.contains("area/cityhall.ron", &trigger));
+        assert!(!fired.contains("area/cityhall.ron", &other));
+        assert!(!fired.contains("area/lobby.json", &trigger));
+    }
+
+    #[test]
+    fn triggers_which_have_fired_before_only_fire_once() {
+        let mut trigger = get_trigger(TriggerOn::Enter, true);
+        trigger.set_fired();
+
+        assert!(!trigger.fires_on_move(
+            &Position { x: 0, y: 0 },
+            &Position { x: 2, y: 3 },
+            &GameFlags::default()
+        ));
+    }
+
+    #[test]
+    fn actions_which_give_items_and_change_the_campaign_are_read_from_ron() {
+        let actions: Vec<ScriptAction> = ron::de::from_str(
+            r#"[
+                GiveItem(to: "Rob", item: Money(50)),
+                Campaign(Issue(issue: "housing", amount: 0.05)),
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            actions,
+            vec![
+                ScriptAction::GiveItem {
+                    to: "Rob".to_string(),
+                    item: Item::Money(50),
+                },
+                ScriptAction::Campaign(CampaignEvent::Issue {
+                    issue: "housing".to_string(),
+                    amount: 0.05,
+                }),
+            ]
+        );
+    }
+}
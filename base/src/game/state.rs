@@ -1,33 +1,139 @@
 use amethyst::{
+    assets::{AssetStorage, Handle, Loader, ProgressCounter},
     core::{ArcThreadPool, SystemBundle},
-    input::{is_key_down, VirtualKeyCode},
-    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, World},
-    renderer::{
-        debug_drawing::DebugLinesComponent,
-        palette::{Pixel, Srgba},
-    },
+    ecs::{Entity, Join},
+    input::{is_key_down, InputEvent, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, World},
     shred::{Dispatcher, DispatcherBuilder},
-    shrev::EventChannel,
+    shrev::{EventChannel, ReaderId},
 };
 
-use crate::systems::movement::update_transforms::UpdateTransformsEvent;
+use crate::{
+    locale::Locale,
+    replay::{get_position_checksum, InputMode, Recording, ReplayOutcome},
+    systems::{movement::update_transforms::UpdateTransformsEvent, talk::TalkEvent},
+};
 
 use super::{
-    area::{get_world_coordinates, ActiveArea, Area},
-    bundle::MovementSystemsBundle,
-    consts::{DEBUG_SPRITE_LAYER, TILE_HEIGHT, TILE_WIDTH},
+    area::Position,
+    bundle::{
+        CampaignSystemsBundle, DebugSystemsBundle, InventorySystemsBundle, LabelSystemsBundle,
+        MinimapSystemsBundle, MovementSystemsBundle, ObjectiveSystemsBundle, PrefabLoaderBundle,
+        RecordingSystemsBundle, RelationshipSystemsBundle, ScriptSystemsBundle,
+    },
+    campaign::Campaign,
+    console::Console,
+    debug::create_debug_overlay,
+    dialogue::{Dialogue, DialogueScreen, InkFormat, StoryData},
+    end::EndGame,
+    hud::create_hud,
+    inventory::InventoryScreen,
+    journal::JournalScreen,
+    minimap::create_minimap,
+    save::{quickload, quicksave},
 };
 
+/// Dialogue of a character whose story is still loading.
+struct PendingDialogue {
+    character: Entity,
+    dialogue: Dialogue,
+    story: Handle<StoryData>,
+    progress: ProgressCounter,
+}
+
 #[derive(Default)]
 pub struct Regular<'a, 'b> {
     dispatcher: Option<Dispatcher<'a, 'b>>,
+    talk_reader: Option<ReaderId<TalkEvent>>,
+    pending_dialogue: Option<PendingDialogue>,
+}
+
+impl<'a, 'b> Regular<'a, 'b> {
+    /// Start loading the story of the last character that the player talked to, if
+    /// they have a `Dialogue`.
+    fn load_dialogue(&mut self, world: &World) {
+        let character = world
+            .read_resource::<EventChannel<TalkEvent>>()
+            .read(self.talk_reader.as_mut().unwrap())
+            .last()
+            .map(|event| event.character);
+
+        let dialogue = character.and_then(|character| {
+            world
+                .read_storage::<Dialogue>()
+                .get(character)
+                .cloned()
+                .map(|dialogue| (character, dialogue))
+        });
+
+        if let Some((character, dialogue)) = dialogue {
+            if is_turned_off_while_recording(world, "dialogue") {
+                return;
+            }
+
+            let mut progress = ProgressCounter::new();
+
+            // Translated stories replace the original story as a whole
+            let path = world.read_resource::<Locale>().story_path(&dialogue.story);
+
+            let story = world.read_resource::<Loader>().load(
+                path,
+                InkFormat,
+                &mut progress,
+                &world.read_resource::<AssetStorage<StoryData>>(),
+            );
+
+            self.pending_dialogue = Some(PendingDialogue {
+                character,
+                dialogue,
+                story,
+                progress,
+            });
+        }
+    }
+
+    /// Open the dialogue screen once the story has loaded.
+    fn open_dialogue(&mut self, world: &World) -> Option<SimpleTrans> {
+        let pending = self.pending_dialogue.take()?;
+
+        if pending.progress.num_failed() > 0 {
+            log::error!("could not load story '{}'", pending.dialogue.story);
+            return None;
+        }
+
+        let story = world
+            .read_resource::<AssetStorage<StoryData>>()
+            .get(&pending.story)
+            .cloned();
+
+        match story {
+            Some(story) => Some(Trans::Push(Box::new(DialogueScreen::new(
+                pending.character,
+                story,
+                pending.dialogue.knot,
+            )))),
+            None => {
+                self.pending_dialogue = Some(pending);
+                None
+            }
+        }
+    }
 }
 
 impl<'a, 'b> SimpleState for Regular<'a, 'b> {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
+        create_hud(world);
+        create_minimap(world);
+        create_debug_overlay(world);
+
         self.dispatcher = Some(setup_game_system_dispatcher(world));
+        self.talk_reader = Some(
+            world
+                .write_resource::<EventChannel<TalkEvent>>()
+                .register_reader(),
+        );
 
         // All rendered entities should have correct `Position`s at this stage
         // but once the camera is set up we need to trigger an update for
@@ -35,20 +141,57 @@ impl<'a, 'b> SimpleState for Regular<'a, 'b> {
         world
             .write_resource::<EventChannel<UpdateTransformsEvent>>()
             .single_write(UpdateTransformsEvent);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        if let InputMode::Record(ref path) = *world.read_resource::<InputMode>() {
+            let checksum = get_position_checksum((&world.read_storage::<Position>()).join());
 
-        // Debug grid
-        draw_area_grid(world);
+            let mut recording = world.write_resource::<Recording>();
+            recording.checksum = Some(checksum);
+
+            if let Err(msg) = recording.write(path) {
+                log::error!("{}", msg);
+            }
+        }
     }
 
     fn handle_event(
         &mut self,
-        _data: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        if let StateEvent::Window(event) = event {
-            if is_key_down(&event, VirtualKeyCode::Escape) {
-                return Trans::Quit;
+        match event {
+            StateEvent::Window(event) => {
+                if is_key_down(&event, VirtualKeyCode::Escape) {
+                    return Trans::Quit;
+                } else if is_key_down(&event, VirtualKeyCode::I) {
+                    return Trans::Push(Box::new(InventoryScreen::default()));
+                } else if is_key_down(&event, VirtualKeyCode::J) {
+                    return Trans::Push(Box::new(JournalScreen::default()));
+                } else if is_key_down(&event, VirtualKeyCode::Grave)
+                    && !is_turned_off_while_recording(data.world, "the console")
+                {
+                    return Trans::Push(Box::new(Console::default()));
+                } else if is_key_down(&event, VirtualKeyCode::F5)
+                    && !is_turned_off_while_recording(data.world, "quicksave")
+                {
+                    quicksave(data.world);
+                } else if is_key_down(&event, VirtualKeyCode::F9)
+                    && !is_turned_off_while_recording(data.world, "quickload")
+                {
+                    quickload(data.world);
+                }
             }
+            // Controller buttons for the screens
+            StateEvent::Input(InputEvent::ActionPressed(action)) => match action.as_str() {
+                "open_inventory" => return Trans::Push(Box::new(InventoryScreen::default())),
+                "open_journal" => return Trans::Push(Box::new(JournalScreen::default())),
+                _ => (),
+            },
+            _ => (),
         }
 
         Trans::None
@@ -59,51 +202,111 @@ impl<'a, 'b> SimpleState for Regular<'a, 'b> {
             dispatcher.dispatch(&data.world.res);
         }
 
-        Trans::None
+        let replay_finished = data
+            .world
+            .res
+            .try_fetch::<ReplayOutcome>()
+            .map(|outcome| outcome.0.is_some())
+            .unwrap_or(false);
+
+        if replay_finished {
+            return Trans::Quit;
+        }
+
+        self.load_dialogue(data.world);
+
+        if let Some(trans) = self.open_dialogue(data.world) {
+            return trans;
+        }
+
+        match data.world.read_resource::<Campaign>().result.clone() {
+            Some(result) => Trans::Switch(Box::new(EndGame::new(result))),
+            None => Trans::None,
+        }
+    }
+}
+
+/// Whether a feature is turned off because input is recorded or replayed, since
+/// the recording would diverge from the game otherwise. Logs a warning if so.
+fn is_turned_off_while_recording(world: &World, feature: &str) -> bool {
+    let input_mode = world.read_resource::<InputMode>();
+    let is_recorded = input_mode.is_recorded();
+
+    if is_recorded {
+        log::warn!(
+            "{} is turned off while recording or replaying input",
+            feature
+        );
     }
+
+    is_recorded
 }
 
 fn setup_game_system_dispatcher<'a, 'b>(world: &mut World) -> Dispatcher<'a, 'b> {
     let mut dispatcher_builder = DispatcherBuilder::new();
 
-    MovementSystemsBundle
+    let input_mode = world.read_resource::<InputMode>().clone();
+
+    let replay = match input_mode {
+        InputMode::Replay(ref path) => {
+            Some(Recording::read(path).unwrap_or_else(|msg| panic!("{}", msg)))
+        }
+        _ => None,
+    };
+
+    MovementSystemsBundle { replay }
         .build(&mut dispatcher_builder)
         .expect("failed to register `MoveSystemsBundle`");
 
-    let mut dispatcher = dispatcher_builder
-        .with_pool(world.read_resource::<ArcThreadPool>().clone())
-        .build();
+    RelationshipSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `RelationshipSystemsBundle`");
 
-    dispatcher.setup(&mut world.res);
+    CampaignSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `CampaignSystemsBundle`");
 
-    dispatcher
-}
+    if let InputMode::Record(_) = input_mode {
+        RecordingSystemsBundle
+            .build(&mut dispatcher_builder)
+            .expect("failed to register `RecordingSystemsBundle`");
+    }
 
-fn draw_area_grid(world: &mut World) {
-    let [nx, ny] = {
-        let ActiveArea(entity) = *world.read_resource::<ActiveArea>();
-        world.read_storage::<Area>().get(entity).unwrap().dimensions
-    };
+    InventorySystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `InventorySystemsBundle`");
 
-    let (size_x, size_y) = get_world_coordinates(nx, ny);
+    ObjectiveSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `ObjectiveSystemsBundle`");
 
-    let mut debug_lines = DebugLinesComponent::new();
+    ScriptSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `ScriptSystemsBundle`");
 
-    let color = Srgba::from_raw(&[110.0 / 255.0, 110.0 / 255.0, 110.0 / 255.0, 0.5]);
+    LabelSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `LabelSystemsBundle`");
 
-    for col in 0..=nx {
-        let x = (col * TILE_WIDTH) as f32;
-        let start = [x, 0.0, DEBUG_SPRITE_LAYER];
-        let end = [x, size_y, DEBUG_SPRITE_LAYER];
-        debug_lines.add_line(start.into(), end.into(), color.clone());
-    }
+    MinimapSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `MinimapSystemsBundle`");
 
-    for row in 0..=ny {
-        let y = (row * TILE_HEIGHT) as f32;
-        let start = [0.0, y, DEBUG_SPRITE_LAYER];
-        let end = [size_x, y, DEBUG_SPRITE_LAYER];
-        debug_lines.add_line(start.into(), end.into(), color.clone());
-    }
+    DebugSystemsBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `DebugSystemsBundle`");
 
-    world.create_entity().with(debug_lines).build();
+    // Prefabs which are spawned from the developer console and dialogue stories are
+    // loaded during the game
+    PrefabLoaderBundle
+        .build(&mut dispatcher_builder)
+        .expect("failed to register `PrefabLoaderBundle`");
+
+    let mut dispatcher = dispatcher_builder
+        .with_pool(world.read_resource::<ArcThreadPool>().clone())
+        .build();
+
+    dispatcher.setup(&mut world.res);
+
+    dispatcher
 }
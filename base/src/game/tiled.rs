@@ -0,0 +1,595 @@
+use amethyst::{
+    assets::{Format, Prefab},
+    Error,
+};
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::{
+    area::{Area, Position},
+    character::{CharacterPrefab, NonPlayerCharacterPrefab},
+    item::Item,
+    map::{get_area_map_prefab, number_triggers, AreaMapPrefab},
+    script::{AreaTrigger, TriggerOn, TriggerRegion},
+};
+
+/// Bits of tile ids which Tiled uses to flip tiles.
+const TILED_FLIP_FLAGS: u32 = 0xe000_0000;
+
+/// Glyph of doors which do not set one.
+const DEFAULT_DOOR_GLYPH: char = '+';
+
+#[derive(Clone, Debug, Deserialize)]
+/// Map exported from the Tiled editor (https://www.mapeditor.org/) in its JSON format.
+///
+/// Tile layers make up the tiles of the area. Every tile that is used needs a
+/// `glyph` property in its tileset and a `collision` property to block movement.
+///
+/// Objects are placed at the tile under their center, by their type:
+///  * `character`: a non-player character named as the object, with the
+///    properties `glyph`, `faction`, and `inventory` and `relationships` written
+///    in RON as in character prefabs
+///  * `prop`: with the properties `glyph` and `collision`
+///  * `door`: a prop which does not block movement, with the `glyph` property
+///  * `item`: with the properties `glyph` and `item` written in RON
+///
+///  * `trigger`: an `AreaTrigger` covering the tiles under the object, with the
+///    properties `on` (`enter`, `exit` or `interact`), `actions` written in RON
+///    as a list of `ScriptAction`s, `once` and `conditions` written in RON as a
+///    list of `FlagCondition`s
+/// The name of the area is read from the `name` property of the map.
+pub struct TiledMap {
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "tilewidth")]
+    pub tile_width: u32,
+    #[serde(rename = "tileheight")]
+    pub tile_height: u32,
+    #[serde(default)]
+    pub properties: Vec<TiledProperty>,
+    pub layers: Vec<TiledLayer>,
+    #[serde(default)]
+    pub tilesets: Vec<TiledTileset>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum TiledLayer {
+    #[serde(rename = "tilelayer")]
+    Tiles {
+        name: String,
+        /// Tile ids row by row from the top, 0 for no tile.
+        #[serde(default)]
+        data: Vec<u32>,
+        #[serde(default)]
+        encoding: Option<String>,
+    },
+    #[serde(rename = "objectgroup")]
+    Objects {
+        name: String,
+        objects: Vec<TiledObject>,
+    },
+    /// Image and group layers are not used.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TiledObject {
+    #[serde(default)]
+    pub name: String,
+    /// Called `class` in some versions of Tiled.
+    #[serde(rename = "type", alias = "class", default)]
+    pub kind: String,
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub height: f32,
+    /// Set for tile objects, which are placed by their lower left corner.
+    #[serde(default)]
+    pub gid: Option<u32>,
+    #[serde(default)]
+    pub properties: Vec<TiledProperty>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TiledTileset {
+    #[serde(rename = "firstgid")]
+    pub first_gid: u32,
+    #[serde(default)]
+    pub tiles: Vec<TiledTile>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TiledTile {
+    pub id: u32,
+    #[serde(default)]
+    pub properties: Vec<TiledProperty>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TiledProperty {
+    pub name: String,
+    pub value: serde_json::Value,
+}
+
+fn get_property<'a>(properties: &'a [TiledProperty], name: &str) -> Option<&'a serde_json::Value> {
+    properties
+        .iter()
+        .find(|property| property.name == name)
+        .map(|property| &property.value)
+}
+
+fn get_string_property(properties: &[TiledProperty], name: &str) -> Result<Option<String>, String> {
+    match get_property(properties, name) {
+        None => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+        Some(value) => Err(format!("property '{}' is not a string: {}", name, value)),
+    }
+}
+
+fn get_bool_property(properties: &[TiledProperty], name: &str) -> Result<bool, String> {
+    match get_property(properties, name) {
+        None => Ok(false),
+        Some(serde_json::Value::Bool(value)) => Ok(*value),
+        Some(value) => Err(format!("property '{}' is not a bool: {}", name, value)),
+    }
+}
+
+fn get_glyph_property(properties: &[TiledProperty]) -> Result<Option<char>, String> {
+    get_string_property(properties, "glyph")?
+        .map(|glyph| {
+            let mut chars = glyph.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("glyph '{}' is not a single character", glyph)),
+            }
+        })
+        .transpose()
+}
+
+/// Read a property which is written in RON, as in prefab files.
+fn get_ron_property<T: DeserializeOwned>(
+    properties: &[TiledProperty],
+    name: &str,
+) -> Result<Option<T>, String> {
+    get_string_property(properties, name)?
+        .map(|value| {
+            ron::de::from_str(&value)
+                .map_err(|err| format!("could not read property '{}': {}", name, err))
+        })
+        .transpose()
+}
+
+impl TiledMap {
+    /// Get the area and all entities of the map, or an error for the first tile
+    /// or object which can not be read.
+    pub fn get_prefab_data(&self) -> Result<Vec<AreaMapPrefab>, String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!(
+                "map must not be empty, but it is {}x{} tiles",
+                self.width, self.height
+            ));
+        }
+
+        let name = get_string_property(&self.properties, "name")?.unwrap_or_default();
+
+        let mut data = vec![AreaMapPrefab::Area(Area {
+            name,
+            dimensions: [self.width, self.height],
+        })];
+
+        for layer in &self.layers {
+            match layer {
+                TiledLayer::Tiles {
+                    name,
+                    data: tiles,
+                    encoding,
+                } => {
+                    if encoding.as_ref().map(|e| e != "csv").unwrap_or(false) {
+                        return Err(format!(
+                            "tile layer '{}' must be saved with CSV encoding",
+                            name
+                        ));
+                    }
+
+                    let tile_count = self.width as usize * self.height as usize;
+
+                    if tiles.len() != tile_count {
+                        return Err(format!(
+                            "tile layer '{}' has {} tiles but the map has {}",
+                            name,
+                            tiles.len(),
+                            tile_count
+                        ));
+                    }
+
+                    for (i, &gid) in tiles.iter().enumerate() {
+                        let position = self.get_tile_position(i as u32);
+                        let tile = self
+                            .get_tile(gid, position)
+                            .map_err(|err| format!("tile layer '{}': {}", name, err))?;
+
+                        data.extend(tile);
+                    }
+                }
+                TiledLayer::Objects { name, objects } => {
+                    for object in objects {
+                        let entity = self.get_object(object).map_err(|err| {
+                            format!("object '{}' in layer '{}': {}", object.name, name, err)
+                        })?;
+
+                        data.push(entity);
+                    }
+                }
+                TiledLayer::Other => (),
+            }
+        }
+
+        number_triggers(&mut data);
+
+        Ok(data)
+    }
+
+    /// Create the prefab with the area as its main entity.
+    pub fn to_prefab(&self) -> Result<Prefab<AreaMapPrefab>, String> {
+        self.get_prefab_data().map(get_area_map_prefab)
+    }
+
+    fn get_tile_position(&self, index: u32) -> Position {
+        let row = index / self.width;
+
+        Position {
+            x: index % self.width,
+            y: self.height.saturating_sub(row + 1),
+        }
+    }
+
+    fn get_tile(&self, gid: u32, position: Position) -> Result<Option<AreaMapPrefab>, String> {
+        let gid = gid & !TILED_FLIP_FLAGS;
+
+        if gid == 0 {
+            return Ok(None);
+        }
+
+        let properties = self
+            .tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)
+            .and_then(|tileset| {
+                tileset
+                    .tiles
+                    .iter()
+                    .find(|tile| tile.id == gid - tileset.first_gid)
+            })
+            .map(|tile| tile.properties.as_slice())
+            .unwrap_or(&[]);
+
+        let glyph = get_glyph_property(properties)?
+            .ok_or_else(|| format!("tile {} has no glyph property", gid))?;
+
+        Ok(Some(AreaMapPrefab::Tile {
+            glyph,
+            position,
+            collision: get_bool_property(properties, "collision")?,
+        }))
+    }
+
+    /// Get the tile under the center of an object.
+    fn get_object_position(&self, object: &TiledObject) -> Result<Position, String> {
+        let x = object.x + object.width / 2.0;
+        let y = match object.gid {
+            Some(_) => object.y - object.height / 2.0,
+            None => object.y + object.height / 2.0,
+        };
+
+        let column = (x / self.tile_width as f32).floor();
+        let row = (y / self.tile_height as f32).floor();
+
+        if column < 0.0 || row < 0.0 || column >= self.width as f32 || row >= self.height as f32 {
+            return Err(format!(
+                "({}, {}) is outside of the map",
+                object.x, object.y
+            ));
+        }
+
+        Ok(Position {
+            x: column as u32,
+            y: self.height - 1 - row as u32,
+        })
+    }
+
+    /// Get the tiles which are covered by an object, or the tile under it for points.
+    fn get_object_region(&self, object: &TiledObject) -> Result<TriggerRegion, String> {
+        let (tw, th) = (self.tile_width as f32, self.tile_height as f32);
+
+        let column = (object.x / tw).floor();
+        let row = (object.y / th).floor();
+        let columns = ((object.x + object.width) / tw).ceil().max(column + 1.0);
+        let rows = ((object.y + object.height) / th).ceil().max(row + 1.0);
+
+        if column < 0.0 || row < 0.0 || columns > self.width as f32 || rows > self.height as f32 {
+            return Err(format!(
+                "({}, {}) to ({}, {}) is outside of the map",
+                object.x,
+                object.y,
+                object.x + object.width,
+                object.y + object.height
+            ));
+        }
+
+        Ok(TriggerRegion::Rect {
+            x: column as u32,
+            y: self.height - rows as u32,
+            width: (columns - column) as u32,
+            height: (rows - row) as u32,
+        })
+    }
+
+    fn get_object(&self, object: &TiledObject) -> Result<AreaMapPrefab, String> {
+        let properties = &object.properties;
+
+        let entity = match object.kind.as_str() {
+            "character" => {
+                AreaMapPrefab::Character(CharacterPrefab::non_player(NonPlayerCharacterPrefab {
+                    glyph: get_glyph_property(properties)?
+                        .ok_or("characters need a glyph property")?,
+                    name: object.name.clone(),
+                    position: self.get_object_position(object)?,
+                    faction: get_string_property(properties, "faction")?,
+                    inventory: get_ron_property(properties, "inventory")?,
+                    relationships: get_ron_property(properties, "relationships")?,
+                    dialogue: get_ron_property(properties, "dialogue")?,
+                    barks: get_ron_property(properties, "barks")?,
+                }))
+            }
+            "prop" => AreaMapPrefab::Tile {
+                glyph: get_glyph_property(properties)?.ok_or("props need a glyph property")?,
+                position: self.get_object_position(object)?,
+                collision: get_bool_property(properties, "collision")?,
+            },
+            "door" => AreaMapPrefab::Tile {
+                glyph: get_glyph_property(properties)?.unwrap_or(DEFAULT_DOOR_GLYPH),
+                position: self.get_object_position(object)?,
+                collision: false,
+            },
+            "item" => AreaMapPrefab::Item {
+                glyph: get_glyph_property(properties)?.ok_or("items need a glyph property")?,
+                position: self.get_object_position(object)?,
+                item: get_ron_property::<Item>(properties, "item")?
+                    .ok_or("items need an item property")?,
+            },
+            "trigger" => AreaMapPrefab::Trigger(AreaTrigger::new(
+                self.get_object_region(object)?,
+                match get_string_property(properties, "on")?
+                    .as_ref()
+                    .map(String::as_str)
+                {
+                    Some("enter") => TriggerOn::Enter,
+                    Some("exit") => TriggerOn::Exit,
+                    Some("interact") => TriggerOn::Interact,
+                    _ => {
+                        return Err(
+                            "triggers need an on property of enter, exit or interact".to_string()
+                        )
+                    }
+                },
+                get_ron_property(properties, "actions")?
+                    .ok_or("triggers need an actions property")?,
+                get_bool_property(properties, "once")?,
+                get_ron_property(properties, "conditions")?.unwrap_or_default(),
+            )),
+            kind => return Err(format!("unknown object type '{}'", kind)),
+        };
+
+        Ok(entity)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Format for reading a `TiledMap` in Tiled's JSON format as a prefab.
+pub struct TiledMapFormat;
+
+impl Format<Prefab<AreaMapPrefab>> for TiledMapFormat {
+    fn name(&self) -> &'static str {
+        "TiledMap"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<Prefab<AreaMapPrefab>, Error> {
+        let tiled_map: TiledMap = serde_json::from_slice(&bytes)
+            .map_err(|err| Error::from_string(format!("could not parse Tiled map: {}", err)))?;
+
+        tiled_map.to_prefab().map_err(Error::from_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::script::ScriptAction;
+
+    fn read_sample_map() -> TiledMap {
+        serde_json::from_str(include_str!("../../../assets/area/lobby.json")).unwrap()
+    }
+
+    fn get_entities_at(data: &[AreaMapPrefab], x: u32, y: u32) -> Vec<&AreaMapPrefab> {
+        data.iter()
+            .filter(|entity| entity.position() == Some(Position { x, y }))
+            .collect()
+    }
+
+    #[test]
+    fn sample_map_is_read_into_an_area() {
+        let data = read_sample_map().get_prefab_data().unwrap();
+
+        match &data[0] {
+            AreaMapPrefab::Area(area) => {
+                assert_eq!(area.name, "Lobby");
+                assert_eq!(area.dimensions, [8, 6]);
+            }
+            other => panic!("expected the area, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tiles_are_placed_with_the_top_row_highest() {
+        let data = read_sample_map().get_prefab_data().unwrap();
+
+        match get_entities_at(&data, 0, 5)[..] {
+            [AreaMapPrefab::Tile {
+                glyph, collision, ..
+            }] => {
+                assert_eq!(*glyph, '#');
+                assert!(*collision);
+            }
+            ref other => panic!("expected a wall, got {:?}", other),
+        }
+
+        assert!(get_entities_at(&data, 1, 4).is_empty());
+    }
+
+    #[test]
+    fn objects_are_placed_at_the_tile_under_their_center() {
+        let data = read_sample_map().get_prefab_data().unwrap();
+
+        match get_entities_at(&data, 2, 3)[..] {
+            [AreaMapPrefab::Character(character)] => {
+                assert_eq!(character.glyph(), 'm');
+                assert_eq!(character.name(), Some("Marge"));
+                assert_eq!(character.faction(), Some("reform"));
+            }
+            ref other => panic!("expected a character, got {:?}", other),
+        }
+
+        match get_entities_at(&data, 5, 2)[..] {
+            [AreaMapPrefab::Item { glyph, item, .. }] => {
+                assert_eq!(*glyph, '$');
+                assert_eq!(*item, Item::Money(50));
+            }
+            ref other => panic!("expected an item, got {:?}", other),
+        }
+
+        match get_entities_at(&data, 4, 0)[..] {
+            [AreaMapPrefab::Tile {
+                glyph, collision, ..
+            }] => {
+                assert_eq!(*glyph, DEFAULT_DOOR_GLYPH);
+                assert!(!*collision);
+            }
+            ref other => panic!("expected a door, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn trigger_objects_cover_the_tiles_under_them() {
+        let data = read_sample_map().get_prefab_data().unwrap();
+
+        let triggers = data
+            .iter()
+            .filter_map(|entity| match entity {
+                AreaMapPrefab::Trigger(trigger) => Some(trigger),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(triggers.len(), 1);
+
+        let trigger = triggers[0];
+        assert_eq!(trigger.on, TriggerOn::Enter);
+        assert_eq!(trigger.region.tiles(), vec![Position { x: 4, y: 0 }]);
+        assert_eq!(
+            trigger.actions,
+            vec![ScriptAction::ChangeArea {
+                map: "area/cityhall.ron".to_string(),
+                position: Position { x: 4, y: 6 },
+            }]
+        );
+    }
+
+    #[test]
+    fn wide_trigger_objects_cover_all_tiles_under_them() {
+        let map = read_sample_map();
+        let object = TiledObject {
+            name: "Hallway".to_string(),
+            kind: "trigger".to_string(),
+            x: 16.0,
+            y: 8.0,
+            width: 40.0,
+            height: 8.0,
+            gid: None,
+            properties: Vec::new(),
+        };
+
+        assert_eq!(
+            map.get_object_region(&object).unwrap().tiles(),
+            vec![
+                Position { x: 1, y: 5 },
+                Position { x: 2, y: 5 },
+                Position { x: 3, y: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_object_types_and_tiles_without_glyphs_are_errors() {
+        let mut map = read_sample_map();
+
+        if let TiledLayer::Objects { objects, .. } = &mut map.layers[1] {
+            objects[0].kind = "vehicle".to_string();
+        }
+
+        assert_eq!(
+            map.get_prefab_data().unwrap_err(),
+            "object 'Marge' in layer 'objects': unknown object type 'vehicle'"
+        );
+
+        let mut map = read_sample_map();
+        map.tilesets[0].tiles.clear();
+
+        assert_eq!(
+            map.get_prefab_data().unwrap_err(),
+            "tile layer 'tiles': tile 1 has no glyph property"
+        );
+    }
+
+    #[test]
+    fn empty_maps_are_errors() {
+        let mut map = read_sample_map();
+        map.width = 0;
+
+        assert_eq!(
+            map.get_prefab_data().unwrap_err(),
+            "map must not be empty, but it is 0x6 tiles"
+        );
+    }
+
+    #[test]
+    fn tile_layers_which_do_not_fit_the_map_are_errors() {
+        let mut map = read_sample_map();
+
+        if let TiledLayer::Tiles { data, .. } = &mut map.layers[0] {
+            data.extend(&[1; 8]);
+        }
+
+        assert_eq!(
+            map.get_prefab_data().unwrap_err(),
+            "tile layer 'tiles' has 56 tiles but the map has 48"
+        );
+
+        let mut map = read_sample_map();
+
+        if let TiledLayer::Tiles { data, .. } = &mut map.layers[0] {
+            data.pop();
+        }
+
+        assert_eq!(
+            map.get_prefab_data().unwrap_err(),
+            "tile layer 'tiles' has 47 tiles but the map has 48"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use amethyst::{
+    core::timing::duration_to_secs,
+    ecs::prelude::{Component, DenseVecStorage},
+};
+
+use std::time::Duration;
+
+use super::area::Position;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// How a tween moves from its start to its target over time.
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Start fast and slow down towards the target.
+    EaseOut,
+}
+
+impl Easing {
+    /// Map the fraction of time passed (in [0, 1]) to the fraction of distance moved.
+    fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Grid position that an entity is rendered at while it slides towards its `Position`.
+///
+/// Game logic only uses the `Position`, this is purely for rendering.
+pub struct Tween {
+    from: [f32; 2],
+    to: Position,
+    /// Time since the move towards `to` started, or `None` while at rest.
+    elapsed: Option<Duration>,
+    easing: Easing,
+}
+
+impl Component for Tween {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Tween {
+    /// Create a tween which is at rest at the position.
+    pub fn new(position: &Position, easing: Easing) -> Self {
+        Tween {
+            from: get_grid_coordinates(position),
+            to: position.clone(),
+            elapsed: None,
+            easing,
+        }
+    }
+
+    /// Current grid coordinates after moving for the given total duration.
+    pub fn current(&self, duration: Duration) -> [f32; 2] {
+        let [x0, y0] = self.from;
+        let [x1, y1] = get_grid_coordinates(&self.to);
+
+        let t = match self.elapsed {
+            Some(elapsed) if elapsed < duration => {
+                duration_to_secs(elapsed) / duration_to_secs(duration)
+            }
+            _ => 1.0,
+        };
+
+        let s = self.easing.apply(t);
+
+        [x0 + (x1 - x0) * s, y0 + (y1 - y0) * s]
+    }
+
+    pub fn is_finished(&self, duration: Duration) -> bool {
+        self.elapsed.map_or(true, |elapsed| elapsed >= duration)
+    }
+
+    /// Start moving towards a new target from the current coordinates, unless it
+    /// already is the target.
+    pub fn retarget(&mut self, target: &Position, duration: Duration) {
+        if &self.to != target {
+            self.from = self.current(duration);
+            self.to = target.clone();
+            self.elapsed = Some(Duration::from_secs(0));
+        }
+    }
+
+    /// Place the tween at rest at the position at once, for positions which are set
+    /// directly instead of stepped to.
+    pub fn snap_to(&mut self, position: &Position) {
+        self.from = get_grid_coordinates(position);
+        self.to = position.clone();
+        self.elapsed = None;
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        if let Some(ref mut elapsed) = self.elapsed {
+            *elapsed += delta;
+        }
+    }
+}
+
+fn get_grid_coordinates(position: &Position) -> [f32; 2] {
+    [position.x as f32, position.y as f32]
+}
+
+/// Get the grid coordinates to render an entity at: the tween if it has one,
+/// otherwise its position.
+pub fn get_render_coordinates(
+    position: &Position,
+    tween: Option<&Tween>,
+    duration: Duration,
+) -> [f32; 2] {
+    tween
+        .map(|tween| tween.current(duration))
+        .unwrap_or_else(|| get_grid_coordinates(position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn new_tweens_are_at_rest() {
+        let tween = Tween::new(&Position { x: 3, y: 4 }, Easing::Linear);
+
+        assert!(tween.is_finished(millis(100)));
+        assert_eq!(tween.current(millis(100)), [3.0, 4.0]);
+    }
+
+    #[test]
+    fn linear_tweens_move_at_constant_speed() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::Linear);
+        tween.retarget(&Position { x: 2, y: 1 }, millis(100));
+
+        assert_eq!(tween.current(millis(100)), [0.0, 0.0]);
+
+        tween.advance(millis(50));
+        assert_eq!(tween.current(millis(100)), [1.0, 0.5]);
+
+        tween.advance(millis(100));
+        assert!(tween.is_finished(millis(100)));
+        assert_eq!(tween.current(millis(100)), [2.0, 1.0]);
+    }
+
+    #[test]
+    fn ease_out_tweens_are_past_halfway_at_half_time() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::EaseOut);
+        tween.retarget(&Position { x: 4, y: 0 }, millis(100));
+        tween.advance(millis(50));
+
+        let [x, _] = tween.current(millis(100));
+        assert!(x > 2.0 && x < 4.0);
+    }
+
+    #[test]
+    fn retargeting_starts_from_the_current_coordinates() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::Linear);
+        tween.retarget(&Position { x: 2, y: 0 }, millis(100));
+        tween.advance(millis(50));
+
+        tween.retarget(&Position { x: 1, y: 2 }, millis(100));
+        assert_eq!(tween.current(millis(100)), [1.0, 0.0]);
+
+        tween.advance(millis(100));
+        assert_eq!(tween.current(millis(100)), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn retargeting_to_the_same_position_does_not_restart() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::Linear);
+        tween.retarget(&Position { x: 2, y: 0 }, millis(100));
+        tween.advance(millis(50));
+        tween.retarget(&Position { x: 2, y: 0 }, millis(100));
+
+        assert_eq!(tween.current(millis(100)), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn snapping_stops_at_the_position_at_once() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::Linear);
+        tween.retarget(&Position { x: 2, y: 0 }, millis(100));
+        tween.advance(millis(50));
+
+        tween.snap_to(&Position { x: 20, y: 10 });
+        assert!(tween.is_finished(millis(100)));
+        assert_eq!(tween.current(millis(100)), [20.0, 10.0]);
+
+        tween.retarget(&Position { x: 20, y: 10 }, millis(100));
+        assert!(tween.is_finished(millis(100)));
+    }
+
+    #[test]
+    fn zero_duration_tweens_are_always_at_their_target() {
+        let mut tween = Tween::new(&Position { x: 0, y: 0 }, Easing::Linear);
+        tween.retarget(&Position { x: 2, y: 0 }, millis(0));
+
+        assert_eq!(tween.current(millis(0)), [2.0, 0.0]);
+    }
+}
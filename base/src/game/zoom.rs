@@ -0,0 +1,102 @@
+use amethyst::{renderer::Camera, window::ScreenDimensions};
+
+use super::consts::{GLYPH_FONT_SIZE, TILE_HEIGHT, TILE_WIDTH};
+
+/// Scales of the area grid that the player can zoom between.
+pub const ZOOM_LEVELS: [f32; 3] = [1.0, 2.0, 3.0];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Use as a resource to keep track of the current zoom level of the area grid.
+///
+/// Tiles and glyphs are rendered at `TILE_WIDTH`, `TILE_HEIGHT` and `GLYPH_FONT_SIZE`
+/// multiplied by the scale of the level.
+pub struct Zoom {
+    /// Index into `ZOOM_LEVELS`.
+    level: usize,
+}
+
+impl Zoom {
+    pub fn scale(&self) -> f32 {
+        ZOOM_LEVELS[self.level]
+    }
+
+    /// Size of area grid tiles on screen (in pixels).
+    pub fn tile_size(&self) -> (f32, f32) {
+        (
+            TILE_WIDTH as f32 * self.scale(),
+            TILE_HEIGHT as f32 * self.scale(),
+        )
+    }
+
+    pub fn glyph_font_size(&self) -> f32 {
+        GLYPH_FONT_SIZE * self.scale()
+    }
+
+    /// Go to the next zoom level. Returns whether the level changed.
+    pub fn zoom_in(&mut self) -> bool {
+        let level = (self.level + 1).min(ZOOM_LEVELS.len() - 1);
+        self.set_level(level)
+    }
+
+    /// Go to the previous zoom level. Returns whether the level changed.
+    pub fn zoom_out(&mut self) -> bool {
+        let level = self.level.saturating_sub(1);
+        self.set_level(level)
+    }
+
+    fn set_level(&mut self, level: usize) -> bool {
+        let changed = level != self.level;
+        self.level = level;
+
+        changed
+    }
+}
+
+/// Create a 2D camera which shows the world at the zoom scale.
+pub fn get_zoomed_camera(dimensions: &ScreenDimensions, zoom: &Zoom) -> Camera {
+    Camera::standard_2d(
+        dimensions.width() / zoom.scale(),
+        dimensions.height() / zoom.scale(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_zoom_is_unscaled() {
+        let zoom = Zoom::default();
+
+        assert_eq!(zoom.scale(), 1.0);
+        assert_eq!(zoom.tile_size(), (TILE_WIDTH as f32, TILE_HEIGHT as f32));
+        assert_eq!(zoom.glyph_font_size(), GLYPH_FONT_SIZE);
+    }
+
+    #[test]
+    fn zooming_scales_tiles_and_glyphs() {
+        let mut zoom = Zoom::default();
+
+        assert!(zoom.zoom_in());
+        assert_eq!(
+            zoom.tile_size(),
+            (2.0 * TILE_WIDTH as f32, 2.0 * TILE_HEIGHT as f32)
+        );
+        assert_eq!(zoom.glyph_font_size(), 2.0 * GLYPH_FONT_SIZE);
+    }
+
+    #[test]
+    fn zoom_stops_at_the_first_and_last_levels() {
+        let mut zoom = Zoom::default();
+
+        assert!(!zoom.zoom_out());
+        assert_eq!(zoom.scale(), ZOOM_LEVELS[0]);
+
+        for _ in 0..ZOOM_LEVELS.len() - 1 {
+            assert!(zoom.zoom_in());
+        }
+
+        assert!(!zoom.zoom_in());
+        assert_eq!(zoom.scale(), ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+    }
+}
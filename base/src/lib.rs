@@ -0,0 +1,11 @@
+pub mod bundle;
+pub mod config;
+pub mod game;
+pub mod lint;
+pub mod locale;
+pub mod menu;
+pub mod render;
+pub mod replay;
+pub mod systems;
+pub mod texture;
+pub mod validate;
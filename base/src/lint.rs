@@ -0,0 +1,470 @@
+use amethyst::assets::Prefab;
+
+use inkling::read_story_from_string;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    game::{
+        get_story_variable_names, AreaMap, AreaMapPrefab, CharacterPrefab, Dialogue,
+        ObjectivesData, ScriptAction, TiledMap, Trigger,
+    },
+    validate::{
+        get_code_lines, get_data_with_lines, list_files, read_file, read_json_file, Problem,
+    },
+};
+
+/// Diverts to these end the story instead of going to a knot.
+const STORY_ENDS: [&str; 2] = ["END", "DONE"];
+
+/// Words in ink conditions which are not variable names.
+const INK_KEYWORDS: [&str; 5] = ["and", "or", "not", "true", "false"];
+
+#[derive(Clone, Debug, PartialEq)]
+/// Dialogue of a character, along with where the character is declared.
+pub struct StoryReference {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub character: String,
+    pub dialogue: Dialogue,
+}
+
+impl StoryReference {
+    fn problem(&self, message: String) -> Problem {
+        Problem {
+            path: self.path.clone(),
+            line: self.line,
+            message,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A knot which is declared in a story.
+pub struct Knot {
+    pub name: String,
+    /// Line of the declaration (starting from 1).
+    pub line: usize,
+}
+
+/// Read every ink story which is the dialogue of a character in a prefab or area
+/// map below the application root and check it for problems:
+///
+///  * stories which are missing or which inkling can not parse
+///  * knots which no divert or character dialogue leads to
+///  * characters whose dialogue starts from a knot which does not exist
+///  * flags of the game which a story uses without declaring them with `VAR`
+///
+/// The flags of the game are those which are set by area triggers or tested by
+/// area triggers and objectives.
+pub fn lint_stories(app_root: &Path) -> Vec<Problem> {
+    let assets = app_root.join("assets");
+
+    let mut problems = Vec::new();
+    let mut references = Vec::new();
+    let mut flags = HashSet::new();
+
+    for path in list_files(&assets.join("prefab"), &["ron"], &mut problems) {
+        if let Some((source, prefab)) = read_file::<Prefab<CharacterPrefab>>(&path, &mut problems) {
+            references.extend(
+                get_data_with_lines(&source, &prefab)
+                    .into_iter()
+                    .filter_map(|(line, character)| get_story_reference(&path, line, character)),
+            );
+        }
+    }
+
+    for path in list_files(&assets.join("area"), &["ron", "json"], &mut problems) {
+        let (source, data) = if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+            match read_json_file::<TiledMap>(&path, &mut problems) {
+                Some(tiled_map) => (None, tiled_map.get_prefab_data()),
+                None => continue,
+            }
+        } else {
+            match read_file::<AreaMap>(&path, &mut problems) {
+                Some((source, area_map)) => (
+                    Some(source),
+                    area_map.get_prefab_data().map_err(|err| err.to_string()),
+                ),
+                None => continue,
+            }
+        };
+
+        // Problems with the area itself are reported by `validate-assets`
+        for entity in data.unwrap_or_default() {
+            match entity {
+                AreaMapPrefab::Character(character) => {
+                    let line = character.dialogue().and_then(|dialogue| {
+                        source
+                            .as_ref()
+                            .and_then(|source| find_line(source, &dialogue.story))
+                    });
+
+                    references.extend(get_story_reference(&path, line, &character));
+                }
+                AreaMapPrefab::Trigger(trigger) => {
+                    flags.extend(trigger.conditions.iter().map(|c| c.name().to_string()));
+                    flags.extend(trigger.actions.iter().filter_map(|action| match action {
+                        ScriptAction::SetFlag(name, _) => Some(name.clone()),
+                        _ => None,
+                    }));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let objectives_path = assets.join("objectives").join("objectives.ron");
+
+    if let Some((_, objectives)) = read_file::<ObjectivesData>(&objectives_path, &mut problems) {
+        flags.extend(objectives.objectives.iter().filter_map(
+            |objective| match objective.trigger {
+                Trigger::Flag(ref condition) => Some(condition.name().to_string()),
+                _ => None,
+            },
+        ));
+    }
+
+    let mut stories: BTreeMap<&str, Vec<&StoryReference>> = BTreeMap::new();
+
+    for reference in references.iter() {
+        stories
+            .entry(reference.dialogue.story.as_str())
+            .or_default()
+            .push(reference);
+    }
+
+    for (story, references) in stories {
+        problems.extend(check_story(&assets.join(story), &references, &flags));
+    }
+
+    problems
+}
+
+/// Check a story which is the dialogue of the referencing characters.
+fn check_story(
+    path: &Path,
+    references: &[&StoryReference],
+    flags: &HashSet<String>,
+) -> Vec<Problem> {
+    let ink = match fs::read_to_string(path) {
+        Ok(ink) => ink,
+        Err(err) => {
+            return references
+                .iter()
+                .map(|reference| {
+                    reference.problem(format!(
+                        "could not read story '{}' of {} ({})",
+                        reference.dialogue.story, reference.character, err
+                    ))
+                })
+                .collect();
+        }
+    };
+
+    let mut problems = Vec::new();
+
+    if let Err(err) = read_story_from_string(&ink) {
+        problems.push(Problem {
+            path: path.to_path_buf(),
+            line: None,
+            message: format!("could not parse story: {}", err),
+        });
+    }
+
+    let knots = get_knots(&ink);
+
+    for reference in references {
+        if let Some(ref knot) = reference.dialogue.knot {
+            if !knots.iter().any(|k| &k.name == knot) {
+                problems.push(reference.problem(format!(
+                    "dialogue of {} starts from knot '{}' which is not in '{}'",
+                    reference.character, knot, reference.dialogue.story
+                )));
+            }
+        }
+    }
+
+    let start_knots = references
+        .iter()
+        .filter_map(|reference| reference.dialogue.knot.as_ref().map(String::as_str))
+        .collect::<Vec<_>>();
+
+    problems.extend(
+        get_unreachable_knots(&ink, &start_knots)
+            .into_iter()
+            .map(|knot| Problem {
+                path: path.to_path_buf(),
+                line: Some(knot.line),
+                message: format!("knot '{}' can not be reached", knot.name),
+            }),
+    );
+
+    problems.extend(
+        get_undeclared_flags(&ink, flags)
+            .into_iter()
+            .map(|(line, name)| Problem {
+                path: path.to_path_buf(),
+                line: Some(line),
+                message: format!(
+                    "flag '{}' is used but not declared, add `VAR {} = false` to share it with the game",
+                    name, name
+                ),
+            }),
+    );
+
+    problems
+}
+
+fn get_story_reference(
+    path: &Path,
+    line: Option<usize>,
+    character: &CharacterPrefab,
+) -> Option<StoryReference> {
+    character.dialogue().map(|dialogue| StoryReference {
+        path: path.to_path_buf(),
+        line,
+        character: character
+            .name()
+            .unwrap_or("the player character")
+            .to_string(),
+        dialogue: dialogue.clone(),
+    })
+}
+
+/// Get the line of the first code line which contains the text.
+fn find_line(source: &str, text: &str) -> Option<usize> {
+    get_code_lines(source)
+        .find(|(_, line)| line.contains(text))
+        .map(|(number, _)| number)
+}
+
+/// Iterate over the lines of a story along with their numbers (starting from 1),
+/// without line comments.
+fn get_ink_lines(ink: &str) -> impl Iterator<Item = (usize, &str)> {
+    ink.lines().enumerate().map(|(i, line)| {
+        let code = match line.find("//") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+
+        (i + 1, code.trim())
+    })
+}
+
+/// Get the name of the knot which is declared on a line (`=== name ===`), if any.
+/// Functions are not knots.
+fn get_knot_name(line: &str) -> Option<&str> {
+    if !line.starts_with("==") {
+        return None;
+    }
+
+    let name = line
+        .trim_start_matches('=')
+        .split(|c: char| c.is_whitespace() || c == '=' || c == '(')
+        .find(|word| !word.is_empty())?;
+
+    if name == "function" {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Get all knots which are declared in a story, in order.
+pub fn get_knots(ink: &str) -> Vec<Knot> {
+    get_ink_lines(ink)
+        .filter_map(|(line, code)| {
+            get_knot_name(code).map(|name| Knot {
+                name: name.to_string(),
+                line,
+            })
+        })
+        .collect()
+}
+
+/// Get the knots of a story which can not be reached by following diverts from the
+/// top of the story or from any of the knots that the story is started from.
+pub fn get_unreachable_knots(ink: &str, start_knots: &[&str]) -> Vec<Knot> {
+    // Diverts from the top of the story are kept under the empty name, which no
+    // knot can have
+    let mut diverts: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current_knot = Some("");
+
+    for (_, code) in get_ink_lines(ink) {
+        if code.starts_with("==") {
+            // Diverts in functions are not followed
+            current_knot = get_knot_name(code);
+            continue;
+        }
+
+        if let Some(knot) = current_knot {
+            diverts
+                .entry(knot)
+                .or_insert_with(Vec::new)
+                .extend(get_divert_targets(code));
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut queue = start_knots.to_vec();
+    queue.push("");
+
+    while let Some(knot) = queue.pop() {
+        if reachable.insert(knot) {
+            if let Some(targets) = diverts.get(knot) {
+                queue.extend(targets);
+            }
+        }
+    }
+
+    get_knots(ink)
+        .into_iter()
+        .filter(|knot| !reachable.contains(knot.name.as_str()))
+        .collect()
+}
+
+/// Get the knots that a line diverts to (`-> name` or `-> name.stitch`).
+fn get_divert_targets(code: &str) -> impl Iterator<Item = &str> {
+    code.split("->").skip(1).filter_map(|target| {
+        target
+            .trim_start()
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .filter(|knot| !knot.is_empty())
+    })
+}
+
+/// Get the flags of the game which a story uses in its conditions (`{name}`) but
+/// does not declare as variables, along with the line where they are first used.
+pub fn get_undeclared_flags(ink: &str, flags: &HashSet<String>) -> Vec<(usize, String)> {
+    let declared = get_story_variable_names(ink)
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let mut undeclared = Vec::new();
+
+    for (line, code) in get_ink_lines(ink) {
+        for expression in code.split('{').skip(1) {
+            let expression = expression
+                .split(|c| c == '}' || c == ':')
+                .next()
+                .unwrap_or("");
+
+            for word in expression.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+                let is_new = !undeclared.iter().any(|(_, name)| name == word);
+
+                if flags.contains(word)
+                    && !declared.contains(word)
+                    && !INK_KEYWORDS.contains(&word)
+                    && !STORY_ENDS.contains(&word)
+                    && is_new
+                {
+                    undeclared.push((line, word.to_string()));
+                }
+            }
+        }
+    }
+
+    undeclared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORY: &str = "
+VAR donations = 0
+
+Hello. -> greeting
+
+=== greeting ===
+= first_time
+{met_the_mayor: You again.|Nice to meet you.}
+*   [Donate] -> donate
+*   {donations > 2 and not met_the_mayor} [Leave] -> END
+*   [Wait] -> greeting.first_time
+
+=== donate ===
+Thanks. -> END
+
+=== secret === // only the mayor knows about this
+Nobody comes here. -> secret
+
+=== function double(x) ===
+~ return x * 2
+";
+
+    #[test]
+    fn knots_are_declared_with_two_or_more_equals_signs() {
+        let names = get_knots(STORY)
+            .into_iter()
+            .map(|knot| (knot.name, knot.line))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                ("greeting".to_string(), 6),
+                ("donate".to_string(), 13),
+                ("secret".to_string(), 16)
+            ]
+        );
+    }
+
+    #[test]
+    fn knots_which_are_only_diverted_to_from_themselves_are_unreachable() {
+        let unreachable = get_unreachable_knots(STORY, &[]);
+
+        assert_eq!(unreachable.len(), 1);
+        assert_eq!(unreachable[0].name, "secret");
+
+        assert!(get_unreachable_knots(STORY, &["secret"]).is_empty());
+    }
+
+    #[test]
+    fn knots_which_are_only_diverted_to_from_unreachable_knots_are_unreachable() {
+        let story = "
+Hello. -> END
+
+=== orphan ===
+Nobody diverts here. -> next
+
+=== next ===
+Only the orphan diverts here. -> END
+";
+
+        let names = get_unreachable_knots(story, &[])
+            .into_iter()
+            .map(|knot| knot.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["orphan".to_string(), "next".to_string()]);
+        assert!(get_unreachable_knots(story, &["orphan"]).is_empty());
+    }
+
+    #[test]
+    fn flags_used_in_conditions_must_be_declared() {
+        let flags = ["met_the_mayor", "donations", "read_waypoint_sign"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            get_undeclared_flags(STORY, &flags),
+            vec![(8, "met_the_mayor".to_string())]
+        );
+    }
+
+    #[test]
+    fn bundled_stories_have_no_problems() {
+        let app_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let problems = lint_stories(&app_root);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+}
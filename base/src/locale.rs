@@ -0,0 +1,359 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::validate::{get_code_lines, list_files, read_file, Problem};
+
+/// Language that all keys must be defined in. Used for keys which are missing
+/// from other languages.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Texts which start with this are keys in the string tables, such as
+/// `"@menu.play_game"` in UI files.
+pub const KEY_PREFIX: char = '@';
+
+/// Names of characters are translated with keys of this prefix followed by the name.
+/// They are optional in every language, including the default.
+pub const CHARACTER_KEY_PREFIX: &str = "character.";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Translated strings of a language by key, read from `assets/locale/<language>.ron`.
+pub struct StringTable {
+    /// Name of the language, in the language itself.
+    pub name: String,
+    pub strings: BTreeMap<String, String>,
+}
+
+impl StringTable {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("could not read string table '{}': {}", path.display(), err))?;
+
+        ron::de::from_str(&contents)
+            .map_err(|err| format!("could not parse string table '{}': {}", path.display(), err))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Use as a resource to look up strings in the current language.
+///
+/// Strings which are missing from the current language are taken from the default
+/// language, and keys which are missing from both are shown as they are.
+pub struct Locale {
+    /// Directory of the string tables.
+    directory: PathBuf,
+    language: String,
+    table: StringTable,
+    fallback: StringTable,
+}
+
+impl Locale {
+    /// Read the string tables of the language and the default language from the
+    /// `locale` directory of the assets.
+    pub fn load(assets: &Path, language: &str) -> Result<Self, String> {
+        let directory = assets.join("locale");
+        let fallback = StringTable::read(&get_table_path(&directory, DEFAULT_LANGUAGE))?;
+
+        let mut locale = Locale {
+            directory,
+            language: DEFAULT_LANGUAGE.to_string(),
+            table: fallback.clone(),
+            fallback,
+        };
+
+        locale.set_language(language)?;
+
+        Ok(locale)
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Switch to another language. Labels are updated by the `LocaleSystem`.
+    pub fn set_language(&mut self, language: &str) -> Result<(), String> {
+        self.table = if language == DEFAULT_LANGUAGE {
+            self.fallback.clone()
+        } else {
+            StringTable::read(&get_table_path(&self.directory, language))?
+        };
+
+        self.language = language.to_string();
+
+        Ok(())
+    }
+
+    /// Codes and names of all languages with a string table, sorted by code.
+    pub fn languages(&self) -> Vec<(String, String)> {
+        list_files(&self.directory, &["ron"], &mut Vec::new())
+            .into_iter()
+            .filter_map(|path| {
+                let code = path.file_stem()?.to_str()?.to_string();
+                let table = StringTable::read(&path).ok()?;
+
+                Some((code, table.name))
+            })
+            .collect()
+    }
+
+    /// Get the string of a key.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.find(key).unwrap_or(key)
+    }
+
+    /// Get the string of a text which is a key (`@key`), or the text itself if it
+    /// is not a key.
+    pub fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+        match get_key(text) {
+            Some(key) => self.get(key),
+            None => text,
+        }
+    }
+
+    /// Get the name of a character in the current language. Names are translated
+    /// with the key `character.<name>` and stay as they are without one.
+    pub fn character_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.find(&format!("{}{}", CHARACTER_KEY_PREFIX, name))
+            .unwrap_or(name)
+    }
+
+    /// Get the path of a story in the current language. Stories are translated as
+    /// whole files in `<language>/` next to the original story, and the original
+    /// is played if there is no translation.
+    pub fn story_path(&self, path: &str) -> String {
+        let original = Path::new(path);
+
+        let translated = match (original.parent(), original.file_name()) {
+            (Some(parent), Some(name)) if self.language != DEFAULT_LANGUAGE => {
+                parent.join(&self.language).join(name)
+            }
+            _ => return path.to_string(),
+        };
+
+        let assets = self.directory.parent().unwrap_or_else(|| Path::new("."));
+
+        if assets.join(&translated).exists() {
+            translated.to_string_lossy().replace('\\', "/")
+        } else {
+            path.to_string()
+        }
+    }
+
+    fn find(&self, key: &str) -> Option<&str> {
+        self.table
+            .strings
+            .get(key)
+            .or_else(|| self.fallback.strings.get(key))
+            .map(String::as_str)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Key of the string which is shown by a `UiText`, added by the `LocaleSystem` to
+/// labels whose text is a key.
+pub struct LocalizedText(pub String);
+
+impl Component for LocalizedText {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Get the key of a text which is a key (`@key`). Single characters are glyphs,
+/// not keys.
+pub fn get_key(text: &str) -> Option<&str> {
+    if text.starts_with(KEY_PREFIX) && text.len() > KEY_PREFIX.len_utf8() {
+        Some(&text[KEY_PREFIX.len_utf8()..])
+    } else {
+        None
+    }
+}
+
+fn get_table_path(directory: &Path, language: &str) -> PathBuf {
+    directory.join(format!("{}.ron", language))
+}
+
+/// Get the keys of all quoted strings which are keys (`"@key"`) in a file, along
+/// with their lines.
+pub fn get_referenced_keys(source: &str) -> Vec<(usize, String)> {
+    get_code_lines(source)
+        .flat_map(|(number, line)| {
+            line.split('"')
+                .skip(1)
+                .step_by(2)
+                .filter_map(get_key)
+                .map(move |key| (number, key.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Get the keys of the default language which are missing from another language.
+pub fn get_untranslated_keys<'a>(default: &'a StringTable, table: &StringTable) -> Vec<&'a str> {
+    default
+        .strings
+        .keys()
+        .filter(|key| !table.strings.contains_key(*key))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Read the string tables of all languages below the application root and report
+/// keys which are missing from a language, keys which are not in the default
+/// language and keys which UI files use but the default language does not have.
+/// Names of characters are optional and not reported.
+pub fn check_translations(app_root: &Path) -> Vec<Problem> {
+    let assets = app_root.join("assets");
+    let directory = assets.join("locale");
+
+    let mut problems = Vec::new();
+
+    let default_path = get_table_path(&directory, DEFAULT_LANGUAGE);
+    let default = match read_file::<StringTable>(&default_path, &mut problems) {
+        Some((_, table)) => table,
+        None => return problems,
+    };
+
+    for path in list_files(&assets.join("ui"), &["ron"], &mut problems) {
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            // Unreadable UI files are reported by `validate-assets`
+            Err(_) => continue,
+        };
+
+        for (line, key) in get_referenced_keys(&source) {
+            if !default.strings.contains_key(&key) {
+                problems.push(Problem {
+                    path: path.clone(),
+                    line: Some(line),
+                    message: format!("key '{}' is not in the default language", key),
+                });
+            }
+        }
+    }
+
+    for path in list_files(&directory, &["ron"], &mut problems) {
+        if path == default_path {
+            continue;
+        }
+
+        let (source, table) = match read_file::<StringTable>(&path, &mut problems) {
+            Some(file) => file,
+            None => continue,
+        };
+
+        for key in get_untranslated_keys(&default, &table)
+            .into_iter()
+            .filter(|key| !key.starts_with(CHARACTER_KEY_PREFIX))
+        {
+            problems.push(Problem {
+                path: path.clone(),
+                line: None,
+                message: format!("key '{}' is not translated", key),
+            });
+        }
+
+        for key in get_untranslated_keys(&table, &default)
+            .into_iter()
+            .filter(|key| !key.starts_with(CHARACTER_KEY_PREFIX))
+        {
+            let quoted = format!("\"{}\"", key);
+
+            problems.push(Problem {
+                path: path.clone(),
+                line: get_code_lines(&source)
+                    .find(|(_, line)| line.starts_with(&quoted))
+                    .map(|(number, _)| number),
+                message: format!("key '{}' is not in the default language", key),
+            });
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_table(strings: &[(&str, &str)]) -> StringTable {
+        StringTable {
+            name: String::new(),
+            strings: strings
+                .iter()
+                .map(|&(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn get_locale() -> Locale {
+        Locale {
+            directory: PathBuf::new(),
+            language: "es".to_string(),
+            table: get_table(&[("menu.quit", "Salir"), ("character.Rob", "Roberto")]),
+            fallback: get_table(&[("menu.quit", "Quit"), ("menu.options", "Options")]),
+        }
+    }
+
+    #[test]
+    fn missing_strings_fall_back_to_the_default_language_then_the_key() {
+        let locale = get_locale();
+
+        assert_eq!(locale.get("menu.quit"), "Salir");
+        assert_eq!(locale.get("menu.options"), "Options");
+        assert_eq!(locale.get("menu.unknown"), "menu.unknown");
+    }
+
+    #[test]
+    fn only_texts_with_the_prefix_are_translated() {
+        let locale = get_locale();
+
+        assert_eq!(locale.translate("@menu.quit"), "Salir");
+        assert_eq!(locale.translate("menu.quit"), "menu.quit");
+        assert_eq!(locale.translate("@"), "@");
+    }
+
+    #[test]
+    fn character_names_stay_the_same_without_a_translation() {
+        let locale = get_locale();
+
+        assert_eq!(locale.character_name("Rob"), "Roberto");
+        assert_eq!(locale.character_name("Marge"), "Marge");
+    }
+
+    #[test]
+    fn referenced_keys_are_found_with_their_lines() {
+        let source =
+            "(\n    text: \"@menu.quit\",\n    // text: \"@menu.old\",\n    glyph: \"@\",\n)";
+
+        assert_eq!(
+            get_referenced_keys(source),
+            vec![(2, "menu.quit".to_string())]
+        );
+    }
+
+    #[test]
+    fn keys_missing_from_a_language_are_untranslated() {
+        let default = get_table(&[("menu.quit", "Quit"), ("menu.options", "Options")]);
+        let table = get_table(&[("menu.quit", "Salir")]);
+
+        assert_eq!(
+            get_untranslated_keys(&default, &table),
+            vec!["menu.options"]
+        );
+        assert!(get_untranslated_keys(&table, &default).is_empty());
+    }
+
+    #[test]
+    fn bundled_languages_translate_every_key() {
+        let app_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let problems = check_translations(&app_root);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+}
@@ -1,11 +1,3 @@
-mod bundle;
-mod config;
-mod game;
-mod menu;
-mod render;
-mod systems;
-mod texture;
-
 use amethyst::{
     config::Config as _,
     core::transform::TransformBundle,
@@ -13,20 +5,27 @@ use amethyst::{
     prelude::*,
     renderer::{types::DefaultBackend, RenderingSystem},
     ui::UiBundle,
+    utils::fps_counter::FpsCounterBundle,
     window::WindowBundle,
 };
 
-use std::env::current_dir;
+use std::env::{args, current_dir};
 
-use bundle::SpriteBundle;
-use config::Config;
-use menu::MainMenu;
-use render::ExampleGraph;
+use windy_city_politics::{
+    bundle::{LocaleBundle, SpriteBundle},
+    config::Config,
+    game::AssetsDir,
+    locale::{Locale, DEFAULT_LANGUAGE},
+    menu::MainMenu,
+    render::ExampleGraph,
+    replay::InputMode,
+};
 
 fn main() -> Result<(), amethyst::Error> {
     amethyst::start_logger(Default::default());
 
     let app_root = current_dir().map_err(|err| amethyst::Error::new(err))?;
+    let input_mode = InputMode::from_args(args().skip(1)).map_err(amethyst::Error::from_string)?;
 
     let binding_path = app_root.join("resources").join("bindings_config.ron");
     let config_path = app_root.join("resources").join("config.ron");
@@ -42,14 +41,26 @@ fn main() -> Result<(), amethyst::Error> {
         .with_bundle(input_bundle)?
         .with_bundle(UiBundle::<DefaultBackend, StringBindings>::new())?
         .with_bundle(SpriteBundle)?
+        .with_bundle(FpsCounterBundle::default())?
+        .with_bundle(LocaleBundle)?
         .with_thread_local(RenderingSystem::<DefaultBackend, _>::new(
             ExampleGraph::default(),
         ));
 
     let assets_dir = app_root.join("assets");
 
-    let mut game = Application::build(assets_dir, MainMenu::default())?
+    let locale = Locale::load(&assets_dir, &config.language)
+        .or_else(|msg| {
+            log::error!("{}", msg);
+            Locale::load(&assets_dir, DEFAULT_LANGUAGE)
+        })
+        .map_err(amethyst::Error::from_string)?;
+
+    let mut game = Application::build(assets_dir.clone(), MainMenu::default())?
+        .with_resource(AssetsDir(assets_dir))
         .with_resource(config)
+        .with_resource(locale)
+        .with_resource(input_mode)
         .build(game_data)?;
 
     game.run();
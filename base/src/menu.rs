@@ -1,34 +1,121 @@
 use amethyst::{
     core::{transform::Parent, Hidden},
-    ecs::{world::EntitiesRes, Entity, Join},
-    input::{is_key_down, VirtualKeyCode},
+    ecs::{world::EntitiesRes, Entity, Join, WriteStorage},
+    input::{is_key_down, InputEvent, VirtualKeyCode},
     prelude::*,
-    ui::{UiCreator, UiTransform},
+    ui::{UiCreator, UiFinder, UiText, UiTransform},
 };
 
-use crate::game::Loading;
+use crate::{game::Loading, locale::Locale};
+
+const MENU_SELECTED_COLOR: [f32; 4] = [0.388, 0.887, 0.678, 1.0];
+const MENU_UNSELECTED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MenuOption {
+    Play,
+    Options,
+    Quit,
+}
+
+/// Menu options in the order they are listed along with the id of their label.
+const MENU_OPTIONS: [(MenuOption, &str); 3] = [
+    (MenuOption::Play, "menu_play_game_label"),
+    (MenuOption::Options, "menu_options_label"),
+    (MenuOption::Quit, "menu_quit_label"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Navigation in menus and screens, with the keyboard or a controller D-pad.
+pub enum MenuCommand {
+    Up,
+    Down,
+    Confirm,
+    Back,
+}
+
+/// Get the menu command for an input event, if any. Commands are read from the
+/// `menu_*` actions of the input bindings.
+pub fn get_menu_command(event: &StateEvent) -> Option<MenuCommand> {
+    match event {
+        StateEvent::Input(InputEvent::ActionPressed(action)) => parse_menu_action(action),
+        _ => None,
+    }
+}
+
+fn parse_menu_action(action: &str) -> Option<MenuCommand> {
+    match action {
+        "menu_up" => Some(MenuCommand::Up),
+        "menu_down" => Some(MenuCommand::Down),
+        "menu_confirm" => Some(MenuCommand::Confirm),
+        "menu_back" => Some(MenuCommand::Back),
+        _ => None,
+    }
+}
 
 #[derive(Default)]
 pub struct MainMenu {
     ui_entity: Option<Entity>,
+    /// Index of the selected option in `MENU_OPTIONS`.
+    selected: usize,
 }
 
 impl SimpleState for MainMenu {
     fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match get_menu_command(&event) {
+            Some(MenuCommand::Up) => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Some(MenuCommand::Down) => {
+                self.selected = (self.selected + 1).min(MENU_OPTIONS.len() - 1);
+            }
+            Some(MenuCommand::Confirm) => {
+                return get_menu_transition(MENU_OPTIONS[self.selected].0)
+            }
+            Some(MenuCommand::Back) => return Trans::Quit,
+            None => (),
+        }
+
         if let StateEvent::Window(event) = event {
             if is_key_down(&event, VirtualKeyCode::P) {
-                return Trans::Push(Box::new(Loading::default()));
+                return get_menu_transition(MenuOption::Play);
+            } else if is_key_down(&event, VirtualKeyCode::O) {
+                return get_menu_transition(MenuOption::Options);
             } else if [VirtualKeyCode::Q, VirtualKeyCode::Escape]
                 .iter()
                 .any(|&key| is_key_down(&event, key))
             {
-                return Trans::Quit;
+                return get_menu_transition(MenuOption::Quit);
             }
         }
 
         Trans::None
     }
 
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        // The menu is loaded in the background so the labels may not exist yet,
+        // their colors are kept up to date every frame
+        let selected = self.selected;
+
+        data.world.exec(
+            |(finder, mut ui_texts): (UiFinder<'_>, WriteStorage<'_, UiText>)| {
+                for (i, (_, id)) in MENU_OPTIONS.iter().enumerate() {
+                    let color = if i == selected {
+                        MENU_SELECTED_COLOR
+                    } else {
+                        MENU_UNSELECTED_COLOR
+                    };
+
+                    if let Some(text) = finder.find(id).and_then(|e| ui_texts.get_mut(e)) {
+                        text.color = color;
+                    }
+                }
+            },
+        );
+
+        Trans::None
+    }
+
     fn on_start(&mut self, data: StateData<GameData>) {
         let world = data.world;
 
@@ -41,20 +128,155 @@ impl SimpleState for MainMenu {
         if let Some(ui) = self.ui_entity {
             let world = data.world;
 
-            hide_entity_and_children(ui, world);
+            set_entity_and_children_hidden(ui, world, true);
+        }
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        if let Some(ui) = self.ui_entity {
+            let world = data.world;
+
+            set_entity_and_children_hidden(ui, world, false);
+        }
+    }
+}
+
+fn get_menu_transition(option: MenuOption) -> SimpleTrans {
+    match option {
+        MenuOption::Play => Trans::Push(Box::new(Loading::default())),
+        MenuOption::Options => Trans::Push(Box::new(OptionsMenu::default())),
+        MenuOption::Quit => Trans::Quit,
+    }
+}
+
+#[derive(Default)]
+/// Lets the player switch the language of the game, see `Locale`.
+pub struct OptionsMenu {
+    ui_entity: Option<Entity>,
+    /// Codes and names of all languages.
+    languages: Vec<(String, String)>,
+}
+
+impl OptionsMenu {
+    /// Switch to the next or previous language in the list.
+    fn change_language(&self, world: &mut World, forward: bool) {
+        let mut locale = world.write_resource::<Locale>();
+
+        let count = self.languages.len();
+        let current = self
+            .languages
+            .iter()
+            .position(|(code, _)| code == locale.language());
+
+        let next = match (current, forward) {
+            (_, _) if count == 0 => return,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+            (None, _) => 0,
+        };
+
+        if let Err(msg) = locale.set_language(&self.languages[next].0) {
+            log::error!("{}", msg);
+        }
+    }
+}
+
+impl SimpleState for OptionsMenu {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        self.languages = world.read_resource::<Locale>().languages();
+
+        world.exec(|mut creator: UiCreator<'_>| {
+            self.ui_entity = Some(creator.create("ui/options.ron", ()));
+        });
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        if let Some(ui) = self.ui_entity.take() {
+            let world = data.world;
+
+            let mut entities = find_all_children(ui, world);
+            entities.push(ui);
+
+            world
+                .delete_entities(&entities)
+                .expect("could not delete options screen entities");
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match get_menu_command(&event) {
+            Some(MenuCommand::Up) => self.change_language(data.world, false),
+            Some(MenuCommand::Down) | Some(MenuCommand::Confirm) => {
+                self.change_language(data.world, true)
+            }
+            Some(MenuCommand::Back) => return Trans::Pop,
+            None => (),
+        }
+
+        if let StateEvent::Window(event) = event {
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Pop;
+            }
         }
+
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        // The screen is loaded in the background so the label may not exist yet
+        let language = {
+            let locale = data.world.read_resource::<Locale>();
+
+            self.languages
+                .iter()
+                .find(|(code, _)| code == locale.language())
+                .map(|(_, name)| format!("< {} >", name))
+                .unwrap_or_default()
+        };
+
+        data.world.exec(
+            |(finder, mut ui_texts): (UiFinder<'_>, WriteStorage<'_, UiText>)| {
+                let label = finder.find("options_language_value");
+
+                if let Some(text) = label.and_then(|e| ui_texts.get_mut(e)) {
+                    if text.text != language {
+                        text.text = language;
+                    }
+                }
+            },
+        );
+
+        Trans::None
     }
 }
 
-fn hide_entity_and_children(current_entity: Entity, world: &mut World) {
+fn set_entity_and_children_hidden(current_entity: Entity, world: &mut World, hidden: bool) {
     for ent in find_children(current_entity, world) {
-        hide_entity_and_children(ent, world);
+        set_entity_and_children_hidden(ent, world, hidden);
     }
 
     let mut hidden_store = world.write_storage::<Hidden>();
-    hidden_store
-        .insert(current_entity, Hidden)
-        .expect("could not access `Hidden` component storage");
+
+    if hidden {
+        hidden_store
+            .insert(current_entity, Hidden)
+            .expect("could not access `Hidden` component storage");
+    } else {
+        hidden_store.remove(current_entity);
+    }
+}
+
+fn find_all_children(current_entity: Entity, world: &World) -> Vec<Entity> {
+    find_children(current_entity, world)
+        .into_iter()
+        .flat_map(|child| {
+            let mut entities = find_all_children(child, world);
+            entities.push(child);
+            entities
+        })
+        .collect()
 }
 
 fn find_children(current_entity: Entity, world: &World) -> Vec<Entity> {
@@ -68,3 +290,15 @@ fn find_children(current_entity: Entity, world: &World) -> Vec<Entity> {
         .map(|(child, _, _)| child)
         .collect::<Vec<_>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_actions_are_parsed_to_commands() {
+        assert_eq!(parse_menu_action("menu_up"), Some(MenuCommand::Up));
+        assert_eq!(parse_menu_action("menu_back"), Some(MenuCommand::Back));
+        assert_eq!(parse_menu_action("action"), None);
+    }
+}
@@ -86,7 +86,7 @@ impl GraphCreator<DefaultBackend> for ExampleGraph {
             SubpassBuilder::new()
                 .with_group(DrawFlat2DTransparentDesc::new().builder())
                 .with_group(DrawUiDesc::default().builder())
-                // DEBUG
+                // Lines of the debug overlay
                 .with_group(DrawDebugLinesDesc::new().builder())
                 .with_color(color)
                 .with_depth_stencil(depth)
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use std::{fs, path::PathBuf};
+
+use crate::{game::Position, systems::movement::Action};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Use as a resource to select where player actions come from. Set from the command line.
+pub enum InputMode {
+    /// Read actions from the keyboard.
+    Live,
+    /// Read actions from the keyboard and record them to a file when the game stops.
+    Record(PathBuf),
+    /// Read actions from a recording instead of the keyboard.
+    Replay(PathBuf),
+}
+
+impl Default for InputMode {
+    fn default() -> Self {
+        InputMode::Live
+    }
+}
+
+impl InputMode {
+    /// Parse the mode from command line arguments (excluding the program name):
+    ///
+    ///  * `--record <path>`  record all actions to the file
+    ///  * `--replay <path>`  replay all actions from the file
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut mode = InputMode::Live;
+
+        while let Some(arg) = args.next() {
+            let path = match arg.as_str() {
+                "--record" | "--replay" => args
+                    .next()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| format!("missing file path after '{}'", arg))?,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            };
+
+            if mode != InputMode::Live {
+                return Err("cannot both record and replay input".to_string());
+            }
+
+            mode = if arg == "--record" {
+                InputMode::Record(path)
+            } else {
+                InputMode::Replay(path)
+            };
+        }
+
+        Ok(mode)
+    }
+
+    /// Whether input is recorded or replayed. Only player actions are part of a
+    /// recording, so dialogue, the console and quicksaves are turned off then.
+    pub fn is_recorded(&self) -> bool {
+        *self != InputMode::Live
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// A player action and when it was taken.
+pub struct RecordedAction {
+    /// Number of game frames since the game state started. Actions are replayed
+    /// on the same frame.
+    pub frame: u64,
+    /// Turn of the `TurnClock` after the action was taken.
+    pub turn: u32,
+    /// Wall-clock time since the game state started, for reference only.
+    pub elapsed_millis: u64,
+    pub action: Action,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Use as a resource to collect all player actions while recording.
+pub struct Recording {
+    pub actions: Vec<RecordedAction>,
+    /// Checksum of all entity positions when the recording stopped.
+    pub checksum: Option<u64>,
+}
+
+impl Recording {
+    pub fn read(path: &PathBuf) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("could not read recording '{}': {}", path.display(), err))?;
+
+        ron::de::from_str(&contents)
+            .map_err(|err| format!("could not parse recording '{}': {}", path.display(), err))
+    }
+
+    pub fn write(&self, path: &PathBuf) -> Result<(), String> {
+        let contents = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| format!("could not serialize recording: {}", err))?;
+
+        fs::write(path, contents)
+            .map_err(|err| format!("could not write recording '{}': {}", path.display(), err))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Use as a resource to report whether a replay ended with the same entity positions
+/// as its recording. Not set until all actions have been replayed.
+pub struct ReplayOutcome(pub Option<bool>);
+
+/// Calculate a checksum of entity positions which does not depend on their order.
+///
+/// Uses the FNV-1a hash which is stable across builds, unlike the standard library
+/// hasher.
+pub fn get_position_checksum<'a, I: Iterator<Item = &'a Position>>(positions: I) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut positions = positions.map(|p| (p.x, p.y)).collect::<Vec<_>>();
+    positions.sort();
+
+    positions.iter().fold(FNV_OFFSET_BASIS, |hash, (x, y)| {
+        x.to_le_bytes()
+            .iter()
+            .chain(y.to_le_bytes().iter())
+            .fold(hash, |hash, &byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn no_arguments_is_live_input() {
+        assert_eq!(InputMode::from_args(get_args(&[])), Ok(InputMode::Live));
+    }
+
+    #[test]
+    fn record_and_replay_arguments_take_a_path() {
+        assert_eq!(
+            InputMode::from_args(get_args(&["--record", "out.ron"])),
+            Ok(InputMode::Record(PathBuf::from("out.ron")))
+        );
+        assert_eq!(
+            InputMode::from_args(get_args(&["--replay", "in.ron"])),
+            Ok(InputMode::Replay(PathBuf::from("in.ron")))
+        );
+    }
+
+    #[test]
+    fn only_live_input_is_not_recorded() {
+        assert!(!InputMode::Live.is_recorded());
+        assert!(InputMode::Record(PathBuf::from("out.ron")).is_recorded());
+        assert!(InputMode::Replay(PathBuf::from("in.ron")).is_recorded());
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        assert!(InputMode::from_args(get_args(&["--record"])).is_err());
+        assert!(InputMode::from_args(get_args(&["--fast"])).is_err());
+        assert!(
+            InputMode::from_args(get_args(&["--record", "a.ron", "--replay", "b.ron"])).is_err()
+        );
+    }
+
+    #[test]
+    fn position_checksum_does_not_depend_on_order() {
+        let positions = vec![Position { x: 1, y: 2 }, Position { x: 3, y: 4 }];
+        let reversed = positions.iter().rev().cloned().collect::<Vec<_>>();
+
+        assert_eq!(
+            get_position_checksum(positions.iter()),
+            get_position_checksum(reversed.iter())
+        );
+    }
+
+    #[test]
+    fn position_checksum_changes_with_positions() {
+        let positions = vec![Position { x: 1, y: 2 }, Position { x: 3, y: 4 }];
+        let moved = vec![Position { x: 1, y: 2 }, Position { x: 3, y: 5 }];
+        let swapped = vec![Position { x: 2, y: 1 }, Position { x: 3, y: 4 }];
+
+        let checksum = get_position_checksum(positions.iter());
+
+        assert_ne!(checksum, get_position_checksum(moved.iter()));
+        assert_ne!(checksum, get_position_checksum(swapped.iter()));
+    }
+}
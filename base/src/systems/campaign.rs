@@ -0,0 +1,103 @@
+use amethyst::{
+    ecs::prelude::{
+        Read, ReadExpect, Resources, System, SystemData, Write, WriteExpect, WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::game::{Campaign, CampaignEvent, Hud, TurnClock};
+
+use super::movement::PlayerMovedEvent;
+
+/// Advances the `TurnClock` once for every step that the player character takes.
+/// Moves which are blocked, such as bumping into walls, do not pass any time.
+pub struct TurnClockSystem {
+    pub reader: Option<ReaderId<PlayerMovedEvent>>,
+}
+
+impl<'s> System<'s> for TurnClockSystem {
+    type SystemData = (
+        Write<'s, TurnClock>,
+        Read<'s, EventChannel<PlayerMovedEvent>>,
+    );
+
+    fn run(&mut self, (mut clock, event_channel): Self::SystemData) {
+        let num_steps = event_channel.read(self.reader.as_mut().unwrap()).count();
+        clock.turn += num_steps as u32;
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<PlayerMovedEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Applies `CampaignEvent`s to the `Campaign` and moves it along the calendar
+/// as turns pass.
+pub struct CampaignSystem {
+    pub reader: Option<ReaderId<CampaignEvent>>,
+}
+
+impl<'s> System<'s> for CampaignSystem {
+    type SystemData = (
+        WriteExpect<'s, Campaign>,
+        Read<'s, TurnClock>,
+        Read<'s, EventChannel<CampaignEvent>>,
+    );
+
+    fn run(&mut self, (mut campaign, clock, event_channel): Self::SystemData) {
+        for event in event_channel.read(self.reader.as_mut().unwrap()) {
+            campaign.apply(event);
+        }
+
+        let day = clock.turn / campaign.data.turns_per_day.max(1);
+        campaign.advance_to_day(day);
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<CampaignEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Shows the campaign calendar and polling numbers in the `Hud`.
+pub struct CampaignHudSystem;
+
+impl<'s> System<'s> for CampaignHudSystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        ReadExpect<'s, Hud>,
+        ReadExpect<'s, Campaign>,
+    );
+
+    fn run(&mut self, (mut ui_texts, hud, campaign): Self::SystemData) {
+        let text = format_campaign_status(&campaign);
+
+        if let Some(ui_text) = ui_texts.get_mut(hud.campaign) {
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+    }
+}
+
+fn format_campaign_status(campaign: &Campaign) -> String {
+    let polls = campaign
+        .polls()
+        .iter()
+        .map(|(name, poll)| format!("{} {:.1}%", name, 100.0 * poll))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    format!(
+        "Day {}/{}  {}",
+        campaign.day, campaign.data.election_day, polls
+    )
+}
@@ -0,0 +1,148 @@
+use amethyst::{
+    core::Hidden,
+    ecs::prelude::{
+        Entities, Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, WriteExpect,
+        WriteStorage,
+    },
+    input::{InputEvent, StringBindings},
+    renderer::{
+        debug_drawing::DebugLinesComponent,
+        palette::{Pixel, Srgba},
+    },
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+    utils::fps_counter::FpsCounter,
+};
+
+use crate::game::{
+    get_area_grid_lines, get_collision_marker_lines, ActiveArea, Area, Collision, DebugOverlay,
+    Position,
+};
+
+use super::movement::update_transforms::UpdateTransformsEvent;
+
+/// Toggles the `DebugOverlay` and keeps it up to date while it is visible.
+///
+/// The overlay shows the area grid, crosses out tiles with `Collision` and shows
+/// the frame rate and number of entities.
+#[derive(Default)]
+pub struct DebugOverlaySystem {
+    input_reader: Option<ReaderId<InputEvent<StringBindings>>>,
+    transforms_reader: Option<ReaderId<UpdateTransformsEvent>>,
+}
+
+impl<'s> System<'s> for DebugOverlaySystem {
+    type SystemData = (
+        WriteExpect<'s, DebugOverlay>,
+        WriteStorage<'s, DebugLinesComponent>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, Hidden>,
+        Entities<'s>,
+        ReadExpect<'s, ActiveArea>,
+        ReadStorage<'s, Area>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Collision>,
+        Read<'s, FpsCounter>,
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Read<'s, EventChannel<UpdateTransformsEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut overlay,
+            mut debug_lines,
+            mut ui_texts,
+            mut hiddens,
+            entities,
+            current_area,
+            areas,
+            positions,
+            collisions,
+            fps_counter,
+            input_events,
+            transforms_events,
+        ) = data;
+
+        let num_toggles = input_events
+            .read(self.input_reader.as_mut().unwrap())
+            .filter(|event| match event {
+                InputEvent::ActionPressed(action) => action == "toggle_debug_overlay",
+                _ => false,
+            })
+            .count();
+
+        let has_moved = transforms_events
+            .read(self.transforms_reader.as_mut().unwrap())
+            .count()
+            > 0;
+
+        let is_toggled = num_toggles % 2 == 1;
+
+        if is_toggled {
+            overlay.is_visible = !overlay.is_visible;
+
+            if overlay.is_visible {
+                hiddens.remove(overlay.text);
+            } else {
+                hiddens
+                    .insert(overlay.text, Hidden)
+                    .expect("could not hide the debug overlay");
+                debug_lines.remove(overlay.lines);
+            }
+        }
+
+        if !overlay.is_visible {
+            return;
+        }
+
+        if let Some(ui_text) = ui_texts.get_mut(overlay.text) {
+            ui_text.text = format!(
+                "FPS {:.0}  entities {}",
+                fps_counter.sampled_fps(),
+                (&entities).join().count()
+            );
+        }
+
+        if is_toggled || has_moved {
+            let grid = areas
+                .get(current_area.0)
+                .map(|area| get_area_grid_lines(&area.dimensions))
+                .unwrap_or_default();
+
+            let markers = get_collision_marker_lines(
+                (&positions, &collisions)
+                    .join()
+                    .map(|(position, _)| position),
+            );
+
+            let mut lines = DebugLinesComponent::with_capacity(grid.len() + markers.len());
+
+            let grid_color = Srgba::from_raw(&[110.0 / 255.0, 110.0 / 255.0, 110.0 / 255.0, 0.5]);
+            let marker_color = Srgba::from_raw(&[1.0, 0.4, 0.4, 0.8]);
+
+            for (start, end) in grid {
+                lines.add_line(start.into(), end.into(), grid_color.clone());
+            }
+
+            for (start, end) in markers {
+                lines.add_line(start.into(), end.into(), marker_color.clone());
+            }
+
+            debug_lines
+                .insert(overlay.lines, lines)
+                .expect("could not draw the debug overlay");
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.input_reader = Some(
+            res.fetch_mut::<EventChannel<InputEvent<StringBindings>>>()
+                .register_reader(),
+        );
+        self.transforms_reader = Some(
+            res.fetch_mut::<EventChannel<UpdateTransformsEvent>>()
+                .register_reader(),
+        );
+    }
+}
@@ -9,26 +9,102 @@ use crate::{
     systems::movement::{Action, Move, PlayerActionEvent},
 };
 
-use std::time::{Duration, Instant};
+use std::time::Instant;
+
+/// Source of the current time for systems which depend on it.
+pub trait TimeSource {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Default)]
+/// Reads the time from the system clock.
+pub struct SystemClock;
+
+impl TimeSource for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Current state of all axes and actions which the player can use.
+pub trait InputState {
+    fn axis_value(&self, axis: &str) -> Option<f32>;
+    fn action_is_down(&self, action: &str) -> Option<bool>;
+    /// Position of the mouse cursor in window coordinates, from the top left corner.
+    fn cursor_position(&self) -> Option<(f32, f32)>;
+    fn is_any_key_down(&self) -> bool;
+    /// Names of all bound actions.
+    fn actions(&self) -> Vec<String>;
+    /// Names of all bound axes.
+    fn axes(&self) -> Vec<String>;
+}
+
+impl InputState for InputHandler<StringBindings> {
+    fn axis_value(&self, axis: &str) -> Option<f32> {
+        InputHandler::axis_value(self, axis).map(|value| value as f32)
+    }
+
+    fn action_is_down(&self, action: &str) -> Option<bool> {
+        InputHandler::action_is_down(self, action)
+    }
+
+    fn cursor_position(&self) -> Option<(f32, f32)> {
+        self.mouse_position().map(|(x, y)| (x as f32, y as f32))
+    }
+
+    fn is_any_key_down(&self) -> bool {
+        self.keys_that_are_down().next().is_some()
+    }
+
+    fn actions(&self) -> Vec<String> {
+        self.bindings.actions().cloned().collect()
+    }
+
+    fn axes(&self) -> Vec<String> {
+        self.bindings.axes().cloned().collect()
+    }
+}
+
+/// Whether the player is using any key, bound action other than the ignored one, or
+/// axis pushed past the deadzone, including controller buttons and sticks.
+pub fn is_any_input_active<I: InputState + ?Sized>(
+    input: &I,
+    ignored_action: &str,
+    deadzone: f32,
+) -> bool {
+    input.is_any_key_down()
+        || input
+            .actions()
+            .iter()
+            .any(|action| action != ignored_action && input.action_is_down(action).unwrap_or(false))
+        || input
+            .axes()
+            .iter()
+            .any(|axis| get_axis_direction(input.axis_value(axis).unwrap_or(0.0), deadzone) != 0)
+}
 
 /// Looks for input events and sends signals to systems.
-pub struct InputSystem {
-    dirty: bool,
-    hold_start: Option<Instant>,
-    last_event: Option<Instant>,
+pub struct InputSystem<C: TimeSource = SystemClock> {
+    clock: C,
+    repeat: RepeatState,
 }
 
 impl Default for InputSystem {
     fn default() -> Self {
+        InputSystem::with_clock(SystemClock)
+    }
+}
+
+impl<C: TimeSource> InputSystem<C> {
+    pub fn with_clock(clock: C) -> Self {
         InputSystem {
-            dirty: false,
-            hold_start: None,
-            last_event: None,
+            clock,
+            repeat: RepeatState::default(),
         }
     }
 }
 
-impl<'s> System<'s> for InputSystem {
+impl<'s, C: TimeSource> System<'s> for InputSystem<C> {
     type SystemData = (
         Write<'s, EventChannel<PlayerActionEvent>>,
         Read<'s, Config>,
@@ -38,60 +114,128 @@ impl<'s> System<'s> for InputSystem {
     fn run(&mut self, data: Self::SystemData) {
         let (mut event_channel, config, input) = data;
 
-        let action = parse_move_event(&input).or_else(|| parse_action_event(&input));
+        let action = parse_input(&*input, &config);
+
+        if let Some(action) = self.repeat.update(action, self.clock.now(), &config) {
+            event_channel.single_write(PlayerActionEvent(action));
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// Decides when an action which is held down should be sent again.
+///
+/// An action is sent once when its input is pressed. If the input is held it will
+/// be sent again after the hold duration has passed and then at every repeat duration
+/// as set for the action in the `Config`.
+pub struct RepeatState {
+    dirty: bool,
+    hold_start: Option<Instant>,
+    last_event: Option<Instant>,
+}
+
+impl RepeatState {
+    /// Update the state with the currently held action (if any) and return the action
+    /// if it should be sent at this time.
+    pub fn update(
+        &mut self,
+        action: Option<Action>,
+        current_time: Instant,
+        config: &Config,
+    ) -> Option<Action> {
+        match action {
+            Some(action) => {
+                let settings = config.get_repeat_settings(action.name());
 
-        if let Some(action) = action {
-            let current_time = Instant::now();
+                let allow_new_event = match self.hold_start {
+                    Some(hold_start) => {
+                        let allow_hold_events =
+                            current_time.duration_since(hold_start) >= settings.min_duration_hold;
 
-            let allow_new_event = match self.hold_start {
-                Some(hold_start) => {
-                    let allow_hold_events =
-                        current_time.duration_since(hold_start) >= config.min_duration_hold;
+                        let allow_repeat_event = self
+                            .last_event
+                            .map(|time| {
+                                current_time.duration_since(time) >= settings.min_duration_repeat
+                            })
+                            .unwrap_or(false);
 
-                    let allow_repeat_event = self
-                        .last_event
-                        .map(|time| current_time.duration_since(time) >= config.min_duration_repeat)
-                        .unwrap_or(false);
+                        allow_hold_events && allow_repeat_event
+                    }
+                    None => {
+                        self.hold_start.replace(current_time);
+                        true
+                    }
+                };
 
-                    allow_hold_events && allow_repeat_event
-                }
-                None => {
-                    self.hold_start.replace(current_time);
-                    true
-                }
-            };
+                if !self.dirty || allow_new_event {
+                    self.dirty = true;
+                    self.last_event = Some(current_time);
 
-            if !self.dirty || allow_new_event {
-                event_channel.single_write(PlayerActionEvent(action));
+                    Some(action)
+                } else {
+                    None
+                }
+            }
+            None => {
+                self.dirty = false;
+                self.hold_start = None;
 
-                self.dirty = true;
-                self.last_event = Some(current_time);
+                None
             }
-        } else {
-            self.dirty = false;
-            self.hold_start = None;
         }
     }
 }
 
-fn parse_action_event<'s>(input: &Read<'s, InputHandler<StringBindings>>) -> Option<Action> {
+/// Get the action which is currently held, if any. Movement takes precedence.
+pub fn parse_input<I: InputState + ?Sized>(input: &I, config: &Config) -> Option<Action> {
+    parse_move_event(input, config).or_else(|| parse_action_event(input))
+}
+
+fn parse_action_event<I: InputState + ?Sized>(input: &I) -> Option<Action> {
     input
         .action_is_down("action")
         .and_then(|value| if value { Some(Action::Action) } else { None })
 }
 
-fn parse_move_event<'s>(input: &Read<'s, InputHandler<StringBindings>>) -> Option<Action> {
-    let dx = input
-        .axis_value("move_horizontal")
-        .map(|v| v as i32)
-        .unwrap_or(0);
+/// Diagonal movement bindings and their directions.
+const DIAGONAL_MOVE_ACTIONS: [(&str, Move); 4] = [
+    ("move_up_left", Move::UpLeft),
+    ("move_up_right", Move::UpRight),
+    ("move_down_left", Move::DownLeft),
+    ("move_down_right", Move::DownRight),
+];
+
+/// Horizontal movement axes in order of precedence: keyboard, controller D-pad and stick.
+const HORIZONTAL_MOVE_AXES: [&str; 3] = ["move_horizontal", "dpad_horizontal", "stick_horizontal"];
+/// Vertical movement axes in order of precedence: keyboard, controller D-pad and stick.
+const VERTICAL_MOVE_AXES: [&str; 3] = ["move_vertical", "dpad_vertical", "stick_vertical"];
+
+/// Parse movement from the movement axes, or diagonal movement bindings if allowed.
+///
+/// If diagonal movement is not allowed and both axes are held, vertical movement
+/// takes precedence.
+fn parse_move_event<I: InputState + ?Sized>(input: &I, config: &Config) -> Option<Action> {
+    let allow_diagonal = config.allow_diagonal_movement;
 
-    let dy = input
-        .axis_value("move_vertical")
-        .map(|v| v as i32)
-        .unwrap_or(0);
+    if allow_diagonal {
+        let diagonal = DIAGONAL_MOVE_ACTIONS
+            .iter()
+            .find(|(action, _)| input.action_is_down(action).unwrap_or(false))
+            .map(|(_, direction)| direction.clone());
+
+        if let Some(direction) = diagonal {
+            return Some(Action::Move(direction));
+        }
+    }
+
+    let dx = get_axes_direction(input, &HORIZONTAL_MOVE_AXES, config.stick_deadzone);
+    let dy = get_axes_direction(input, &VERTICAL_MOVE_AXES, config.stick_deadzone);
 
     let direction = match (dx, dy) {
+        (-1, 1) if allow_diagonal => Some(Move::UpLeft),
+        (1, 1) if allow_diagonal => Some(Move::UpRight),
+        (-1, -1) if allow_diagonal => Some(Move::DownLeft),
+        (1, -1) if allow_diagonal => Some(Move::DownRight),
         (_, 1) => Some(Move::Up),
         (_, -1) => Some(Move::Down),
         (-1, _) => Some(Move::Left),
@@ -101,3 +245,307 @@ fn parse_move_event<'s>(input: &Read<'s, InputHandler<StringBindings>>) -> Optio
 
     direction.map(|dir| Action::Move(dir))
 }
+
+/// Get the direction (-1, 0 or 1) of the first axis which is pushed past the deadzone.
+fn get_axes_direction<I: InputState + ?Sized>(input: &I, axes: &[&str], deadzone: f32) -> i32 {
+    axes.iter()
+        .filter_map(|axis| input.axis_value(axis))
+        .map(|value| get_axis_direction(value, deadzone))
+        .find(|&direction| direction != 0)
+        .unwrap_or(0)
+}
+
+/// Round an axis value to its direction, ignoring values inside the deadzone.
+fn get_axis_direction(value: f32, deadzone: f32) -> i32 {
+    if value.abs() > deadzone {
+        value.signum() as i32
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::RepeatSettings;
+
+    use std::{cell::Cell, collections::HashMap, time::Duration};
+
+    struct FakeClock {
+        now: Cell<Instant>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            FakeClock {
+                now: Cell::new(Instant::now()),
+            }
+        }
+
+        fn advance(&self, millis: u64) {
+            self.now.set(self.now.get() + Duration::from_millis(millis));
+        }
+    }
+
+    impl TimeSource for FakeClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeInput {
+        axes: HashMap<&'static str, f32>,
+        actions: HashMap<&'static str, bool>,
+    }
+
+    impl InputState for FakeInput {
+        fn axis_value(&self, axis: &str) -> Option<f32> {
+            self.axes.get(axis).cloned()
+        }
+
+        fn action_is_down(&self, action: &str) -> Option<bool> {
+            self.actions.get(action).cloned()
+        }
+
+        fn cursor_position(&self) -> Option<(f32, f32)> {
+            None
+        }
+
+        fn is_any_key_down(&self) -> bool {
+            false
+        }
+
+        fn actions(&self) -> Vec<String> {
+            self.actions
+                .keys()
+                .map(|action| action.to_string())
+                .collect()
+        }
+
+        fn axes(&self) -> Vec<String> {
+            self.axes.keys().map(|axis| axis.to_string()).collect()
+        }
+    }
+
+    fn get_config() -> Config {
+        let mut repeat = HashMap::new();
+        repeat.insert(
+            "action".to_string(),
+            RepeatSettings {
+                min_duration_hold: Duration::from_millis(1000),
+                min_duration_repeat: Duration::from_millis(500),
+            },
+        );
+
+        Config {
+            allow_diagonal_movement: false,
+            tween_duration: Duration::from_millis(0),
+            stick_deadzone: 0.25,
+            default_repeat: RepeatSettings {
+                min_duration_hold: Duration::from_millis(300),
+                min_duration_repeat: Duration::from_millis(100),
+            },
+            repeat,
+        }
+    }
+
+    fn get_input_config(allow_diagonal: bool) -> Config {
+        Config {
+            allow_diagonal_movement: allow_diagonal,
+            ..get_config()
+        }
+    }
+
+    /// Hold the action for the given times (in milliseconds between each) and return
+    /// which updates sent the action.
+    fn hold_action(action: Action, steps: &[u64]) -> Vec<bool> {
+        let clock = FakeClock::new();
+        let config = get_config();
+        let mut state = RepeatState::default();
+
+        steps
+            .iter()
+            .map(|&millis| {
+                clock.advance(millis);
+                state
+                    .update(Some(action.clone()), clock.now(), &config)
+                    .is_some()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pressed_action_is_sent_once_before_hold_duration() {
+        assert_eq!(
+            hold_action(Action::Move(Move::Up), &[0, 100, 100]),
+            vec![true, false, false]
+        );
+    }
+
+    #[test]
+    fn held_action_repeats_after_hold_duration() {
+        assert_eq!(
+            hold_action(Action::Move(Move::Up), &[0, 300, 50, 50, 100]),
+            vec![true, true, false, true, true]
+        );
+    }
+
+    #[test]
+    fn repeat_settings_are_read_per_action() {
+        assert_eq!(
+            hold_action(Action::Action, &[0, 300, 700, 100, 400]),
+            vec![true, false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn released_action_is_sent_again_on_next_press() {
+        let clock = FakeClock::new();
+        let config = get_config();
+        let mut state = RepeatState::default();
+
+        let action = Some(Action::Action);
+
+        assert!(state.update(action.clone(), clock.now(), &config).is_some());
+        assert!(state.update(None, clock.now(), &config).is_none());
+
+        clock.advance(10);
+        assert!(state.update(action.clone(), clock.now(), &config).is_some());
+    }
+
+    #[test]
+    fn vertical_movement_takes_precedence_over_horizontal_without_diagonals() {
+        let mut input = FakeInput::default();
+        input.axes.insert("move_horizontal", -1.0);
+        input.axes.insert("move_vertical", 1.0);
+
+        assert_eq!(
+            parse_input(&input, &get_input_config(false)),
+            Some(Action::Move(Move::Up))
+        );
+    }
+
+    #[test]
+    fn combined_axes_are_diagonal_movement_if_allowed() {
+        let mut input = FakeInput::default();
+        input.axes.insert("move_horizontal", -1.0);
+        input.axes.insert("move_vertical", 1.0);
+        assert_eq!(
+            parse_input(&input, &get_input_config(true)),
+            Some(Action::Move(Move::UpLeft))
+        );
+
+        input.axes.insert("move_horizontal", 1.0);
+        input.axes.insert("move_vertical", -1.0);
+        assert_eq!(
+            parse_input(&input, &get_input_config(true)),
+            Some(Action::Move(Move::DownRight))
+        );
+    }
+
+    #[test]
+    fn single_axis_is_cardinal_movement_with_diagonals_allowed() {
+        let mut input = FakeInput::default();
+        input.axes.insert("move_horizontal", -1.0);
+
+        assert_eq!(
+            parse_input(&input, &get_input_config(true)),
+            Some(Action::Move(Move::Left))
+        );
+    }
+
+    #[test]
+    fn diagonal_bindings_are_only_read_if_allowed() {
+        let mut input = FakeInput::default();
+        input.actions.insert("move_up_right", true);
+
+        assert_eq!(
+            parse_input(&input, &get_input_config(true)),
+            Some(Action::Move(Move::UpRight))
+        );
+        assert_eq!(parse_input(&input, &get_input_config(false)), None);
+    }
+
+    #[test]
+    fn movement_takes_precedence_over_action() {
+        let mut input = FakeInput::default();
+        input.actions.insert("action", true);
+        assert_eq!(
+            parse_input(&input, &get_input_config(false)),
+            Some(Action::Action)
+        );
+
+        input.axes.insert("move_horizontal", 1.0);
+        assert_eq!(
+            parse_input(&input, &get_input_config(false)),
+            Some(Action::Move(Move::Right))
+        );
+    }
+
+    #[test]
+    fn controller_axes_move_past_the_deadzone() {
+        let mut input = FakeInput::default();
+        input.axes.insert("stick_horizontal", 0.2);
+        assert_eq!(parse_input(&input, &get_input_config(false)), None);
+
+        input.axes.insert("stick_horizontal", -0.7);
+        assert_eq!(
+            parse_input(&input, &get_input_config(false)),
+            Some(Action::Move(Move::Left))
+        );
+
+        input.axes.insert("dpad_vertical", 1.0);
+        assert_eq!(
+            parse_input(&input, &get_input_config(true)),
+            Some(Action::Move(Move::UpLeft))
+        );
+    }
+
+    #[test]
+    fn keyboard_axes_take_precedence_over_controller_axes() {
+        let mut input = FakeInput::default();
+        input.axes.insert("move_horizontal", 1.0);
+        input.axes.insert("stick_horizontal", -1.0);
+
+        assert_eq!(
+            parse_input(&input, &get_input_config(false)),
+            Some(Action::Move(Move::Right))
+        );
+    }
+
+    #[test]
+    fn axis_values_inside_the_deadzone_have_no_direction() {
+        assert_eq!(get_axis_direction(0.0, 0.0), 0);
+        assert_eq!(get_axis_direction(0.25, 0.25), 0);
+        assert_eq!(get_axis_direction(-0.3, 0.25), -1);
+        assert_eq!(get_axis_direction(1.0, 0.25), 1);
+    }
+
+    #[test]
+    fn no_input_is_no_action() {
+        let mut input = FakeInput::default();
+        input.actions.insert("action", false);
+        input.axes.insert("move_horizontal", 0.0);
+
+        assert_eq!(parse_input(&input, &get_input_config(false)), None);
+    }
+
+    #[test]
+    fn bound_actions_and_axes_are_active_input() {
+        let mut input = FakeInput::default();
+        input.actions.insert("move_to_cursor", true);
+        input.actions.insert("open_inventory", false);
+        input.axes.insert("stick_x", 0.1);
+
+        assert!(!is_any_input_active(&input, "move_to_cursor", 0.25));
+
+        input.actions.insert("open_inventory", true);
+        assert!(is_any_input_active(&input, "move_to_cursor", 0.25));
+
+        input.actions.insert("open_inventory", false);
+        input.axes.insert("stick_x", -0.8);
+        assert!(is_any_input_active(&input, "move_to_cursor", 0.25));
+    }
+}
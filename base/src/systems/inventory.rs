@@ -0,0 +1,136 @@
+use amethyst::{
+    core::Named,
+    ecs::prelude::{
+        Entities, Join, Read, ReadStorage, Resources, System, SystemData, Write, WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::game::{Inventory, Item, ItemTransferEvent, MessageLog, PlayerCharacter, Position};
+
+use super::movement::{Action, PlayerActionEvent};
+
+/// Picks up all items that the player character is standing on when the action
+/// key is pressed.
+///
+/// Picked up items lose their `Position` right away, so that they can not be picked
+/// up again before their entity is deleted.
+pub struct ItemPickupSystem {
+    pub reader: Option<ReaderId<PlayerActionEvent>>,
+}
+
+impl<'s> System<'s> for ItemPickupSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Inventory>,
+        WriteStorage<'s, Position>,
+        Write<'s, MessageLog>,
+        ReadStorage<'s, Item>,
+        ReadStorage<'s, PlayerCharacter>,
+        Read<'s, EventChannel<PlayerActionEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut inventories,
+            mut positions,
+            mut message_log,
+            items,
+            characters,
+            event_channel,
+        ): Self::SystemData,
+    ) {
+        for event in event_channel.read(self.reader.as_mut().unwrap()) {
+            if let PlayerActionEvent(Action::Action) = event {
+                let player_position = (&positions, &characters)
+                    .join()
+                    .map(|(position, _)| position.clone())
+                    .next();
+
+                let picked_up = (&entities, &items, &positions)
+                    .join()
+                    .filter(|(_, _, position)| Some(*position) == player_position.as_ref())
+                    .map(|(entity, item, _)| (entity, item.clone()))
+                    .collect::<Vec<_>>();
+
+                for (entity, item) in picked_up {
+                    positions.remove(entity);
+                    entities
+                        .delete(entity)
+                        .expect("could not delete picked up item entity");
+
+                    message_log.push(format!("Picked up: {}", item));
+
+                    for (inventory, _) in (&mut inventories, &characters).join() {
+                        inventory.add(item.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<PlayerActionEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Moves items between character inventories for every `ItemTransferEvent`.
+/// Transfers of items that the giver does not have are ignored.
+///
+/// Items which the player gives away or receives are noted in the `MessageLog`.
+pub struct ItemTransferSystem {
+    pub reader: Option<ReaderId<ItemTransferEvent>>,
+}
+
+impl<'s> System<'s> for ItemTransferSystem {
+    type SystemData = (
+        WriteStorage<'s, Inventory>,
+        Write<'s, MessageLog>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, PlayerCharacter>,
+        Read<'s, EventChannel<ItemTransferEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (mut inventories, mut message_log, names, characters, event_channel): Self::SystemData,
+    ) {
+        for event in event_channel.read(self.reader.as_mut().unwrap()) {
+            if !inventories.contains(event.to) {
+                continue;
+            }
+
+            let item = inventories
+                .get_mut(event.from)
+                .and_then(|inventory| inventory.take(&event.item));
+
+            if let (Some(item), Some(inventory)) = (item, inventories.get_mut(event.to)) {
+                if characters.contains(event.from) {
+                    if let Some(name) = names.get(event.to) {
+                        message_log.push(format!("Gave to {}: {}", name.name, item));
+                    }
+                } else if characters.contains(event.to) {
+                    if let Some(name) = names.get(event.from) {
+                        message_log.push(format!("Received from {}: {}", name.name, item));
+                    }
+                }
+
+                inventory.add(item);
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<ItemTransferEvent>>()
+                .register_reader(),
+        );
+    }
+}
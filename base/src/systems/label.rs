@@ -0,0 +1,260 @@
+use amethyst::{
+    core::{timing::Time, Named},
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage},
+    ui::{UiImage, UiText, UiTransform},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    config::Config,
+    game::{
+        consts::{
+            BARK_DISTANCE, BARK_INTERVAL, NAME_LABEL_DISTANCE, NAME_LABEL_ROWS, SPEECH_BUBBLE_ROWS,
+        },
+        get_label_coordinates, get_name_label_text, get_name_label_transform,
+        get_speech_bubble_text, get_speech_bubble_transform, set_speech_bubble_alpha, Barks,
+        GameRng, NameLabel, PlayerCharacter, Position, SpeechBubble, Zoom,
+    },
+    locale::Locale,
+};
+
+/// Shows the name of every character within `NAME_LABEL_DISTANCE` of the player
+/// above its glyph, if `show_name_labels` is set in the `Config`.
+///
+/// Labels are created when their character comes into range and deleted when it
+/// leaves it or the area.
+pub struct NameLabelSystem;
+
+impl<'s> System<'s> for NameLabelSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, NameLabel>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiTransform>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadStorage<'s, Named>,
+        Read<'s, Config>,
+        Read<'s, Locale>,
+        Read<'s, Zoom>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut labels,
+            mut ui_texts,
+            mut ui_transforms,
+            positions,
+            player_characters,
+            names,
+            config,
+            locale,
+            zoom,
+        ) = data;
+
+        let player_position = (&positions, &player_characters)
+            .join()
+            .map(|(position, _)| position)
+            .next();
+
+        let mut characters = HashMap::new();
+
+        if let (true, Some(player_position)) = (config.show_name_labels, player_position) {
+            for (entity, position, named, ui_text, ui_transform) in
+                (&entities, &positions, &names, &ui_texts, &ui_transforms).join()
+            {
+                if !named.name.is_empty()
+                    && position.distance_to(player_position) <= NAME_LABEL_DISTANCE
+                {
+                    let name = locale.character_name(&named.name).to_string();
+                    let coordinates = (ui_transform.local_x, ui_transform.local_y);
+
+                    characters.insert(entity, (name, coordinates, ui_text.font.clone()));
+                }
+            }
+        }
+
+        let tile_height = zoom.tile_size().1;
+        let mut labelled = HashSet::new();
+
+        for (entity, label) in (&entities, &labels).join() {
+            match characters.get(&label.character) {
+                Some((name, coordinates, _)) => {
+                    if let Some(ui_text) = ui_texts.get_mut(entity) {
+                        if &ui_text.text != name {
+                            ui_text.text = name.clone();
+                        }
+                    }
+
+                    if let Some(ui_transform) = ui_transforms.get_mut(entity) {
+                        let (x, y) =
+                            get_label_coordinates(*coordinates, NAME_LABEL_ROWS, tile_height);
+
+                        ui_transform.local_x = x;
+                        ui_transform.local_y = y;
+                    }
+
+                    labelled.insert(label.character);
+                }
+                None => {
+                    entities
+                        .delete(entity)
+                        .expect("could not delete a name label");
+                }
+            }
+        }
+
+        for (character, (name, coordinates, font)) in characters {
+            if labelled.contains(&character) {
+                continue;
+            }
+
+            let mut ui_transform = get_name_label_transform();
+            let (x, y) = get_label_coordinates(coordinates, NAME_LABEL_ROWS, tile_height);
+            ui_transform.local_x = x;
+            ui_transform.local_y = y;
+
+            entities
+                .build_entity()
+                .with(NameLabel { character }, &mut labels)
+                .with(get_name_label_text(font, &name), &mut ui_texts)
+                .with(ui_transform, &mut ui_transforms)
+                .build();
+        }
+    }
+}
+
+/// Lets characters with `Barks` within `BARK_DISTANCE` of the player say one of
+/// their lines now and then in a `SpeechBubble` above their glyph.
+///
+/// Bubbles follow their character, fade out and are deleted after `BARK_DURATION`
+/// or when their character leaves the area. A character only has one bubble at a time.
+/// Cooldowns between barks run down also while the player is out of range, and lines
+/// are picked with the seeded `GameRng`.
+pub struct BarkSystem;
+
+impl<'s> System<'s> for BarkSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Barks>,
+        WriteStorage<'s, SpeechBubble>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, UiTransform>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, PlayerCharacter>,
+        Read<'s, Locale>,
+        Read<'s, Zoom>,
+        Read<'s, Time>,
+        Write<'s, GameRng>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut barks,
+            mut bubbles,
+            mut ui_texts,
+            mut ui_images,
+            mut ui_transforms,
+            positions,
+            player_characters,
+            locale,
+            zoom,
+            time,
+            mut rng,
+        ) = data;
+
+        let seconds = time.delta_seconds();
+        let tile_height = zoom.tile_size().1;
+
+        let player_position = (&positions, &player_characters)
+            .join()
+            .map(|(position, _)| position)
+            .next();
+
+        // Glyphs of characters are placed by the `UpdateCharTileTransformsSystem`
+        let coordinates = (&entities, &barks, &ui_transforms)
+            .join()
+            .map(|(entity, _, ui_transform)| (entity, (ui_transform.local_x, ui_transform.local_y)))
+            .collect::<HashMap<Entity, (f32, f32)>>();
+
+        let mut speaking = HashSet::new();
+
+        for (entity, bubble, ui_text, ui_image, ui_transform) in (
+            &entities,
+            &mut bubbles,
+            &mut ui_texts,
+            &mut ui_images,
+            &mut ui_transforms,
+        )
+            .join()
+        {
+            bubble.age += seconds;
+
+            match coordinates.get(&bubble.character) {
+                Some(&character) if !bubble.is_finished() => {
+                    let (x, y) = get_label_coordinates(character, SPEECH_BUBBLE_ROWS, tile_height);
+
+                    ui_transform.local_x = x;
+                    ui_transform.local_y = y;
+                    set_speech_bubble_alpha(ui_text, ui_image, bubble.alpha());
+
+                    speaking.insert(bubble.character);
+                }
+                _ => {
+                    entities
+                        .delete(entity)
+                        .expect("could not delete a speech bubble");
+                }
+            }
+        }
+
+        let rng = &mut rng.0;
+        let mut new_barks = Vec::new();
+
+        for (entity, character_barks, position, ui_text) in
+            (&entities, &mut barks, &positions, &ui_texts).join()
+        {
+            character_barks.cooldown = (character_barks.cooldown - seconds).max(0.0);
+
+            let is_near = player_position
+                .map(|player_position| position.distance_to(player_position) <= BARK_DISTANCE)
+                .unwrap_or(false);
+
+            if !is_near || character_barks.cooldown > 0.0 || speaking.contains(&entity) {
+                continue;
+            }
+
+            character_barks.cooldown = rng.gen_range(BARK_INTERVAL.0, BARK_INTERVAL.1);
+
+            if let (Some(line), Some(&character)) =
+                (character_barks.lines.choose(rng), coordinates.get(&entity))
+            {
+                let line = locale.translate(line).to_string();
+                new_barks.push((entity, line, ui_text.font.clone(), character));
+            }
+        }
+
+        for (character, line, font, coordinates) in new_barks {
+            let (ui_text, ui_image) = get_speech_bubble_text(font, &line);
+
+            let mut ui_transform = get_speech_bubble_transform(&line);
+            let (x, y) = get_label_coordinates(coordinates, SPEECH_BUBBLE_ROWS, tile_height);
+            ui_transform.local_x = x;
+            ui_transform.local_y = y;
+
+            entities
+                .build_entity()
+                .with(SpeechBubble::new(character), &mut bubbles)
+                .with(ui_text, &mut ui_texts)
+                .with(ui_image, &mut ui_images)
+                .with(ui_transform, &mut ui_transforms)
+                .build();
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use amethyst::{
+    ecs::prelude::{Entities, Entity, Join, Read, System, WriteStorage},
+    ui::UiText,
+};
+
+use crate::locale::{get_key, Locale, LocalizedText};
+
+/// Shows the strings of the current `Locale` in labels whose text is a key (`@key`),
+/// such as those in UI files, and updates them when the language changes.
+pub struct LocaleSystem;
+
+impl<'s> System<'s> for LocaleSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, Locale>,
+        WriteStorage<'s, LocalizedText>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (entities, locale, mut localized_texts, mut ui_texts): Self::SystemData) {
+        let new_labels = (&entities, &ui_texts, !&localized_texts)
+            .join()
+            .filter_map(|(entity, ui_text, _)| {
+                get_key(&ui_text.text).map(|key| (entity, key.to_string()))
+            })
+            .collect::<Vec<(Entity, String)>>();
+
+        for (entity, key) in new_labels {
+            localized_texts
+                .insert(entity, LocalizedText(key))
+                .expect("could not add the key of a label");
+        }
+
+        for (LocalizedText(key), ui_text) in (&localized_texts, &mut ui_texts).join() {
+            let text = locale.get(key);
+
+            if ui_text.text != text {
+                ui_text.text = text.to_string();
+            }
+        }
+    }
+}
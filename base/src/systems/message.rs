@@ -0,0 +1,31 @@
+use amethyst::{
+    ecs::prelude::{Read, ReadExpect, System, WriteStorage},
+    ui::UiText,
+};
+
+use crate::game::{consts::HUD_MESSAGE_LINES, Hud, MessageLog};
+
+/// Shows the latest messages from the `MessageLog` in the `Hud`.
+pub struct MessageHudSystem;
+
+impl<'s> System<'s> for MessageHudSystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        ReadExpect<'s, Hud>,
+        Read<'s, MessageLog>,
+    );
+
+    fn run(&mut self, (mut ui_texts, hud, message_log): Self::SystemData) {
+        let text = message_log
+            .latest(HUD_MESSAGE_LINES)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(ui_text) = ui_texts.get_mut(hud.messages) {
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+    }
+}
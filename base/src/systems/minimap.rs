@@ -0,0 +1,157 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{Hidden, Named},
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, WriteExpect,
+        WriteStorage,
+    },
+    input::{InputEvent, StringBindings},
+    renderer::Texture,
+    shrev::{EventChannel, ReaderId},
+    ui::{UiImage, UiTransform},
+};
+
+use crate::game::{
+    create_minimap_image, get_minimap_size, ActiveArea, Area, Collision, FactionMember, Factions,
+    Minimap, MinimapTile, PlayerCharacter, Position,
+};
+
+use super::movement::update_transforms::UpdateTransformsEvent;
+
+/// Toggles the `Minimap` and redraws it while it is visible and entities have moved.
+/// It is resized to the active area when it is redrawn, eg. after changing areas.
+///
+/// Entities with `Collision` are shown as walls unless they are characters.
+#[derive(Default)]
+pub struct MinimapSystem {
+    input_reader: Option<ReaderId<InputEvent<StringBindings>>>,
+    transforms_reader: Option<ReaderId<UpdateTransformsEvent>>,
+}
+
+impl<'s> System<'s> for MinimapSystem {
+    type SystemData = (
+        WriteExpect<'s, Minimap>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, Hidden>,
+        ReadExpect<'s, ActiveArea>,
+        ReadStorage<'s, Area>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Collision>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, FactionMember>,
+        Read<'s, Factions>,
+        Read<'s, AssetStorage<Texture>>,
+        ReadExpect<'s, Loader>,
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Read<'s, EventChannel<UpdateTransformsEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut minimap,
+            mut images,
+            mut ui_transforms,
+            mut hiddens,
+            current_area,
+            areas,
+            positions,
+            collisions,
+            characters,
+            names,
+            members,
+            factions,
+            texture_storage,
+            loader,
+            input_events,
+            transforms_events,
+        ) = data;
+
+        let num_toggles = input_events
+            .read(self.input_reader.as_mut().unwrap())
+            .filter(|event| match event {
+                InputEvent::ActionPressed(action) => action == "toggle_minimap",
+                _ => false,
+            })
+            .count();
+
+        let has_moved = transforms_events
+            .read(self.transforms_reader.as_mut().unwrap())
+            .count()
+            > 0;
+
+        let is_toggled = num_toggles % 2 == 1;
+
+        if is_toggled {
+            minimap.is_visible = !minimap.is_visible;
+
+            if minimap.is_visible {
+                hiddens.remove(minimap.entity);
+            } else {
+                hiddens
+                    .insert(minimap.entity, Hidden)
+                    .expect("could not hide the minimap");
+            }
+        }
+
+        if !minimap.is_visible || !(is_toggled || has_moved) {
+            return;
+        }
+
+        let area = match areas.get(current_area.0) {
+            Some(area) => area,
+            None => return,
+        };
+
+        let tiles = (
+            &positions,
+            &collisions,
+            characters.maybe(),
+            names.maybe(),
+            members.maybe(),
+        )
+            .join()
+            .map(|(position, _, player, name, member)| {
+                let tile = match (player, name) {
+                    (Some(_), _) => MinimapTile::Player,
+                    (None, Some(_)) => MinimapTile::Character(
+                        member
+                            .and_then(|FactionMember(id)| factions.get(id))
+                            .map(|faction| faction.color),
+                    ),
+                    (None, None) => MinimapTile::Wall,
+                };
+
+                (position.clone(), tile)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(ui_transform) = ui_transforms.get_mut(minimap.entity) {
+            let (width, height) = get_minimap_size(&area.dimensions);
+            ui_transform.width = width;
+            ui_transform.height = height;
+        }
+
+        match create_minimap_image(&area.dimensions, &tiles, &texture_storage, &loader) {
+            Ok(image) => {
+                images
+                    .insert(minimap.entity, image)
+                    .expect("could not update the minimap image");
+            }
+            Err(msg) => log::error!("could not draw the minimap: {}", msg),
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.input_reader = Some(
+            res.fetch_mut::<EventChannel<InputEvent<StringBindings>>>()
+                .register_reader(),
+        );
+        self.transforms_reader = Some(
+            res.fetch_mut::<EventChannel<UpdateTransformsEvent>>()
+                .register_reader(),
+        );
+    }
+}
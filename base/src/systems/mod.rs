@@ -1,8 +1,34 @@
+pub mod campaign;
+pub mod debug;
 pub mod input;
+pub mod inventory;
+pub mod label;
+pub mod locale;
+pub mod message;
+pub mod minimap;
+pub mod mouse;
 pub mod movement;
+pub mod objective;
+pub mod relations;
+pub mod replay;
+pub mod script;
+pub mod talk;
 
+pub use campaign::{CampaignHudSystem, CampaignSystem, TurnClockSystem};
+pub use debug::DebugOverlaySystem;
 pub use input::InputSystem;
+pub use inventory::{ItemPickupSystem, ItemTransferSystem};
+pub use label::{BarkSystem, NameLabelSystem};
+pub use locale::LocaleSystem;
+pub use message::MessageHudSystem;
+pub use minimap::MinimapSystem;
+pub use mouse::{ClickToMoveSystem, MouseHoverSystem};
 pub use movement::{
-    CameraMovementSystem, PlayerMovementSystem, UpdateCharTileTransformsSystem,
-    UpdateTransformsSystem,
+    CameraMovementSystem, PlayerMovementSystem, ScreenResizeSystem, TweenSystem,
+    UpdateCharTileTransformsSystem, UpdateTransformsSystem, ZoomSystem,
 };
+pub use objective::ObjectiveSystem;
+pub use relations::{RelationshipHudSystem, RelationshipSystem};
+pub use replay::{InputRecorderSystem, ReplaySystem};
+pub use script::{AreaChangeEvent, AreaChangeSystem, AreaTriggerSystem};
+pub use talk::{TalkEvent, TalkSystem};
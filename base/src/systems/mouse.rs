@@ -0,0 +1,322 @@
+use amethyst::{
+    core::{Named, Transform},
+    ecs::prelude::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+    renderer::ActiveCamera,
+    shrev::EventChannel,
+    ui::{UiText, UiTransform},
+    window::ScreenDimensions,
+};
+
+use std::time::Instant;
+
+use crate::{
+    config::Config,
+    game::{ActiveArea, Area, Collision, DebugOverlay, Hud, Item, PlayerCharacter, Position, Zoom},
+    locale::Locale,
+    render::get_screen_center_coordinates,
+    systems::{
+        input::{is_any_input_active, InputState, SystemClock, TimeSource},
+        movement::{
+            find_path, get_active_camera_coordinates, get_grid_position_for_screen_coordinates,
+            Action, PlayerActionEvent,
+        },
+    },
+};
+
+/// Distance from the mouse cursor to the tooltip (in pixels).
+const TOOLTIP_OFFSET: (f32, f32) = (12.0, 12.0);
+
+/// Shows a tooltip next to the mouse cursor with what is on the tile under it.
+///
+/// The entity id is added to the label while the `DebugOverlay` is visible.
+pub struct MouseHoverSystem;
+
+impl<'s> System<'s> for MouseHoverSystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiTransform>,
+        ReadExpect<'s, Hud>,
+        ReadExpect<'s, DebugOverlay>,
+        Entities<'s>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, ActiveCamera>,
+        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, Zoom>,
+        ReadExpect<'s, ActiveArea>,
+        ReadStorage<'s, Area>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, Item>,
+        ReadStorage<'s, Transform>,
+        Read<'s, Locale>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut ui_texts,
+            mut ui_transforms,
+            hud,
+            debug_overlay,
+            entities,
+            input,
+            camera,
+            dimensions,
+            zoom,
+            current_area,
+            areas,
+            positions,
+            names,
+            items,
+            transforms,
+            locale,
+        ) = data;
+
+        let cursor = get_cursor_screen_coordinates(&*input, &dimensions);
+
+        let hovered = cursor.and_then(|coordinates| {
+            get_grid_position_for_screen_coordinates(
+                get_screen_center_coordinates(&dimensions),
+                get_active_camera_coordinates(&camera, &transforms),
+                coordinates,
+                zoom.tile_size(),
+            )
+        });
+
+        let text = match (hovered, areas.get(current_area.0)) {
+            (Some(target), Some(area)) => {
+                let label = (&entities, &positions, &names)
+                    .join()
+                    .find(|(_, position, _)| **position == target)
+                    .map(|(entity, _, name)| {
+                        (entity, locale.character_name(&name.name).to_string())
+                    })
+                    .or_else(|| {
+                        (&entities, &positions, &items)
+                            .join()
+                            .find(|(_, position, _)| **position == target)
+                            .map(|(entity, _, item)| (entity, item.to_string()))
+                    })
+                    .map(|(entity, label)| {
+                        if debug_overlay.is_visible {
+                            format!("{} #{}", label, entity.id())
+                        } else {
+                            label
+                        }
+                    });
+
+                get_tooltip_text(label, area, &target)
+            }
+            _ => String::new(),
+        };
+
+        if let Some(ui_text) = ui_texts.get_mut(hud.tooltip) {
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+
+        if let (Some((x, y)), Some(transform)) = (cursor, ui_transforms.get_mut(hud.tooltip)) {
+            transform.local_x = x + TOOLTIP_OFFSET.0;
+            transform.local_y = y + TOOLTIP_OFFSET.1;
+        }
+    }
+}
+
+/// Walks the player character to the tile which was clicked, one step at a time.
+///
+/// The path is found again before every step, so the walk goes around characters
+/// which block the way. Pressing any key or other bound action, or pushing a
+/// controller stick, stops the walk. Steps are taken at the repeat rate of held
+/// movement keys as set in the `Config`.
+pub struct ClickToMoveSystem<C: TimeSource = SystemClock> {
+    clock: C,
+    target: Option<Position>,
+    was_clicked: bool,
+    last_step: Option<Instant>,
+}
+
+impl Default for ClickToMoveSystem {
+    fn default() -> Self {
+        ClickToMoveSystem::with_clock(SystemClock)
+    }
+}
+
+impl<C: TimeSource> ClickToMoveSystem<C> {
+    pub fn with_clock(clock: C) -> Self {
+        ClickToMoveSystem {
+            clock,
+            target: None,
+            was_clicked: false,
+            last_step: None,
+        }
+    }
+}
+
+impl<'s, C: TimeSource> System<'s> for ClickToMoveSystem<C> {
+    type SystemData = (
+        Write<'s, EventChannel<PlayerActionEvent>>,
+        Read<'s, Config>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, ActiveCamera>,
+        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, Zoom>,
+        ReadExpect<'s, ActiveArea>,
+        ReadStorage<'s, Area>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadStorage<'s, Collision>,
+        ReadStorage<'s, Transform>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut event_channel,
+            config,
+            input,
+            camera,
+            dimensions,
+            zoom,
+            current_area,
+            areas,
+            positions,
+            characters,
+            collisions,
+            transforms,
+        ) = data;
+
+        let is_clicked = input.action_is_down("move_to_cursor").unwrap_or(false);
+
+        if is_clicked && !self.was_clicked {
+            self.target =
+                get_cursor_screen_coordinates(&*input, &dimensions).and_then(|coordinates| {
+                    get_grid_position_for_screen_coordinates(
+                        get_screen_center_coordinates(&dimensions),
+                        get_active_camera_coordinates(&camera, &transforms),
+                        coordinates,
+                        zoom.tile_size(),
+                    )
+                });
+            self.last_step = None;
+        } else if is_any_input_active(&*input, "move_to_cursor", config.stick_deadzone) {
+            self.target = None;
+        }
+
+        self.was_clicked = is_clicked;
+
+        let target = match self.target.as_ref() {
+            Some(target) => target,
+            None => return,
+        };
+
+        let now = self.clock.now();
+        let step_duration = config.get_repeat_settings("move").min_duration_repeat;
+
+        if let Some(last_step) = self.last_step {
+            if now.duration_since(last_step) < step_duration {
+                return;
+            }
+        }
+
+        let player_position = (&positions, &characters)
+            .join()
+            .map(|(position, _)| position)
+            .next();
+
+        let occupied_positions = (&positions, &collisions, !&characters)
+            .join()
+            .map(|(position, _, _)| position.clone())
+            .collect::<Vec<_>>();
+
+        let step = match (player_position, areas.get(current_area.0)) {
+            (Some(start), Some(area)) => find_path(
+                start,
+                target,
+                &area.dimensions,
+                &occupied_positions,
+                config.allow_diagonal_movement,
+            )
+            .and_then(|path| path.first().cloned()),
+            _ => None,
+        };
+
+        match step {
+            Some(direction) => {
+                event_channel.single_write(PlayerActionEvent(Action::Move(direction)));
+                self.last_step = Some(now);
+            }
+            None => self.target = None,
+        }
+    }
+}
+
+/// Get the mouse cursor position in screen coordinates, from the bottom left corner
+/// like the `UiTransform`s of character tiles.
+fn get_cursor_screen_coordinates<I: InputState + ?Sized>(
+    input: &I,
+    dimensions: &ScreenDimensions,
+) -> Option<(f32, f32)> {
+    input
+        .cursor_position()
+        .map(|(x, y)| (x, dimensions.height() - y))
+}
+
+/// Describe what is on a tile: the label of what stands there, otherwise the area
+/// name and the tile coordinates. Empty if the tile is outside of the area.
+fn get_tooltip_text(label: Option<String>, area: &Area, position: &Position) -> String {
+    let [size_x, size_y] = area.dimensions;
+
+    if position.x >= size_x || position.y >= size_y {
+        String::new()
+    } else {
+        label.unwrap_or_else(|| format!("{} ({}, {})", area.name, position.x, position.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_area() -> Area {
+        Area {
+            name: "City Hall".to_string(),
+            dimensions: [10, 5],
+        }
+    }
+
+    #[test]
+    fn tooltip_shows_the_label_of_what_is_on_the_tile() {
+        assert_eq!(
+            get_tooltip_text(
+                Some("Rob".to_string()),
+                &get_area(),
+                &Position { x: 1, y: 1 }
+            ),
+            "Rob"
+        );
+    }
+
+    #[test]
+    fn tooltip_for_empty_tiles_shows_area_and_coordinates() {
+        assert_eq!(
+            get_tooltip_text(None, &get_area(), &Position { x: 3, y: 4 }),
+            "City Hall (3, 4)"
+        );
+    }
+
+    #[test]
+    fn tooltip_outside_of_the_area_is_empty() {
+        assert_eq!(
+            get_tooltip_text(None, &get_area(), &Position { x: 10, y: 0 }),
+            ""
+        );
+        assert_eq!(
+            get_tooltip_text(
+                Some("Rob".to_string()),
+                &get_area(),
+                &Position { x: 0, y: 5 }
+            ),
+            ""
+        );
+    }
+}
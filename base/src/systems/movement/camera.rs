@@ -1,20 +1,56 @@
 use amethyst::{
     ecs::prelude::{
-        Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, WriteStorage,
+        Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, Write, WriteStorage,
     },
     renderer::Camera,
     shrev::{EventChannel, ReaderId},
+    window::ScreenDimensions,
 };
 
-use crate::game::{ActiveArea, Area, PlayerCharacter, Position};
+use crate::{
+    game::{get_zoomed_camera, ActiveArea, Area, PlayerCharacter, Position, Zoom},
+    render::get_screen_center_coordinates,
+};
 
 use super::{player::clamp_position, update_transforms::UpdateTransformsEvent};
 
-// Camera position buffers to halt movement this many tiles before the current area edge.
-const CAMERA_AREA_EDGE_BUFFER_WIDTH_X: u32 = 17;
-const CAMERA_AREA_EDGE_BUFFER_WIDTH_Y: u32 = 7;
+/// Updates the `Camera` projection when the screen is resized and signals that all
+/// transforms (and the camera bounds) have to be updated.
+#[derive(Default)]
+pub struct ScreenResizeSystem {
+    dimensions: Option<(f32, f32)>,
+}
+
+impl<'s> System<'s> for ScreenResizeSystem {
+    type SystemData = (
+        WriteStorage<'s, Camera>,
+        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, Zoom>,
+        Write<'s, EventChannel<UpdateTransformsEvent>>,
+    );
+
+    fn run(&mut self, (mut cameras, dimensions, zoom, mut events): Self::SystemData) {
+        let current = (dimensions.width(), dimensions.height());
+
+        if self.dimensions != Some(current) {
+            // The initial dimensions are set up along with the camera
+            if self.dimensions.is_some() {
+                for camera in (&mut cameras).join() {
+                    *camera = get_zoomed_camera(&dimensions, &zoom);
+                }
+
+                events.single_write(UpdateTransformsEvent);
+            }
+
+            self.dimensions = Some(current);
+        }
+    }
+}
 
 /// Moves the `Camera` along with the player character.
+///
+/// The camera stops before the area edges so that nothing outside of the area
+/// is shown, depending on how many tiles fit on the screen.
 pub struct CameraMovementSystem {
     pub reader: Option<ReaderId<UpdateTransformsEvent>>,
 }
@@ -26,13 +62,23 @@ impl<'s> System<'s> for CameraMovementSystem {
         ReadStorage<'s, PlayerCharacter>,
         ReadExpect<'s, ActiveArea>,
         ReadStorage<'s, Area>,
+        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, Zoom>,
         Read<'s, EventChannel<UpdateTransformsEvent>>,
     );
 
-    fn run(
-        &mut self,
-        (mut positions, cameras, characters, current_area, areas, event_channel): Self::SystemData,
-    ) {
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut positions,
+            cameras,
+            characters,
+            current_area,
+            areas,
+            dimensions,
+            zoom,
+            event_channel,
+        ) = data;
+
         for _ in event_channel.read(self.reader.as_mut().unwrap()) {
             let target = (&positions, &characters)
                 .join()
@@ -42,11 +88,12 @@ impl<'s> System<'s> for CameraMovementSystem {
                 .unwrap_or(Position { x: 0, y: 0 });
 
             let area_size = areas.get(current_area.0).unwrap().dimensions;
-            let [min_x, min_y, max_x, max_y] = get_valid_camera_positions(
-                &area_size,
-                CAMERA_AREA_EDGE_BUFFER_WIDTH_X,
-                CAMERA_AREA_EDGE_BUFFER_WIDTH_Y,
+            let (border_x, border_y) = get_camera_edge_buffer(
+                get_screen_center_coordinates(&dimensions),
+                zoom.tile_size(),
             );
+            let [min_x, min_y, max_x, max_y] =
+                get_valid_camera_positions(&area_size, border_x, border_y);
 
             for (position, _) in (&mut positions, &cameras).join() {
                 update_position(position, &target, &[min_x, min_y, max_x, max_y]);
@@ -63,27 +110,43 @@ impl<'s> System<'s> for CameraMovementSystem {
     }
 }
 
+/// Get the number of tiles that are visible between the camera tile and the left
+/// and bottom edges of the screen, including partially visible tiles.
+///
+/// The camera tile is placed with its lower left corner at the screen center.
+fn get_camera_edge_buffer(
+    (center_x, center_y): (f32, f32),
+    (tile_width, tile_height): (f32, f32),
+) -> (u32, u32) {
+    (
+        (center_x / tile_width).ceil() as u32,
+        (center_y / tile_height).ceil() as u32,
+    )
+}
+
 /// Get the closed area in which the camera can move on the current grid.
 ///
 /// The allowed area will leave a border of input size to all edges in which the camera
-/// will not enter.
+/// will not enter. Since the camera tile starts at the screen center the border to the
+/// upper and right edges includes the camera tile, and areas which are smaller than
+/// the screen are centered on the middle of the grid.
 fn get_valid_camera_positions(
     [size_x, size_y]: &[u32; 2],
     border_x: u32,
     border_y: u32,
 ) -> [u32; 4] {
     [
-        clamp_position(border_x as i32, 0, size_x.saturating_sub(1) / 2),
-        clamp_position(border_y as i32, 0, size_y.saturating_sub(1) / 2),
+        clamp_position(border_x as i32, 0, size_x / 2),
+        clamp_position(border_y as i32, 0, size_y / 2),
         clamp_position(
-            *size_x as i32 - border_x as i32 - 1,
-            size_x.saturating_sub(1) / 2,
-            *size_x,
+            *size_x as i32 - border_x as i32,
+            size_x / 2,
+            size_x.saturating_sub(1),
         ),
         clamp_position(
-            *size_y as i32 - border_y as i32 - 1,
-            size_y.saturating_sub(1) / 2,
-            *size_y,
+            *size_y as i32 - border_y as i32,
+            size_y / 2,
+            size_y.saturating_sub(1),
         ),
     ]
 }
@@ -102,6 +165,23 @@ fn update_position(
 mod tests {
     use super::*;
 
+    #[test]
+    fn camera_edge_buffer_is_the_number_of_tiles_to_the_screen_edge() {
+        assert_eq!(
+            get_camera_edge_buffer((640.0, 360.0), (16.0, 24.0)),
+            (40, 15)
+        );
+    }
+
+    #[test]
+    fn camera_edge_buffer_includes_partially_visible_tiles() {
+        assert_eq!(
+            get_camera_edge_buffer((650.0, 370.0), (16.0, 24.0)),
+            (41, 16)
+        );
+        assert_eq!(get_camera_edge_buffer((0.0, 0.0), (16.0, 24.0)), (0, 0));
+    }
+
     #[test]
     fn valid_camera_positions_without_border_is_full_area() {
         assert_eq!(&[0, 0, 0, 0], &get_valid_camera_positions(&[0, 0], 0, 0));
@@ -110,19 +190,19 @@ mod tests {
 
     #[test]
     fn valid_camera_positions_with_small_border_works() {
-        assert_eq!(&[1, 1, 18, 8], &get_valid_camera_positions(&[20, 10], 1, 1));
-        assert_eq!(&[2, 2, 17, 7], &get_valid_camera_positions(&[20, 10], 2, 2));
+        assert_eq!(&[1, 1, 19, 9], &get_valid_camera_positions(&[20, 10], 1, 1));
+        assert_eq!(&[2, 2, 18, 8], &get_valid_camera_positions(&[20, 10], 2, 2));
     }
 
     #[test]
     fn valid_camera_positions_with_large_borders_are_centered() {
         assert_eq!(
-            &[9, 4, 10, 4],
+            &[9, 5, 11, 5],
             &get_valid_camera_positions(&[20, 10], 9, 9),
             "border larger than size along y but not x"
         );
         assert_eq!(
-            &[9, 4, 9, 4],
+            &[10, 5, 10, 5],
             &get_valid_camera_positions(&[20, 10], 100, 100),
             "border larger than area"
         );
@@ -135,15 +215,32 @@ mod tests {
         assert_eq!(&[0, 0, 0, 0], &get_valid_camera_positions(&[0, 0], 0, 1));
 
         // Small borders
-        assert_eq!(&[1, 0, 8, 0], &get_valid_camera_positions(&[10, 0], 1, 0));
-        assert_eq!(&[0, 1, 0, 8], &get_valid_camera_positions(&[0, 10], 0, 1));
-        assert_eq!(&[1, 2, 18, 7], &get_valid_camera_positions(&[20, 10], 1, 2));
+        assert_eq!(&[1, 0, 9, 0], &get_valid_camera_positions(&[10, 0], 1, 0));
+        assert_eq!(&[0, 1, 0, 9], &get_valid_camera_positions(&[0, 10], 0, 1));
+        assert_eq!(&[1, 2, 19, 8], &get_valid_camera_positions(&[20, 10], 1, 2));
 
         // Large border for either
-        assert_eq!(&[9, 1, 9, 8], &get_valid_camera_positions(&[20, 10], 10, 1));
         assert_eq!(
-            &[1, 4, 18, 4],
+            &[10, 1, 10, 9],
+            &get_valid_camera_positions(&[20, 10], 10, 1)
+        );
+        assert_eq!(
+            &[1, 5, 19, 5],
             &get_valid_camera_positions(&[20, 10], 1, 10)
         );
     }
+
+    #[test]
+    fn camera_shows_the_last_tiles_of_even_sized_areas() {
+        // 40 tiles to the left and 40 (including the camera tile) to the right
+        let (border_x, border_y) = get_camera_edge_buffer((640.0, 360.0), (16.0, 24.0));
+        let [min_x, min_y, max_x, max_y] =
+            get_valid_camera_positions(&[100, 40], border_x, border_y);
+
+        assert_eq!([min_x, min_y, max_x, max_y], [40, 15, 60, 25]);
+
+        // The rightmost and topmost visible tiles are the last ones of the area
+        assert_eq!(max_x + border_x - 1, 99);
+        assert_eq!(max_y + border_y - 1, 39);
+    }
 }
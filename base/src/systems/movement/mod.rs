@@ -1,25 +1,90 @@
 mod camera;
+mod path;
 mod player;
+mod tween;
 pub mod update_transforms;
+mod zoom;
 
-pub use camera::CameraMovementSystem;
+pub use camera::{CameraMovementSystem, ScreenResizeSystem};
+pub use path::find_path;
 pub use player::PlayerMovementSystem;
-pub use update_transforms::{UpdateCharTileTransformsSystem, UpdateTransformsSystem};
+pub use tween::{SnapTweensEvent, TweenSystem};
+pub use update_transforms::{
+    get_active_camera_coordinates, get_grid_position_for_screen_coordinates,
+    UpdateCharTileTransformsSystem, UpdateTransformsSystem,
+};
+pub use zoom::ZoomSystem;
 
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+use crate::game::Position;
+
+#[derive(Clone, Debug)]
 /// Event emitted if the player character has done something.
 pub struct PlayerActionEvent(pub Action);
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+/// Event emitted when the player character has moved to another tile.
+pub struct PlayerMovedEvent {
+    pub from: Position,
+    pub to: Position,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum Action {
     Action,
     Move(Move),
 }
 
-#[derive(Debug)]
+impl Action {
+    /// Name of the kind of action, used to look up its settings in the `Config`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Action => "action",
+            Action::Move(_) => "move",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum Move {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+/// All directions along the grid axes.
+pub const CARDINAL_MOVES: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+
+/// All directions including diagonals.
+pub const ALL_MOVES: [Move; 8] = [
+    Move::Up,
+    Move::Down,
+    Move::Left,
+    Move::Right,
+    Move::UpLeft,
+    Move::UpRight,
+    Move::DownLeft,
+    Move::DownRight,
+];
+
+impl Move {
+    /// Number of tiles moved along x and y.
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Move::Up => (0, 1),
+            Move::Down => (0, -1),
+            Move::Left => (-1, 0),
+            Move::Right => (1, 0),
+            Move::UpLeft => (-1, 1),
+            Move::UpRight => (1, 1),
+            Move::DownLeft => (-1, -1),
+            Move::DownRight => (1, -1),
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::game::Position;
+
+use super::{player::is_squeezing_diagonally, Move, ALL_MOVES, CARDINAL_MOVES};
+
+/// Find the shortest sequence of moves from the start to the goal inside an area
+/// of given size, going around all occupied positions.
+///
+/// If the goal itself is occupied (eg. by a character) the path ends next to it.
+/// Returns `None` if the goal cannot be reached.
+pub fn find_path(
+    start: &Position,
+    goal: &Position,
+    [size_x, size_y]: &[u32; 2],
+    occupied_positions: &[Position],
+    allow_diagonal: bool,
+) -> Option<Vec<Move>> {
+    if goal.x >= *size_x || goal.y >= *size_y {
+        return None;
+    }
+
+    let directions: &[Move] = if allow_diagonal {
+        &ALL_MOVES
+    } else {
+        &CARDINAL_MOVES
+    };
+
+    let occupied = occupied_positions.iter().collect::<HashSet<_>>();
+
+    let mut came_from: HashMap<Position, (Position, Move)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(start.clone());
+
+    while let Some(position) = queue.pop_front() {
+        if &position == goal {
+            let mut path = get_moves_to(goal, &came_from);
+
+            if occupied.contains(goal) {
+                path.pop();
+            }
+
+            return Some(path);
+        }
+
+        for direction in directions {
+            let (dx, dy) = direction.delta();
+            let (x, y) = (position.x as i32 + dx, position.y as i32 + dy);
+
+            if x < 0 || y < 0 || x >= *size_x as i32 || y >= *size_y as i32 {
+                continue;
+            }
+
+            let next = Position {
+                x: x as u32,
+                y: y as u32,
+            };
+
+            let is_visited = &next == start || came_from.contains_key(&next);
+            let is_blocked = occupied.contains(&next) && &next != goal;
+
+            if is_visited
+                || is_blocked
+                || is_squeezing_diagonally(&position, &next, occupied_positions)
+            {
+                continue;
+            }
+
+            came_from.insert(next.clone(), (position.clone(), direction.clone()));
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+/// Walk back from the goal to the start and return the moves in order.
+fn get_moves_to(goal: &Position, came_from: &HashMap<Position, (Position, Move)>) -> Vec<Move> {
+    let mut path = Vec::new();
+    let mut position = goal;
+
+    while let Some((previous, direction)) = came_from.get(position) {
+        path.push(direction.clone());
+        position = previous;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk(start: &Position, path: &[Move]) -> Position {
+        path.iter().fold(start.clone(), |position, direction| {
+            let (dx, dy) = direction.delta();
+
+            Position {
+                x: (position.x as i32 + dx) as u32,
+                y: (position.y as i32 + dy) as u32,
+            }
+        })
+    }
+
+    #[test]
+    fn path_to_the_start_is_empty() {
+        let start = Position { x: 2, y: 2 };
+
+        assert_eq!(
+            find_path(&start, &start, &[5, 5], &[], false),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn path_along_a_free_line_is_straight() {
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 3, y: 0 };
+
+        assert_eq!(
+            find_path(&start, &goal, &[5, 5], &[], false),
+            Some(vec![Move::Right, Move::Right, Move::Right])
+        );
+    }
+
+    #[test]
+    fn path_goes_around_occupied_positions() {
+        let start = Position { x: 0, y: 1 };
+        let goal = Position { x: 2, y: 1 };
+        let wall = vec![Position { x: 1, y: 0 }, Position { x: 1, y: 1 }];
+
+        let path = find_path(&start, &goal, &[3, 3], &wall, false).unwrap();
+
+        assert_eq!(path.len(), 4);
+        assert_eq!(walk(&start, &path), goal);
+    }
+
+    #[test]
+    fn path_uses_diagonals_if_allowed() {
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 2, y: 2 };
+
+        assert_eq!(
+            find_path(&start, &goal, &[5, 5], &[], true),
+            Some(vec![Move::UpRight, Move::UpRight])
+        );
+        assert_eq!(
+            find_path(&start, &goal, &[5, 5], &[], false).unwrap().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn path_does_not_squeeze_between_occupied_positions() {
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 1, y: 1 };
+        let occupied = vec![Position { x: 1, y: 0 }, Position { x: 0, y: 1 }];
+
+        assert_eq!(find_path(&start, &goal, &[2, 2], &occupied, true), None);
+    }
+
+    #[test]
+    fn path_to_an_occupied_goal_ends_next_to_it() {
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 3, y: 0 };
+
+        let path = find_path(&start, &goal, &[5, 5], &[goal.clone()], false).unwrap();
+
+        assert_eq!(walk(&start, &path), Position { x: 2, y: 0 });
+    }
+
+    #[test]
+    fn unreachable_goals_have_no_path() {
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 2, y: 0 };
+        let wall = vec![Position { x: 1, y: 0 }, Position { x: 1, y: 1 }];
+
+        assert_eq!(find_path(&start, &goal, &[3, 2], &wall, false), None);
+        assert_eq!(
+            find_path(&start, &Position { x: 5, y: 0 }, &[3, 2], &[], false),
+            None
+        );
+    }
+}
@@ -7,9 +7,12 @@ use amethyst::{
 
 use crate::game::{ActiveArea, Area, Collision, PlayerCharacter, Position};
 
-use super::{update_transforms::UpdateTransformsEvent, Action, Move, PlayerActionEvent};
+use super::{
+    update_transforms::UpdateTransformsEvent, Action, Move, PlayerActionEvent, PlayerMovedEvent,
+};
 
-/// Moves the `PlayerCharacter` inside the current active `Area`.
+/// Moves the `PlayerCharacter` inside the current active `Area` and emits
+/// a `PlayerMovedEvent` when it lands on a new tile.
 pub struct PlayerMovementSystem {
     pub reader: Option<ReaderId<PlayerActionEvent>>,
 }
@@ -18,6 +21,7 @@ impl<'s> System<'s> for PlayerMovementSystem {
     type SystemData = (
         WriteStorage<'s, Position>,
         Write<'s, EventChannel<UpdateTransformsEvent>>,
+        Write<'s, EventChannel<PlayerMovedEvent>>,
         ReadStorage<'s, PlayerCharacter>,
         ReadExpect<'s, ActiveArea>,
         ReadStorage<'s, Area>,
@@ -26,8 +30,16 @@ impl<'s> System<'s> for PlayerMovementSystem {
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut positions, mut events, character, current_area, areas, collisions, event_channel) =
-            data;
+        let (
+            mut positions,
+            mut events,
+            mut moved_events,
+            character,
+            current_area,
+            areas,
+            collisions,
+            event_channel,
+        ) = data;
 
         for event in event_channel.read(self.reader.as_mut().unwrap()) {
             if let PlayerActionEvent(Action::Move(direction)) = event {
@@ -44,7 +56,15 @@ impl<'s> System<'s> for PlayerMovementSystem {
                 for (position, _) in (&mut positions, &character).join() {
                     let destination = get_destination(position, &direction, &[0, 0, max_x, max_y]);
 
-                    if !occupied_positions.contains(&destination) {
+                    if *position != destination
+                        && !occupied_positions.contains(&destination)
+                        && !is_squeezing_diagonally(position, &destination, &occupied_positions)
+                    {
+                        moved_events.single_write(PlayerMovedEvent {
+                            from: position.clone(),
+                            to: destination.clone(),
+                        });
+
                         position.x = destination.x;
                         position.y = destination.y;
                     }
@@ -69,16 +89,34 @@ fn get_destination(
     direction: &Move,
     [min_x, min_y, max_x, max_y]: &[u32; 4],
 ) -> Position {
-    let mut new_position = position.clone();
+    let (dx, dy) = direction.delta();
 
-    match direction {
-        Move::Up => new_position.y = clamp_position(position.y as i32 + 1, *min_y, *max_y),
-        Move::Down => new_position.y = clamp_position(position.y as i32 - 1, *min_y, *max_y),
-        Move::Left => new_position.x = clamp_position(position.x as i32 - 1, *min_x, *max_x),
-        Move::Right => new_position.x = clamp_position(position.x as i32 + 1, *min_x, *max_x),
+    Position {
+        x: clamp_position(position.x as i32 + dx, *min_x, *max_x),
+        y: clamp_position(position.y as i32 + dy, *min_y, *max_y),
     }
+}
 
-    new_position
+/// Whether a diagonal move passes between two occupied tiles, which is not allowed.
+pub(super) fn is_squeezing_diagonally(
+    position: &Position,
+    destination: &Position,
+    occupied_positions: &[Position],
+) -> bool {
+    let is_diagonal = position.x != destination.x && position.y != destination.y;
+
+    let horizontal_neighbour = Position {
+        x: destination.x,
+        y: position.y,
+    };
+    let vertical_neighbour = Position {
+        x: position.x,
+        y: destination.y,
+    };
+
+    is_diagonal
+        && occupied_positions.contains(&horizontal_neighbour)
+        && occupied_positions.contains(&vertical_neighbour)
 }
 
 /// Clamp input value to the range [min, max]. Assumes that max >= min.
@@ -96,6 +134,54 @@ pub fn clamp_position(position: i32, min: u32, max: u32) -> u32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn diagonal_destinations_move_along_both_axes() {
+        let position = Position { x: 5, y: 5 };
+        let area = [0, 0, 10, 10];
+
+        assert_eq!(
+            get_destination(&position, &Move::UpLeft, &area),
+            Position { x: 4, y: 6 }
+        );
+        assert_eq!(
+            get_destination(&position, &Move::DownRight, &area),
+            Position { x: 6, y: 4 }
+        );
+    }
+
+    #[test]
+    fn diagonal_destinations_at_area_edge_slide_along_it() {
+        let position = Position { x: 0, y: 5 };
+
+        assert_eq!(
+            get_destination(&position, &Move::UpLeft, &[0, 0, 10, 10]),
+            Position { x: 0, y: 6 }
+        );
+    }
+
+    #[test]
+    fn diagonal_moves_between_two_occupied_tiles_are_squeezing() {
+        let position = Position { x: 5, y: 5 };
+        let destination = Position { x: 6, y: 6 };
+
+        let both = vec![Position { x: 6, y: 5 }, Position { x: 5, y: 6 }];
+        let one = vec![Position { x: 6, y: 5 }];
+
+        assert!(is_squeezing_diagonally(&position, &destination, &both));
+        assert!(!is_squeezing_diagonally(&position, &destination, &one));
+        assert!(!is_squeezing_diagonally(&position, &destination, &[]));
+    }
+
+    #[test]
+    fn cardinal_moves_are_never_squeezing() {
+        let position = Position { x: 5, y: 5 };
+        let destination = Position { x: 6, y: 5 };
+
+        let occupied = vec![Position { x: 6, y: 5 }, Position { x: 5, y: 5 }];
+
+        assert!(!is_squeezing_diagonally(&position, &destination, &occupied));
+    }
+
     #[test]
     fn position_clamps_to_closed_range() {
         // Lower than min
@@ -0,0 +1,105 @@
+use amethyst::{
+    core::timing::Time,
+    ecs::prelude::{
+        Entities, Join, Read, ReadStorage, Resources, System, SystemData, Write, WriteStorage,
+    },
+    renderer::Camera,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    config::Config,
+    game::{Easing, Position, Tween},
+};
+
+use super::update_transforms::UpdateTransformsEvent;
+
+#[derive(Clone, Copy, Debug)]
+/// Event to render all entities at their `Position` at once instead of sliding
+/// there, emitted when positions are set directly such as by teleports and area
+/// changes. Must be emitted after the `TweenSystem` has run in a frame, so that
+/// the camera has followed the player character when it is read.
+pub struct SnapTweensEvent;
+
+/// Slides the rendered coordinates of entities towards their `Position` over
+/// the tween duration set in the `Config`. Does nothing if the duration is zero.
+///
+/// The camera eases out as it reaches its target, so that it slows down instead
+/// of stopping at once when it is clamped at the area edges. On a `SnapTweensEvent`
+/// all entities are placed at their position without sliding.
+#[derive(Default)]
+pub struct TweenSystem {
+    reader: Option<ReaderId<SnapTweensEvent>>,
+}
+
+impl<'s> System<'s> for TweenSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Tween>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Camera>,
+        Read<'s, Config>,
+        Read<'s, Time>,
+        Write<'s, EventChannel<UpdateTransformsEvent>>,
+        Read<'s, EventChannel<SnapTweensEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut tweens, positions, cameras, config, time, mut events, snap_events) =
+            data;
+
+        let is_snapping = snap_events.read(self.reader.as_mut().unwrap()).count() > 0;
+        let duration = config.tween_duration;
+
+        if duration.as_millis() == 0 {
+            return;
+        }
+
+        let untweened = (&entities, &positions, !&tweens)
+            .join()
+            .map(|(entity, position, _)| {
+                let easing = if cameras.contains(entity) {
+                    Easing::EaseOut
+                } else {
+                    Easing::Linear
+                };
+
+                (entity, Tween::new(position, easing))
+            })
+            .collect::<Vec<_>>();
+
+        for (entity, tween) in untweened {
+            tweens
+                .insert(entity, tween)
+                .expect("could not insert `Tween` component");
+        }
+
+        let mut is_moving = false;
+
+        for (position, tween) in (&positions, &mut tweens).join() {
+            if is_snapping {
+                tween.snap_to(position);
+            } else {
+                tween.retarget(position, duration);
+            }
+
+            if !tween.is_finished(duration) {
+                tween.advance(time.delta_time());
+                is_moving = true;
+            }
+        }
+
+        // Keep updating transforms until every tween has reached its target
+        if is_moving || is_snapping {
+            events.single_write(UpdateTransformsEvent);
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<SnapTweensEvent>>()
+                .register_reader(),
+        );
+    }
+}
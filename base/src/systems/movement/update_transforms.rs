@@ -10,9 +10,10 @@ use amethyst::{
 };
 
 use crate::{
+    config::Config,
     game::{
         consts::{TILE_HEIGHT, TILE_WIDTH},
-        get_world_coordinates, Position,
+        get_render_coordinates, Position, Tween, Zoom,
     },
     render::get_screen_center_coordinates,
 };
@@ -32,6 +33,9 @@ pub struct UpdateTransformsEvent;
 ///
 /// TODO: Replace this logic by using a custom render pass for character tiles
 /// which uses `Transform`?
+///
+/// Entities (including the camera) with a `Tween` are placed at its coordinates
+/// instead of their `Position`.
 pub struct UpdateCharTileTransformsSystem {
     pub reader: Option<ReaderId<UpdateTransformsEvent>>,
 }
@@ -41,20 +45,36 @@ impl<'s> System<'s> for UpdateCharTileTransformsSystem {
         WriteStorage<'s, UiTransform>,
         Read<'s, ActiveCamera>,
         ReadExpect<'s, ScreenDimensions>,
+        Read<'s, Config>,
+        Read<'s, Zoom>,
         ReadStorage<'s, Position>,
+        ReadStorage<'s, Tween>,
         Read<'s, EventChannel<UpdateTransformsEvent>>,
     );
 
-    fn run(&mut self, (mut transforms, camera, dimensions, positions, events): Self::SystemData) {
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut transforms, camera, dimensions, config, zoom, positions, tweens, events) = data;
+
         for _ in events.read(self.reader.as_mut().unwrap()) {
+            let duration = config.tween_duration;
             let screen_center = get_screen_center_coordinates(&dimensions);
-            let camera_position = get_active_camera_position(&camera, &positions);
 
-            for (transform, entity_position) in (&mut transforms, &positions).join() {
+            let camera_coordinates = camera
+                .entity
+                .and_then(|entity| {
+                    positions.get(entity).map(|position| {
+                        get_render_coordinates(position, tweens.get(entity), duration)
+                    })
+                })
+                .unwrap_or([0.0, 0.0]);
+
+            for (transform, position, tween) in (&mut transforms, &positions, tweens.maybe()).join()
+            {
                 let (x, y) = get_screen_absolute_coordinates_for_entity_grid_position(
                     screen_center,
-                    &camera_position,
-                    entity_position,
+                    camera_coordinates,
+                    get_render_coordinates(position, tween, duration),
+                    zoom.tile_size(),
                 );
 
                 transform.local_x = x;
@@ -80,14 +100,21 @@ pub struct UpdateTransformsSystem {
 impl<'s> System<'s> for UpdateTransformsSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
+        Read<'s, Config>,
         ReadStorage<'s, Position>,
+        ReadStorage<'s, Tween>,
         Read<'s, EventChannel<UpdateTransformsEvent>>,
     );
 
-    fn run(&mut self, (mut transforms, positions, events): Self::SystemData) {
+    fn run(&mut self, (mut transforms, config, positions, tweens, events): Self::SystemData) {
         for _ in events.read(self.reader.as_mut().unwrap()) {
-            for (transform, position) in (&mut transforms, &positions).join() {
-                let (x, y) = get_world_coordinates(position.x, position.y);
+            for (transform, position, tween) in (&mut transforms, &positions, tweens.maybe()).join()
+            {
+                let (x, y) = get_world_coordinates_for_grid_coordinates(get_render_coordinates(
+                    position,
+                    tween,
+                    config.tween_duration,
+                ));
                 transform.set_translation_x(x);
                 transform.set_translation_y(y);
             }
@@ -103,32 +130,200 @@ impl<'s> System<'s> for UpdateTransformsSystem {
     }
 }
 
-fn get_active_camera_position(
+/// Get the (possibly fractional) grid coordinates of the active camera from its
+/// `Transform`, which follows its `Tween` while the camera pans, or origin if there
+/// is none.
+pub fn get_active_camera_coordinates(
     camera: &ActiveCamera,
-    positions: &ReadStorage<Position>,
-) -> Position {
+    transforms: &ReadStorage<Transform>,
+) -> [f32; 2] {
     camera
         .entity
-        .and_then(|entity| positions.get(entity))
-        .cloned()
-        .unwrap_or(Position { x: 0, y: 0 })
+        .and_then(|entity| transforms.get(entity))
+        .map(|transform| {
+            let translation = transform.translation();
+            get_grid_coordinates_for_world_coordinates((translation.x, translation.y))
+        })
+        .unwrap_or([0.0, 0.0])
+}
+
+/// Translate from (possibly fractional) grid coordinates to world pixel coordinates.
+fn get_world_coordinates_for_grid_coordinates([x, y]: [f32; 2]) -> (f32, f32) {
+    (x * TILE_WIDTH as f32, y * TILE_HEIGHT as f32)
+}
+
+/// Translate from world pixel coordinates to (possibly fractional) grid coordinates.
+fn get_grid_coordinates_for_world_coordinates((x, y): (f32, f32)) -> [f32; 2] {
+    [x / TILE_WIDTH as f32, y / TILE_HEIGHT as f32]
 }
 
+/// Get the screen-absolute coordinates of an entity's center from its grid
+/// coordinates, with tiles of the given size (in pixels).
 fn get_screen_absolute_coordinates_for_entity_grid_position(
     screen_center: (f32, f32),
-    camera: &Position,
-    entity: &Position,
+    camera: [f32; 2],
+    entity: [f32; 2],
+    (tile_width, tile_height): (f32, f32),
 ) -> (f32, f32) {
     let (x0, y0) = screen_center;
 
-    let (xcamera, ycamera) = get_world_coordinates(camera.x, camera.y);
-    let (xentity, yentity) = get_world_coordinates(entity.x, entity.y);
+    let dx = (entity[0] - camera[0]) * tile_width;
+    let dy = (entity[1] - camera[1]) * tile_height;
+
+    (x0 + dx + tile_width / 2.0, y0 + dy + tile_height / 2.0)
+}
+
+/// Get the grid position of the tile at screen-absolute coordinates, eg. the mouse
+/// cursor, with tiles of the given size (in pixels). This is the inverse of how
+/// character tiles are placed on screen, also while the camera is between tiles.
+///
+/// Returns `None` if the coordinates lie outside of the grid.
+pub fn get_grid_position_for_screen_coordinates(
+    screen_center: (f32, f32),
+    camera: [f32; 2],
+    (x, y): (f32, f32),
+    (tile_width, tile_height): (f32, f32),
+) -> Option<Position> {
+    let (x0, y0) = screen_center;
+
+    let grid_x = (camera[0] + (x - x0) / tile_width).floor() as i32;
+    let grid_y = (camera[1] + (y - y0) / tile_height).floor() as i32;
+
+    if grid_x >= 0 && grid_y >= 0 {
+        Some(Position {
+            x: grid_x as u32,
+            y: grid_y as u32,
+        })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILE_SIZE: (f32, f32) = (16.0, 24.0);
+
+    #[test]
+    fn screen_coordinates_of_entities_map_back_to_their_grid_position() {
+        let screen_center = (400.0, 300.0);
+        let camera = [10.0, 5.0];
+
+        for &tile_size in &[TILE_SIZE, (32.0, 48.0)] {
+            for entity in &[
+                Position { x: 10, y: 5 },
+                Position { x: 0, y: 0 },
+                Position { x: 23, y: 2 },
+            ] {
+                let coordinates = get_screen_absolute_coordinates_for_entity_grid_position(
+                    screen_center,
+                    camera,
+                    [entity.x as f32, entity.y as f32],
+                    tile_size,
+                );
+
+                assert_eq!(
+                    get_grid_position_for_screen_coordinates(
+                        screen_center,
+                        camera,
+                        coordinates,
+                        tile_size
+                    ),
+                    Some(entity.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fractional_grid_coordinates_are_placed_between_tiles() {
+        let (x, y) = get_screen_absolute_coordinates_for_entity_grid_position(
+            (400.0, 300.0),
+            [10.0, 5.0],
+            [10.5, 4.5],
+            TILE_SIZE,
+        );
+
+        assert_eq!((x, y), (400.0 + 8.0 + 8.0, 300.0 + 12.0 - 12.0));
+    }
+
+    #[test]
+    fn larger_tiles_are_placed_further_apart() {
+        let (x, y) = get_screen_absolute_coordinates_for_entity_grid_position(
+            (400.0, 300.0),
+            [10.0, 5.0],
+            [11.0, 6.0],
+            (32.0, 48.0),
+        );
+
+        assert_eq!((x, y), (400.0 + 32.0 + 16.0, 300.0 + 48.0 + 24.0));
+    }
+
+    #[test]
+    fn screen_coordinates_cover_the_whole_tile() {
+        let screen_center = (400.0, 300.0);
+        let camera = Position { x: 10, y: 5 };
+        let coordinates = [camera.x as f32, camera.y as f32];
+
+        let corner = (400.0, 300.0);
+        let opposite_corner = (400.0 + 16.0 - 0.5, 300.0 + 24.0 - 0.5);
+
+        assert_eq!(
+            get_grid_position_for_screen_coordinates(screen_center, coordinates, corner, TILE_SIZE),
+            Some(camera.clone())
+        );
+        assert_eq!(
+            get_grid_position_for_screen_coordinates(
+                screen_center,
+                coordinates,
+                opposite_corner,
+                TILE_SIZE
+            ),
+            Some(camera.clone())
+        );
+    }
 
-    let dx = xentity - xcamera;
-    let dy = yentity - ycamera;
+    #[test]
+    fn screen_coordinates_outside_of_the_grid_have_no_position() {
+        assert_eq!(
+            get_grid_position_for_screen_coordinates(
+                (400.0, 300.0),
+                [0.0, 0.0],
+                (399.0, 300.0),
+                TILE_SIZE
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn screen_coordinates_follow_the_camera_between_tiles() {
+        let screen_center = (400.0, 300.0);
 
-    (
-        x0 + dx + (TILE_WIDTH / 2) as f32,
-        y0 + dy + (TILE_HEIGHT / 2) as f32,
-    )
+        // Halfway through panning one tile to the right the glyph of the tile at
+        // (10, 5) is drawn half a tile left of where it was
+        let camera = [10.5, 5.0];
+        let coordinates = get_screen_absolute_coordinates_for_entity_grid_position(
+            screen_center,
+            camera,
+            [10.0, 5.0],
+            TILE_SIZE,
+        );
+
+        assert_eq!(coordinates, (400.0, 312.0));
+        assert_eq!(
+            get_grid_position_for_screen_coordinates(screen_center, camera, coordinates, TILE_SIZE),
+            Some(Position { x: 10, y: 5 })
+        );
+    }
+
+    #[test]
+    fn world_coordinates_map_back_to_grid_coordinates() {
+        let world = get_world_coordinates_for_grid_coordinates([3.5, 2.0]);
+        assert_eq!(
+            get_grid_coordinates_for_world_coordinates(world),
+            [3.5, 2.0]
+        );
+    }
 }
@@ -0,0 +1,84 @@
+use amethyst::{
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, Write, WriteStorage,
+    },
+    input::{InputEvent, StringBindings},
+    renderer::Camera,
+    shrev::{EventChannel, ReaderId},
+    ui::{UiText, UiTransform},
+    window::ScreenDimensions,
+};
+
+use crate::game::{get_zoomed_camera, Glyph, Zoom};
+
+use super::update_transforms::UpdateTransformsEvent;
+
+/// Changes the `Zoom` level on the zoom in and out actions and rescales the camera
+/// and all glyphs to match.
+pub struct ZoomSystem {
+    pub reader: Option<ReaderId<InputEvent<StringBindings>>>,
+}
+
+impl<'s> System<'s> for ZoomSystem {
+    type SystemData = (
+        Write<'s, Zoom>,
+        WriteStorage<'s, Camera>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiTransform>,
+        ReadStorage<'s, Glyph>,
+        ReadExpect<'s, ScreenDimensions>,
+        Write<'s, EventChannel<UpdateTransformsEvent>>,
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut zoom,
+            mut cameras,
+            mut ui_texts,
+            mut ui_transforms,
+            glyphs,
+            dimensions,
+            mut events,
+            input_events,
+        ) = data;
+
+        let mut is_changed = false;
+
+        for event in input_events.read(self.reader.as_mut().unwrap()) {
+            if let InputEvent::ActionPressed(action) = event {
+                is_changed |= match action.as_str() {
+                    "zoom_in" => zoom.zoom_in(),
+                    "zoom_out" => zoom.zoom_out(),
+                    _ => false,
+                };
+            }
+        }
+
+        if !is_changed {
+            return;
+        }
+
+        for camera in (&mut cameras).join() {
+            *camera = get_zoomed_camera(&dimensions, &zoom);
+        }
+
+        let (tile_width, tile_height) = zoom.tile_size();
+
+        for (ui_text, ui_transform, _) in (&mut ui_texts, &mut ui_transforms, &glyphs).join() {
+            ui_text.font_size = zoom.glyph_font_size();
+            ui_transform.width = tile_width;
+            ui_transform.height = tile_height;
+        }
+
+        events.single_write(UpdateTransformsEvent);
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<InputEvent<StringBindings>>>()
+                .register_reader(),
+        );
+    }
+}
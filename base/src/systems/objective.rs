@@ -0,0 +1,116 @@
+use amethyst::{
+    core::Named,
+    ecs::prelude::{
+        Entities, Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, Write,
+        WriteExpect, WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::game::{
+    ActiveArea, Area, Campaign, CampaignEvent, GameFlags, Inventory, MessageLog, Objectives,
+    PlayerCharacter, RelationshipEvent, Reward, Subject, TriggerContext,
+};
+
+use super::talk::TalkEvent;
+
+/// Tracks the progress of all `Objectives`, completing them and handing out their
+/// rewards when their triggers are met.
+pub struct ObjectiveSystem {
+    pub reader: Option<ReaderId<TalkEvent>>,
+}
+
+impl<'s> System<'s> for ObjectiveSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteExpect<'s, Objectives>,
+        Write<'s, MessageLog>,
+        WriteStorage<'s, Inventory>,
+        Write<'s, EventChannel<RelationshipEvent>>,
+        Write<'s, EventChannel<CampaignEvent>>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadExpect<'s, ActiveArea>,
+        ReadStorage<'s, Area>,
+        ReadExpect<'s, Campaign>,
+        Read<'s, GameFlags>,
+        Read<'s, EventChannel<TalkEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut objectives,
+            mut message_log,
+            mut inventories,
+            mut relationship_events,
+            mut campaign_events,
+            names,
+            characters,
+            current_area,
+            areas,
+            campaign,
+            flags,
+            talk_events,
+        ) = data;
+
+        for TalkEvent { character } in talk_events.read(self.reader.as_mut().unwrap()) {
+            if let Some(name) = names.get(*character) {
+                objectives.talked_to.insert(name.name.to_string());
+            }
+        }
+
+        let completed = {
+            let context = TriggerContext {
+                area: areas
+                    .get(current_area.0)
+                    .map(|area| area.name.as_str())
+                    .unwrap_or(""),
+                inventory: (&inventories, &characters)
+                    .join()
+                    .map(|(inventory, _)| inventory)
+                    .next(),
+                polls: campaign.polls(),
+                flags: &flags,
+            };
+
+            objectives.complete_triggered(&context)
+        };
+
+        for objective in completed {
+            message_log.push(format!("Objective complete: {}", objective.description));
+
+            for reward in objective.rewards {
+                match reward {
+                    Reward::Item(item) => {
+                        message_log.push(format!("Received: {}", item));
+
+                        for (inventory, _) in (&mut inventories, &characters).join() {
+                            inventory.add(item.clone());
+                        }
+                    }
+                    Reward::Relationship { character, change } => {
+                        let entity = (&entities, &names)
+                            .join()
+                            .find(|(_, name)| name.name == character)
+                            .map(|(entity, _)| entity);
+
+                        if let Some(entity) = entity {
+                            relationship_events.single_write(RelationshipEvent {
+                                character: entity,
+                                subject: Subject::Player,
+                                change,
+                            });
+                        }
+                    }
+                    Reward::Campaign(event) => campaign_events.single_write(event),
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(res.fetch_mut::<EventChannel<TalkEvent>>().register_reader());
+    }
+}
@@ -0,0 +1,112 @@
+use amethyst::{
+    core::Named,
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData, WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::{
+    game::{Attitude, Hud, PlayerCharacter, Position, RelationshipEvent, Relationships, Subject},
+    locale::Locale,
+};
+
+/// Applies all `RelationshipEvent`s to the `Relationships` of their characters.
+pub struct RelationshipSystem {
+    pub reader: Option<ReaderId<RelationshipEvent>>,
+}
+
+impl<'s> System<'s> for RelationshipSystem {
+    type SystemData = (
+        WriteStorage<'s, Relationships>,
+        Read<'s, EventChannel<RelationshipEvent>>,
+    );
+
+    fn run(&mut self, (mut relationships, event_channel): Self::SystemData) {
+        for event in event_channel.read(self.reader.as_mut().unwrap()) {
+            if let Some(relationship) = relationships.get_mut(event.character) {
+                relationship.adjust(&event.subject, &event.change);
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<RelationshipEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Shows how a character standing next to the player regards them in the `Hud`.
+pub struct RelationshipHudSystem;
+
+impl<'s> System<'s> for RelationshipHudSystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        ReadExpect<'s, Hud>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, Relationships>,
+        Read<'s, Locale>,
+    );
+
+    fn run(
+        &mut self,
+        (mut ui_texts, hud, positions, characters, names, relationships, locale): Self::SystemData,
+    ) {
+        let player_position = (&positions, &characters)
+            .join()
+            .map(|(position, _)| position)
+            .next();
+
+        let text = player_position
+            .and_then(|target| {
+                (&positions, &names, &relationships)
+                    .join()
+                    .find(|(position, _, _)| position.is_adjacent_to(target))
+            })
+            .map(|(_, name, relationship)| {
+                format_attitude(
+                    locale.character_name(&name.name),
+                    &relationship.toward(&Subject::Player),
+                )
+            })
+            .unwrap_or_default();
+
+        if let Some(ui_text) = ui_texts.get_mut(hud.relationship) {
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+    }
+}
+
+fn format_attitude(name: &str, attitude: &Attitude) -> String {
+    format!(
+        "{}  trust {:+}  favor {:+}  alignment {:+}",
+        name, attitude.trust, attitude.favor, attitude.alignment
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attitudes_are_formatted_with_signs() {
+        let attitude = Attitude {
+            trust: 10,
+            favor: -5,
+            alignment: 0,
+        };
+
+        assert_eq!(
+            format_attitude("Rob", &attitude),
+            "Rob  trust +10  favor -5  alignment +0"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use amethyst::{
+    ecs::prelude::{Join, Read, ReadStorage, Resources, System, SystemData, Write},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    game::{Position, TurnClock},
+    replay::{get_position_checksum, RecordedAction, Recording, ReplayOutcome},
+};
+
+use super::movement::PlayerActionEvent;
+
+use std::time::Instant;
+
+/// Appends every player action to the `Recording` along with when it was taken.
+pub struct InputRecorderSystem {
+    reader: Option<ReaderId<PlayerActionEvent>>,
+    frame: u64,
+    start: Instant,
+}
+
+impl Default for InputRecorderSystem {
+    fn default() -> Self {
+        InputRecorderSystem {
+            reader: None,
+            frame: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl<'s> System<'s> for InputRecorderSystem {
+    type SystemData = (
+        Write<'s, Recording>,
+        Read<'s, TurnClock>,
+        Read<'s, EventChannel<PlayerActionEvent>>,
+    );
+
+    fn run(&mut self, (mut recording, clock, event_channel): Self::SystemData) {
+        let elapsed = self.start.elapsed();
+        let elapsed_millis = elapsed.as_secs() * 1000 + u64::from(elapsed.subsec_millis());
+
+        for PlayerActionEvent(action) in event_channel.read(self.reader.as_mut().unwrap()) {
+            recording.actions.push(RecordedAction {
+                frame: self.frame,
+                turn: clock.turn,
+                elapsed_millis,
+                action: action.clone(),
+            });
+        }
+
+        self.frame += 1;
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<PlayerActionEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Sends the player actions of a `Recording` on the frames that they were recorded,
+/// in place of the `InputSystem`.
+///
+/// Once all actions have been sent the entity positions are compared to the checksum
+/// of the recording and the result is set in the `ReplayOutcome` resource.
+pub struct ReplaySystem {
+    recording: Recording,
+    index: usize,
+    frame: u64,
+}
+
+impl ReplaySystem {
+    pub fn new(recording: Recording) -> Self {
+        ReplaySystem {
+            recording,
+            index: 0,
+            frame: 0,
+        }
+    }
+}
+
+impl<'s> System<'s> for ReplaySystem {
+    type SystemData = (
+        Write<'s, EventChannel<PlayerActionEvent>>,
+        Write<'s, ReplayOutcome>,
+        ReadStorage<'s, Position>,
+    );
+
+    fn run(&mut self, (mut event_channel, mut outcome, positions): Self::SystemData) {
+        let actions = &self.recording.actions;
+
+        if self.index >= actions.len() {
+            // Entities were moved by the final action during the previous frame
+            if outcome.0.is_none() {
+                let checksum = get_position_checksum((&positions).join());
+                let matches = self
+                    .recording
+                    .checksum
+                    .map(|recorded| recorded == checksum)
+                    .unwrap_or(false);
+
+                if matches {
+                    log::info!("replay finished with matching checksum {}", checksum);
+                } else {
+                    log::warn!(
+                        "replay diverged from the recording: checksum {} but expected {:?}",
+                        checksum,
+                        self.recording.checksum
+                    );
+                }
+
+                outcome.0 = Some(matches);
+            }
+
+            return;
+        }
+
+        let frame = self.frame;
+
+        while let Some(recorded) = actions.get(self.index).filter(|a| a.frame <= frame) {
+            event_channel.single_write(PlayerActionEvent(recorded.action.clone()));
+            self.index += 1;
+        }
+
+        self.frame += 1;
+    }
+}
@@ -0,0 +1,313 @@
+use amethyst::{
+    assets::{Handle, Prefab, PrefabLoader},
+    core::Named,
+    ecs::prelude::{
+        Entities, Entity, Join, Read, ReadExpect, ReadStorage, Resources, System, SystemData,
+        Write, WriteExpect, WriteStorage,
+    },
+    renderer::Camera,
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::game::{
+    init_faction_members, load_area_map, ActiveArea, ActiveAreaMap, Area, AreaMapPrefab,
+    AreaTrigger, CampaignEvent, FactionMember, Factions, FiredTriggers, GameFlags,
+    ItemTransferEvent, MessageLog, PlayerCharacter, Position, Relationships, ScriptAction,
+};
+
+use super::{
+    movement::{
+        update_transforms::UpdateTransformsEvent, Action, PlayerActionEvent, PlayerMovedEvent,
+        SnapTweensEvent,
+    },
+    talk::TalkEvent,
+};
+
+#[derive(Clone, Debug)]
+/// Event emitted to leave the current area for another area map.
+pub struct AreaChangeEvent {
+    /// Path to the area map, relative to the assets directory.
+    pub map: String,
+    /// Where the player character is placed in the new area.
+    pub position: Position,
+}
+
+/// Fires the `AreaTrigger`s of the current area when the player character moves
+/// into or out of them or presses the action key inside of them, and runs their
+/// scripted actions. Fired triggers are kept in `FiredTriggers`.
+#[derive(Default)]
+pub struct AreaTriggerSystem {
+    moved_reader: Option<ReaderId<PlayerMovedEvent>>,
+    action_reader: Option<ReaderId<PlayerActionEvent>>,
+}
+
+impl<'s> System<'s> for AreaTriggerSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, AreaTrigger>,
+        WriteStorage<'s, Position>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, PlayerCharacter>,
+        Write<'s, MessageLog>,
+        Write<'s, GameFlags>,
+        Write<'s, FiredTriggers>,
+        ReadExpect<'s, ActiveAreaMap>,
+        Write<'s, EventChannel<TalkEvent>>,
+        Write<'s, EventChannel<AreaChangeEvent>>,
+        Write<'s, EventChannel<CampaignEvent>>,
+        Write<'s, EventChannel<ItemTransferEvent>>,
+        Write<'s, EventChannel<UpdateTransformsEvent>>,
+        Write<'s, EventChannel<SnapTweensEvent>>,
+        Read<'s, EventChannel<PlayerMovedEvent>>,
+        Read<'s, EventChannel<PlayerActionEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut triggers,
+            mut positions,
+            names,
+            characters,
+            mut message_log,
+            mut flags,
+            mut fired_triggers,
+            current_area_map,
+            mut talk_events,
+            mut area_change_events,
+            mut campaign_events,
+            mut item_transfer_events,
+            mut transforms_events,
+            mut snap_events,
+            moved_events,
+            action_events,
+        ) = data;
+
+        let mut actions = Vec::new();
+
+        for PlayerMovedEvent { from, to } in moved_events.read(self.moved_reader.as_mut().unwrap())
+        {
+            for trigger in (&mut triggers).join() {
+                if trigger.fires_on_move(from, to, &flags) {
+                    fired_triggers.insert(&current_area_map.0, trigger);
+                    actions.extend(trigger.fire());
+                }
+            }
+        }
+
+        for event in action_events.read(self.action_reader.as_mut().unwrap()) {
+            if let PlayerActionEvent(Action::Action) = event {
+                let player_position = (&positions, &characters)
+                    .join()
+                    .map(|(position, _)| position.clone())
+                    .next();
+
+                if let Some(position) = player_position {
+                    for trigger in (&mut triggers).join() {
+                        if trigger.fires_on_interact(&position, &flags) {
+                            fired_triggers.insert(&current_area_map.0, trigger);
+                            actions.extend(trigger.fire());
+                        }
+                    }
+                }
+            }
+        }
+
+        let find_character = |name: &str| -> Option<Entity> {
+            (&entities, &names)
+                .join()
+                .find(|(_, named)| named.name == name)
+                .map(|(entity, _)| entity)
+        };
+
+        for action in actions {
+            match action {
+                ScriptAction::StartDialogue(name) => match find_character(&name) {
+                    Some(character) => talk_events.single_write(TalkEvent { character }),
+                    None => log::warn!("no character named '{}' to talk to", name),
+                },
+                ScriptAction::ShowMessage(message) => message_log.push(message),
+                ScriptAction::MoveCharacter { name, to } => {
+                    match find_character(&name).and_then(|entity| positions.get_mut(entity)) {
+                        Some(position) => {
+                            *position = to;
+                            transforms_events.single_write(UpdateTransformsEvent);
+                            snap_events.single_write(SnapTweensEvent);
+                        }
+                        None => log::warn!("no character named '{}' to move", name),
+                    }
+                }
+                ScriptAction::ChangeArea { map, position } => {
+                    area_change_events.single_write(AreaChangeEvent { map, position })
+                }
+                ScriptAction::SetFlag(name, value) => flags.set(name, value),
+                ScriptAction::Campaign(event) => campaign_events.single_write(event),
+                ScriptAction::GiveItem { to, item } => {
+                    let player = (&entities, &characters)
+                        .join()
+                        .map(|(entity, _)| entity)
+                        .next();
+
+                    match (player, find_character(&to)) {
+                        (Some(from), Some(to)) => {
+                            item_transfer_events.single_write(ItemTransferEvent { from, to, item })
+                        }
+                        _ => log::warn!("no character named '{}' to give an item to", to),
+                    }
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.moved_reader = Some(
+            res.fetch_mut::<EventChannel<PlayerMovedEvent>>()
+                .register_reader(),
+        );
+        self.action_reader = Some(
+            res.fetch_mut::<EventChannel<PlayerActionEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Replaces the current area with another area map on an `AreaChangeEvent`.
+///
+/// Everything in the current area except for the player character and camera is
+/// removed at once. The old `Area` stays active until the new one has loaded, then
+/// the player character is placed in the new area, its faction members are set up
+/// and its triggers which have fired before are marked as fired.
+#[derive(Default)]
+pub struct AreaChangeSystem {
+    reader: Option<ReaderId<AreaChangeEvent>>,
+    /// Entity of the area which is loading and the change which loads it.
+    loading: Option<(Entity, AreaChangeEvent)>,
+}
+
+impl<'s> System<'s> for AreaChangeSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteExpect<'s, ActiveArea>,
+        WriteExpect<'s, ActiveAreaMap>,
+        WriteStorage<'s, Position>,
+        WriteStorage<'s, Handle<Prefab<AreaMapPrefab>>>,
+        WriteStorage<'s, AreaTrigger>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, Relationships>,
+        ReadStorage<'s, Area>,
+        ReadStorage<'s, PlayerCharacter>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, FactionMember>,
+        ReadStorage<'s, Named>,
+        Read<'s, Factions>,
+        Read<'s, FiredTriggers>,
+        PrefabLoader<'s, AreaMapPrefab>,
+        Write<'s, MessageLog>,
+        Write<'s, EventChannel<UpdateTransformsEvent>>,
+        Write<'s, EventChannel<SnapTweensEvent>>,
+        Read<'s, EventChannel<AreaChangeEvent>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut current_area,
+            mut current_area_map,
+            mut positions,
+            mut handles,
+            mut triggers,
+            mut ui_texts,
+            mut relationships,
+            areas,
+            characters,
+            cameras,
+            members,
+            names,
+            factions,
+            fired_triggers,
+            loader,
+            mut message_log,
+            mut transforms_events,
+            mut snap_events,
+            area_change_events,
+        ) = data;
+
+        // Only the last change matters if several happen at once
+        if let Some(event) = area_change_events
+            .read(self.reader.as_mut().unwrap())
+            .last()
+        {
+            for (entity, _, _, _) in (&entities, &positions, !&characters, !&cameras).join() {
+                entities
+                    .delete(entity)
+                    .expect("could not remove an entity of the area");
+            }
+
+            for (entity, _) in (&entities, &triggers).join() {
+                entities
+                    .delete(entity)
+                    .expect("could not remove a trigger of the area");
+            }
+
+            if let Some((entity, _)) = self.loading.take() {
+                entities
+                    .delete(entity)
+                    .expect("could not remove the area which was loading");
+            }
+
+            let entity = entities.create();
+            handles
+                .insert(entity, load_area_map(&event.map, &loader, ()))
+                .expect("could not load the area");
+
+            self.loading = Some((entity, event.clone()));
+        }
+
+        let loaded = self
+            .loading
+            .as_ref()
+            .and_then(|(entity, event)| areas.get(*entity).map(|area| (*entity, event, area)));
+
+        if let Some((entity, event, area)) = loaded {
+            entities
+                .delete(current_area.0)
+                .expect("could not remove the previous area");
+            current_area.0 = entity;
+            current_area_map.0 = event.map.clone();
+
+            for (player_position, _) in (&mut positions, &characters).join() {
+                *player_position = event.position.clone();
+            }
+
+            init_faction_members(
+                &factions,
+                &members,
+                &names,
+                &mut ui_texts,
+                &mut relationships,
+            );
+
+            for trigger in (&mut triggers).join() {
+                if fired_triggers.contains(&event.map, trigger) {
+                    trigger.set_fired();
+                }
+            }
+
+            message_log.push(format!("Entered {}", area.name));
+            transforms_events.single_write(UpdateTransformsEvent);
+            snap_events.single_write(SnapTweensEvent);
+
+            self.loading = None;
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<AreaChangeEvent>>()
+                .register_reader(),
+        );
+    }
+}
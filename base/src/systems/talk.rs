@@ -0,0 +1,68 @@
+use amethyst::{
+    core::Named,
+    ecs::prelude::{
+        Entities, Entity, Join, Read, ReadStorage, Resources, System, SystemData, Write,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::game::{PlayerCharacter, Position};
+
+use super::movement::{Action, PlayerActionEvent};
+
+#[derive(Clone, Debug)]
+/// Event emitted when the player starts talking to a character.
+pub struct TalkEvent {
+    pub character: Entity,
+}
+
+/// Starts talking to a named character next to the player when the action key is pressed.
+pub struct TalkSystem {
+    pub reader: Option<ReaderId<PlayerActionEvent>>,
+}
+
+impl<'s> System<'s> for TalkSystem {
+    type SystemData = (
+        Entities<'s>,
+        Write<'s, EventChannel<TalkEvent>>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, PlayerCharacter>,
+        Read<'s, EventChannel<PlayerActionEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut talk_events, positions, names, characters, event_channel): Self::SystemData,
+    ) {
+        for event in event_channel.read(self.reader.as_mut().unwrap()) {
+            if let PlayerActionEvent(Action::Action) = event {
+                let player_position = (&positions, &characters)
+                    .join()
+                    .map(|(position, _)| position)
+                    .next();
+
+                let character = player_position.and_then(|target| {
+                    (&entities, &positions, &names)
+                        .join()
+                        .find(|(_, position, name)| {
+                            !name.name.is_empty() && position.is_adjacent_to(target)
+                        })
+                        .map(|(entity, _, _)| entity)
+                });
+
+                if let Some(character) = character {
+                    talk_events.single_write(TalkEvent { character });
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<PlayerActionEvent>>()
+                .register_reader(),
+        );
+    }
+}
@@ -0,0 +1,776 @@
+use amethyst::{
+    assets::Prefab,
+    input::{Bindings, StringBindings},
+    ui::UiWidget,
+    window::DisplayConfig,
+};
+
+use serde::de::DeserializeOwned;
+
+use std::{
+    collections::{HashMap, HashSet},
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{
+    config::Config,
+    game::{
+        consts::START_AREA_MAP, AreaMap, AreaMapPrefab, CampaignData, CharacterPrefab, District,
+        FactionsData, ObjectivesData, Position, TiledMap,
+    },
+    locale::StringTable,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Something wrong with an asset or resource file.
+pub struct Problem {
+    pub path: PathBuf,
+    /// Line in the file (starting from 1), if known.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Kind of entity which is placed in an area.
+pub enum PlacementKind {
+    /// Characters block movement.
+    Character {
+        name: Option<String>,
+    },
+    Item,
+    /// Walls and props of area maps.
+    Tile {
+        collision: bool,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An entity which is placed at a position in an area by an area map or prefab.
+pub struct Placement {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    /// Describes the entity in messages.
+    pub description: String,
+    pub glyph: char,
+    pub position: Position,
+    pub kind: PlacementKind,
+}
+
+impl Placement {
+    fn problem(&self, message: String) -> Problem {
+        Problem {
+            path: self.path.clone(),
+            line: self.line,
+            message,
+        }
+    }
+
+    fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.path.display(), line),
+            None => format!("{}", self.path.display()),
+        }
+    }
+}
+
+/// Run a check of the files below the application root from the command line, for
+/// the binaries which check assets without starting the game.
+///
+/// The application root is the first argument, or the current directory if there
+/// is none. All problems are printed and the process exits with an error if there
+/// are any, otherwise the success message is printed.
+pub fn run_check(check: fn(&Path) -> Vec<Problem>, success: &str) {
+    let app_root = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .or_else(|| env::current_dir().ok())
+        .expect("could not get the current directory");
+
+    let problems = check(&app_root);
+
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+
+    if problems.is_empty() {
+        println!("{} ({})", success, app_root.display());
+    } else {
+        eprintln!("Found {} problem(s)", problems.len());
+        process::exit(1);
+    }
+}
+
+/// Read all prefab, data, UI and resource files which the game loads below the
+/// application root and check them for problems.
+///
+/// Every file is parsed as the type that the game reads it as. Entities which are
+/// placed by area maps and prefabs are checked against the area dimensions, against
+/// each other and against the defined factions. Prefabs are placed in the area
+/// that the game starts in. Districts are generated from their seeds and checked
+/// in the same way.
+pub fn validate_assets(app_root: &Path) -> Vec<Problem> {
+    let assets = app_root.join("assets");
+    let resources = app_root.join("resources");
+
+    let mut problems = Vec::new();
+
+    read_file::<Config>(&resources.join("config.ron"), &mut problems);
+    read_file::<Bindings<StringBindings>>(&resources.join("bindings_config.ron"), &mut problems);
+    read_file::<DisplayConfig>(&resources.join("display_config.ron"), &mut problems);
+
+    read_file::<CampaignData>(&assets.join("campaign").join("campaign.ron"), &mut problems);
+    read_file::<ObjectivesData>(
+        &assets.join("objectives").join("objectives.ron"),
+        &mut problems,
+    );
+    let factions =
+        read_file::<FactionsData>(&assets.join("factions").join("factions.ron"), &mut problems)
+            .map(|(_, factions)| factions);
+
+    for path in list_files(&assets.join("ui"), &["ron"], &mut problems) {
+        read_file::<UiWidget>(&path, &mut problems);
+    }
+
+    for path in list_files(&assets.join("locale"), &["ron"], &mut problems) {
+        read_file::<StringTable>(&path, &mut problems);
+    }
+
+    let mut prefab_placements = Vec::new();
+
+    // Prefabs hold characters, as in `Loading` and the developer console
+    for path in list_files(&assets.join("prefab"), &["ron"], &mut problems) {
+        if let Some((source, prefab)) = read_file::<Prefab<CharacterPrefab>>(&path, &mut problems) {
+            let characters = get_data_with_lines(&source, &prefab);
+
+            if let Some(factions) = factions.as_ref() {
+                problems.extend(check_character_factions(&path, &characters, factions));
+            }
+
+            prefab_placements.extend(get_character_placements(&path, &characters));
+        }
+    }
+
+    let start_area = assets.join(START_AREA_MAP);
+
+    if !start_area.exists() {
+        problems.push(Problem {
+            path: start_area.clone(),
+            line: None,
+            message: "the area that the game starts in does not exist".to_string(),
+        });
+    }
+
+    for path in list_files(&assets.join("area"), &["ron", "json"], &mut problems) {
+        let placements = if path == start_area {
+            prefab_placements.as_slice()
+        } else {
+            &[]
+        };
+
+        if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+            if let Some(tiled_map) = read_json_file::<TiledMap>(&path, &mut problems) {
+                problems.extend(check_tiled_map(
+                    &path,
+                    &tiled_map,
+                    factions.as_ref(),
+                    placements,
+                ));
+            }
+        } else if let Some((source, area_map)) = read_file::<AreaMap>(&path, &mut problems) {
+            problems.extend(check_area_map(
+                &path,
+                &source,
+                &area_map,
+                factions.as_ref(),
+                placements,
+            ));
+        }
+    }
+
+    for path in list_files(&assets.join("district"), &["ron"], &mut problems) {
+        if let Some((_, district)) = read_file::<District>(&path, &mut problems) {
+            problems.extend(check_district(&path, &district, factions.as_ref()));
+        }
+    }
+
+    problems
+}
+
+/// Check that all glyphs of an area map are in its legend and that everything
+/// in it can be placed along with the other placements.
+fn check_area_map(
+    path: &Path,
+    source: &str,
+    area_map: &AreaMap,
+    factions: Option<&FactionsData>,
+    placements: &[Placement],
+) -> Vec<Problem> {
+    let first_row_line = get_map_first_line(source);
+
+    let data = match area_map.get_prefab_data() {
+        Ok(data) => data,
+        Err(err) => {
+            return vec![Problem {
+                path: path.to_path_buf(),
+                line: first_row_line.map(|line| line + err.row),
+                message: err.to_string(),
+            }];
+        }
+    };
+
+    let [_, ny] = area_map.dimensions();
+    let get_line =
+        |position: &Position| first_row_line.map(|line| line + (ny - 1 - position.y) as usize);
+
+    check_area_data(
+        path,
+        &data,
+        &area_map.dimensions(),
+        get_line,
+        factions,
+        placements,
+    )
+}
+
+/// Check that a map made in Tiled can be read and that everything in it can be
+/// placed along with the other placements. Positions in JSON files have no lines.
+fn check_tiled_map(
+    path: &Path,
+    tiled_map: &TiledMap,
+    factions: Option<&FactionsData>,
+    placements: &[Placement],
+) -> Vec<Problem> {
+    match tiled_map.get_prefab_data() {
+        Ok(data) => check_area_data(
+            path,
+            &data,
+            &[tiled_map.width, tiled_map.height],
+            |_| None,
+            factions,
+            placements,
+        ),
+        Err(message) => vec![Problem {
+            path: path.to_path_buf(),
+            line: None,
+            message,
+        }],
+    }
+}
+
+/// Check that a district can be generated and that everything in it can be
+/// placed. Generated entities have no lines.
+fn check_district(
+    path: &Path,
+    district: &District,
+    factions: Option<&FactionsData>,
+) -> Vec<Problem> {
+    match district.get_prefab_data() {
+        Ok(data) => check_area_data(path, &data, &district.dimensions(), |_| None, factions, &[]),
+        Err(message) => vec![Problem {
+            path: path.to_path_buf(),
+            line: None,
+            message,
+        }],
+    }
+}
+
+/// Check the factions of all characters of an area, that all entities in it
+/// can be placed along with the other placements and that its triggers are inside of it.
+fn check_area_data<F: Fn(&Position) -> Option<usize>>(
+    path: &Path,
+    data: &[AreaMapPrefab],
+    dimensions: &[u32; 2],
+    get_line: F,
+    factions: Option<&FactionsData>,
+    placements: &[Placement],
+) -> Vec<Problem> {
+    let characters = data
+        .iter()
+        .filter_map(|entity| match entity {
+            AreaMapPrefab::Character(character) => {
+                Some((get_line(&character.position()), character))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut problems = factions
+        .map(|factions| check_character_factions(path, &characters, factions))
+        .unwrap_or_default();
+
+    let mut all_placements = get_character_placements(path, &characters);
+
+    all_placements.extend(data.iter().filter_map(|entity| match entity {
+        AreaMapPrefab::Tile {
+            glyph,
+            position,
+            collision,
+        } => Some(Placement {
+            path: path.to_path_buf(),
+            line: get_line(position),
+            description: format!("{} '{}'", if *collision { "wall" } else { "prop" }, glyph),
+            glyph: *glyph,
+            position: position.clone(),
+            kind: PlacementKind::Tile {
+                collision: *collision,
+            },
+        }),
+        AreaMapPrefab::Item {
+            glyph,
+            position,
+            item,
+        } => Some(Placement {
+            path: path.to_path_buf(),
+            line: get_line(position),
+            description: format!("item '{}'", item),
+            glyph: *glyph,
+            position: position.clone(),
+            kind: PlacementKind::Item,
+        }),
+        _ => None,
+    }));
+
+    all_placements.extend(placements.iter().cloned());
+
+    problems.extend(check_placements(dimensions, &all_placements));
+
+    let [nx, ny] = *dimensions;
+
+    for entity in data {
+        if let AreaMapPrefab::Trigger(trigger) = entity {
+            let tiles = trigger.region.tiles();
+
+            if tiles.is_empty() || tiles.iter().any(|tile| tile.x >= nx || tile.y >= ny) {
+                problems.push(Problem {
+                    path: path.to_path_buf(),
+                    line: None,
+                    message: format!(
+                        "{:?} trigger covers no tiles or tiles outside of the area, which is {} by {} tiles",
+                        trigger.on, nx, ny
+                    ),
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+/// Read and parse a RON file, adding a problem if it fails. Returns the source
+/// along with the parsed value.
+pub(crate) fn read_file<T: DeserializeOwned>(
+    path: &Path,
+    problems: &mut Vec<Problem>,
+) -> Option<(String, T)> {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            problems.push(Problem {
+                path: path.to_path_buf(),
+                line: None,
+                message: format!("could not read file ({})", err),
+            });
+
+            return None;
+        }
+    };
+
+    match ron::de::from_str::<T>(&source) {
+        Ok(value) => Some((source, value)),
+        Err(err) => {
+            problems.push(Problem {
+                path: path.to_path_buf(),
+                line: get_error_line(&source, &err),
+                message: err.to_string(),
+            });
+
+            None
+        }
+    }
+}
+
+/// Read and parse a JSON file, adding a problem if it fails.
+pub(crate) fn read_json_file<T: DeserializeOwned>(
+    path: &Path,
+    problems: &mut Vec<Problem>,
+) -> Option<T> {
+    let result = fs::read(path)
+        .map_err(|err| (None, format!("could not read file ({})", err)))
+        .and_then(|bytes| {
+            serde_json::from_slice(&bytes).map_err(|err| (Some(err.line()), err.to_string()))
+        });
+
+    match result {
+        Ok(value) => Some(value),
+        Err((line, message)) => {
+            problems.push(Problem {
+                path: path.to_path_buf(),
+                line,
+                message,
+            });
+
+            None
+        }
+    }
+}
+
+/// List the files in a directory with one of the given extensions.
+pub(crate) fn list_files(
+    directory: &Path,
+    extensions: &[&str],
+    problems: &mut Vec<Problem>,
+) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            problems.push(Problem {
+                path: directory.to_path_buf(),
+                line: None,
+                message: format!("could not read directory ({})", err),
+            });
+
+            return Vec::new();
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| extensions.contains(&ext))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    paths
+}
+
+/// Get the line of a parsing error.
+///
+/// Errors from deserializing the parsed values, such as unknown fields, do not come
+/// with a position. For those the line of the first field or variant name that the
+/// message refers to is used, if it can be found.
+pub fn get_error_line(source: &str, error: &ron::de::Error) -> Option<usize> {
+    match error {
+        ron::de::Error::Parser(_, position) => Some(position.line),
+        ron::de::Error::Message(message) => {
+            let name = message.split('`').nth(1)?;
+
+            get_code_lines(source)
+                .find(|(_, line)| {
+                    line.starts_with(name)
+                        && line[name.len()..]
+                            .trim_start()
+                            .starts_with(|c| c == ':' || c == '(')
+                })
+                .map(|(number, _)| number)
+        }
+        _ => None,
+    }
+}
+
+/// Get the lines of a prefab file at which the `data` of its entities starts,
+/// in order, also when an entity is written on a single line as `(data: ...)`.
+/// Entities without data are not included.
+pub fn get_data_lines(source: &str) -> Vec<usize> {
+    get_code_lines(source)
+        .filter(|(_, line)| {
+            line.trim_start_matches('(')
+                .trim_start()
+                .starts_with("data:")
+        })
+        .map(|(number, _)| number)
+        .collect()
+}
+
+/// Get the line of the first row of the map in an area map file.
+pub fn get_map_first_line(source: &str) -> Option<usize> {
+    get_code_lines(source)
+        .find(|(_, line)| line.starts_with("map:"))
+        .map(|(number, line)| {
+            // The map starts on the next line if nothing but the quote follows the key
+            if line["map:".len()..].trim() == "\"" {
+                number + 1
+            } else {
+                number
+            }
+        })
+}
+
+/// Iterate over the trimmed lines of a file along with their numbers (starting
+/// from 1), skipping comments.
+pub(crate) fn get_code_lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.starts_with("//"))
+}
+
+pub(crate) fn get_data_with_lines<'a, T>(
+    source: &str,
+    prefab: &'a Prefab<T>,
+) -> Vec<(Option<usize>, &'a T)> {
+    let mut lines = get_data_lines(source).into_iter();
+
+    prefab
+        .entities()
+        .filter_map(|entity| entity.data())
+        .map(|data| (lines.next(), data))
+        .collect()
+}
+
+fn get_character_placements(
+    path: &Path,
+    characters: &[(Option<usize>, &CharacterPrefab)],
+) -> Vec<Placement> {
+    characters
+        .iter()
+        .map(|(line, character)| Placement {
+            path: path.to_path_buf(),
+            line: *line,
+            description: character
+                .name()
+                .map(|name| format!("character '{}'", name))
+                .unwrap_or_else(|| "player character".to_string()),
+            glyph: character.glyph(),
+            position: character.position(),
+            kind: PlacementKind::Character {
+                name: character.name().map(str::to_string),
+            },
+        })
+        .collect()
+}
+
+fn check_character_factions(
+    path: &Path,
+    characters: &[(Option<usize>, &CharacterPrefab)],
+    factions: &FactionsData,
+) -> Vec<Problem> {
+    let ids = factions
+        .factions
+        .iter()
+        .map(|faction| faction.id.as_str())
+        .collect::<HashSet<_>>();
+
+    characters
+        .iter()
+        .filter_map(|(line, character)| {
+            character
+                .faction()
+                .filter(|id| !ids.contains(id))
+                .map(|id| Problem {
+                    path: path.to_path_buf(),
+                    line: *line,
+                    message: format!("faction '{}' is not defined", id),
+                })
+        })
+        .collect()
+}
+
+/// Check that all placed entities are inside of the area, that no two entities
+/// which block movement are at the same position and that no two characters share
+/// both glyph and name.
+pub fn check_placements(dimensions: &[u32; 2], placements: &[Placement]) -> Vec<Problem> {
+    let [nx, ny] = *dimensions;
+
+    let mut problems = Vec::new();
+    let mut blocked_positions: HashMap<&Position, &Placement> = HashMap::new();
+    let mut characters_by_label: HashMap<(char, Option<&str>), &Placement> = HashMap::new();
+
+    for placement in placements {
+        let Position { x, y } = placement.position;
+
+        if x >= nx || y >= ny {
+            problems.push(placement.problem(format!(
+                "{} at ({}, {}) is outside of the area, which is {} by {} tiles",
+                placement.description, x, y, nx, ny
+            )));
+        }
+
+        let has_collision = match placement.kind {
+            PlacementKind::Character { .. } => true,
+            PlacementKind::Tile { collision } => collision,
+            PlacementKind::Item => false,
+        };
+
+        if has_collision {
+            if let Some(other) = blocked_positions.get(&placement.position) {
+                problems.push(placement.problem(format!(
+                    "{} at ({}, {}) is at the same position as {} ({})",
+                    placement.description,
+                    x,
+                    y,
+                    other.description,
+                    other.location()
+                )));
+            } else {
+                blocked_positions.insert(&placement.position, placement);
+            }
+        }
+
+        if let PlacementKind::Character { ref name } = placement.kind {
+            let label = (placement.glyph, name.as_ref().map(String::as_str));
+
+            if let Some(other) = characters_by_label.get(&label) {
+                problems.push(placement.problem(format!(
+                    "{} has the same glyph '{}' and name as {} ({})",
+                    placement.description,
+                    placement.glyph,
+                    other.description,
+                    other.location()
+                )));
+            } else {
+                characters_by_label.insert(label, placement);
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_character(name: &str, glyph: char, x: u32, y: u32) -> Placement {
+        Placement {
+            path: PathBuf::from("character.ron"),
+            line: Some(x as usize),
+            description: format!("character '{}'", name),
+            glyph,
+            position: Position { x, y },
+            kind: PlacementKind::Character {
+                name: Some(name.to_string()),
+            },
+        }
+    }
+
+    fn get_item(x: u32, y: u32) -> Placement {
+        Placement {
+            path: PathBuf::from("item.ron"),
+            line: None,
+            description: "item '$10'".to_string(),
+            glyph: '$',
+            position: Position { x, y },
+            kind: PlacementKind::Item,
+        }
+    }
+
+    #[test]
+    fn placements_inside_of_the_area_are_fine() {
+        let placements = vec![
+            get_character("Austin", 'a', 0, 0),
+            get_character("Cado", 'c', 9, 4),
+            get_item(0, 0),
+        ];
+
+        assert!(check_placements(&[10, 5], &placements).is_empty());
+    }
+
+    #[test]
+    fn placements_outside_of_the_area_are_reported() {
+        let placements = vec![get_character("Austin", 'a', 10, 0), get_item(0, 5)];
+        let problems = check_placements(&[10, 5], &placements);
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(
+            problems[0].to_string(),
+            "character.ron:10: character 'Austin' at (10, 0) is outside of the area, which is 10 by 5 tiles"
+        );
+        assert_eq!(problems[1].line, None);
+    }
+
+    #[test]
+    fn characters_at_the_same_position_are_reported() {
+        let placements = vec![
+            get_character("Austin", 'a', 2, 3),
+            get_character("Cado", 'c', 2, 3),
+        ];
+        let problems = check_placements(&[10, 5], &placements);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            problems[0].message,
+            "character 'Cado' at (2, 3) is at the same position as character 'Austin' (character.ron:2)"
+        );
+    }
+
+    #[test]
+    fn characters_with_the_same_glyph_and_name_are_reported() {
+        let placements = vec![
+            get_character("Austin", 'a', 1, 0),
+            get_character("Austin", 'A', 2, 0),
+            get_character("Austin", 'a', 3, 0),
+        ];
+        let problems = check_placements(&[10, 5], &placements);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(3));
+    }
+
+    #[test]
+    fn characters_on_walls_are_reported_but_items_on_walls_are_not() {
+        let wall = Placement {
+            path: PathBuf::from("cityhall.ron"),
+            line: Some(20),
+            description: "wall '#'".to_string(),
+            glyph: '#',
+            position: Position { x: 0, y: 0 },
+            kind: PlacementKind::Tile { collision: true },
+        };
+
+        let placements = vec![wall, get_item(0, 0), get_character("Austin", 'a', 0, 0)];
+        let problems = check_placements(&[10, 5], &placements);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            problems[0].message,
+            "character 'Austin' at (0, 0) is at the same position as wall '#' (cityhall.ron:20)"
+        );
+    }
+
+    #[test]
+    fn map_starts_on_the_line_after_the_quote() {
+        assert_eq!(get_map_first_line("(\n    map: \"\n###\n\",\n)"), Some(3));
+        assert_eq!(get_map_first_line("(\n    map: \"###\n\",\n)"), Some(2));
+        assert_eq!(get_map_first_line("(\n    name: \"Lobby\",\n)"), None);
+    }
+
+    #[test]
+    fn data_lines_are_found_in_order_skipping_comments() {
+        let source = "(\n    entities: [\n        // data: skipped\n        (\n            data: A(),\n        ),\n        (data: B()),\n    ],\n)";
+
+        assert_eq!(get_data_lines(source), vec![5, 7]);
+    }
+
+    #[test]
+    fn parser_errors_have_their_line() {
+        let source = "(\n    x: 1,\n    y: ,\n)";
+        let err = ron::de::from_str::<Position>(source).unwrap_err();
+
+        assert_eq!(get_error_line(source, &err), Some(3));
+    }
+
+    #[test]
+    fn unknown_field_errors_have_the_line_of_the_field() {
+        let source = "(\n    x: 1,\n    y: 2,\n    z: 3,\n)";
+        let err = ron::de::from_str::<Position>(source).unwrap_err();
+
+        assert_eq!(get_error_line(source, &err), Some(4));
+    }
+}